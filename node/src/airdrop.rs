@@ -0,0 +1,163 @@
+//! The `airdrop-tree` subcommand: the Merkle tree of an airdrop and the proofs of its claims, for
+//! `create_airdrop` and `claim_airdrop` of the ERC20 assets pallet.
+//!
+//! The recipients are read from a CSV file of `<address>, <amount>` rows, whose order gives the
+//! leaf indices. Lines starting with `#` are ignored. Leaves encode amounts as the balance type of
+//! the registry the airdrop is created in, so the tree only holds for that registry.
+
+use crate::export::parse_registry;
+use node_template_runtime::{
+	pallet_template::{airdrop_leaf, MerkleHash, MerkleTree},
+	AccountId, AssetBalance, AssetRegistry, StableBalance,
+};
+use serde::Serialize;
+use sp_core::{crypto::Ss58Codec, H256};
+use sp_runtime::traits::{CheckedAdd, Zero};
+use std::{convert::TryFrom, fs::File, io, path::PathBuf};
+use structopt::StructOpt;
+
+/// The `airdrop-tree` command.
+#[derive(Debug, StructOpt)]
+pub struct AirdropTreeCmd {
+	/// The CSV file of recipients and their amounts.
+	#[structopt(parse(from_os_str))]
+	pub input: PathBuf,
+
+	/// The file to write the tree to. Defaults to stdout.
+	#[structopt(long, short, parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	/// The registry of the airdropped asset, `community` or `stable`.
+	#[structopt(long, default_value = "community", parse(try_from_str = parse_registry))]
+	pub registry: AssetRegistry,
+}
+
+/// A claim of an airdrop and its proof.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AirdropClaim {
+	/// The index of the claim's leaf.
+	pub index: u32,
+	/// The recipient.
+	pub account: AccountId,
+	/// The amount it may claim.
+	pub amount: AssetBalance,
+	/// The proof to claim it with.
+	pub proof: Vec<H256>,
+}
+
+/// The tree of an airdrop, summed up.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AirdropTree {
+	/// The root to create the airdrop with.
+	pub merkle_root: H256,
+	/// The number of recipients to create the airdrop with.
+	pub recipients: u32,
+	/// The budget the claims add up to.
+	pub total: AssetBalance,
+	/// The claims, in leaf order.
+	pub claims: Vec<AirdropClaim>,
+}
+
+impl AirdropTreeCmd {
+	/// Run the command.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let file = File::open(&self.input).map_err(|e| {
+			format!("Error opening airdrop recipients {}: {}", self.input.display(), e)
+		})?;
+		let recipients = read_recipients(file).map_err(|e| {
+			format!("Error parsing airdrop recipients {}: {}", self.input.display(), e)
+		})?;
+		let tree = build_tree(self.registry, recipients)?;
+
+		let output: Box<dyn io::Write> = match &self.output {
+			Some(path) => Box::new(File::create(path)?),
+			None => Box::new(io::stdout()),
+		};
+		serde_json::to_writer_pretty(output, &tree)
+			.map_err(|e| format!("Unable to write the airdrop tree: {}", e))?;
+		Ok(())
+	}
+}
+
+fn read_recipients(reader: impl io::Read) -> Result<Vec<(AccountId, AssetBalance)>, String> {
+	let mut reader = csv::ReaderBuilder::new()
+		.has_headers(false)
+		.comment(Some(b'#'))
+		.trim(csv::Trim::All)
+		.from_reader(reader);
+	let mut recipients = Vec::new();
+	for record in reader.records() {
+		let record = record.map_err(|e| e.to_string())?;
+		let line = record.position().map_or(0, |p| p.line());
+		if record.len() != 2 {
+			return Err(format!("line {}: expected 2 columns, found {}", line, record.len()))
+		}
+		let account = AccountId::from_ss58check(&record[0])
+			.map_err(|e| format!("line {}: invalid address: {:?}", line, e))?;
+		let amount =
+			record[1].parse().map_err(|e| format!("line {}: invalid amount: {}", line, e))?;
+		recipients.push((account, amount));
+	}
+	Ok(recipients)
+}
+
+/// The leaf entitling `who` to `amount`, encoded as a balance of `registry`.
+fn leaf(
+	registry: AssetRegistry,
+	index: u32,
+	who: &AccountId,
+	amount: AssetBalance,
+) -> Result<MerkleHash, String> {
+	Ok(match registry {
+		AssetRegistry::Community => airdrop_leaf(index, who, amount),
+		AssetRegistry::Stable => {
+			let amount = StableBalance::try_from(amount)
+				.map_err(|_| format!("claim {}: {} exceeds a stable balance", index, amount))?;
+			airdrop_leaf(index, who, amount)
+		},
+	})
+}
+
+fn build_tree(
+	registry: AssetRegistry,
+	recipients: Vec<(AccountId, AssetBalance)>,
+) -> Result<AirdropTree, String> {
+	if recipients.is_empty() {
+		return Err("An airdrop needs at least one recipient".into())
+	}
+	let count = u32::try_from(recipients.len()).map_err(|_| "Too many airdrop recipients")?;
+	let total = recipients
+		.iter()
+		.try_fold(AssetBalance::zero(), |total, (_, amount)| total.checked_add(amount))
+		.ok_or("The airdrop amounts overflow")?;
+	if registry == AssetRegistry::Stable && StableBalance::try_from(total).is_err() {
+		return Err("The airdrop amounts overflow a stable balance".into())
+	}
+
+	let tree = MerkleTree::new(
+		recipients
+			.iter()
+			.enumerate()
+			.map(|(index, (who, amount))| leaf(registry, index as u32, who, *amount))
+			.collect::<Result<_, _>>()?,
+	);
+	let merkle_root = tree.root().expect("the tree has leaves; qed").into();
+	let claims = recipients
+		.into_iter()
+		.enumerate()
+		.map(|(index, (account, amount))| AirdropClaim {
+			index: index as u32,
+			account,
+			amount,
+			proof: tree
+				.proof(index)
+				.expect("every recipient is a leaf; qed")
+				.into_iter()
+				.map(H256::from)
+				.collect(),
+		})
+		.collect();
+	Ok(AirdropTree { merkle_root, recipients: count, total, claims })
+}
@@ -1,4 +1,5 @@
 use sc_cli::RunCmd;
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -8,6 +9,16 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Index ERC20 transfers, mints, burns and approvals into a database next to the chain's and
+	/// serve them through the `erc20_transferHistory` RPC methods. Full nodes only.
+	#[structopt(long)]
+	pub erc20_indexer: bool,
+
+	/// A CSV or JSON token ledger of native balances, assets, asset metadata and asset balances
+	/// to seed the `dev`, `local` or `ledger` chain with. Required by the `ledger` chain.
+	#[structopt(long, parse(from_os_str))]
+	pub token_ledger: Option<PathBuf>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -26,6 +37,13 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export the holders, balances, approvals and metadata of ERC20 assets at a given block as
+	/// CSV or JSON.
+	ExportLedger(crate::export::ExportLedgerCmd),
+
+	/// Build the Merkle tree of an ERC20 airdrop and the proofs of its claims from a CSV file.
+	AirdropTree(crate::airdrop::AirdropTreeCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -38,4 +56,12 @@ pub enum Subcommand {
 	/// The custom benchmark subcommmand benchmarking runtime pallets.
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+	/// Try some command against runtime state.
+	#[cfg(feature = "try-runtime")]
+	TryRuntime(try_runtime_cli::TryRuntimeCmd),
+
+	/// Try some command against runtime state. Note: `try-runtime` feature must be enabled.
+	#[cfg(not(feature = "try-runtime"))]
+	TryRuntime,
 }
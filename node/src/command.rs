@@ -18,6 +18,7 @@
 use crate::{
 	chain_spec,
 	cli::{Cli, Subcommand},
+	ledger::TokenLedger,
 	service,
 };
 use node_template_runtime::Block;
@@ -50,9 +51,16 @@ impl SubstrateCli for Cli {
 	}
 
 	fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+		let ledger = self.token_ledger.as_deref().map(TokenLedger::load).transpose()?;
 		Ok(match id {
-			"dev" => Box::new(chain_spec::development_config()?),
-			"" | "local" => Box::new(chain_spec::local_testnet_config()?),
+			"dev" => Box::new(chain_spec::development_config(ledger.unwrap_or_default())?),
+			"" | "local" => Box::new(chain_spec::local_testnet_config(ledger.unwrap_or_default())?),
+			"ledger" => Box::new(chain_spec::ledger_testnet_config(
+				ledger.ok_or("The `ledger` chain needs a `--token-ledger`.")?,
+			)?),
+			_ if ledger.is_some() =>
+				return Err("`--token-ledger` only applies to the `dev`, `local` and `ledger` chains."
+					.into()),
 			path =>
 				Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
 		})
@@ -95,6 +103,14 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
+		Some(Subcommand::ExportLedger(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = service::new_partial(&config)?;
+				cmd.run(client)
+			})
+		},
+		Some(Subcommand::AirdropTree(cmd)) => cmd.run(),
 		Some(Subcommand::ImportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -125,12 +141,30 @@ pub fn run() -> sc_cli::Result<()> {
 				     `--features runtime-benchmarks`."
 					.into())
 			},
+		#[cfg(feature = "try-runtime")]
+		Some(Subcommand::TryRuntime(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				// We don't need any of the components of `new_partial`, just a runtime, or a task
+				// manager to do `async_run`.
+				let registry = config.prometheus_config.as_ref().map(|cfg| &cfg.registry);
+				let task_manager =
+					sc_service::TaskManager::new(config.task_executor.clone(), registry)
+						.map_err(|e| sc_cli::Error::Service(sc_service::Error::Prometheus(e)))?;
+				Ok((cmd.run::<Block, service::Executor>(config), task_manager))
+			})
+		},
+		#[cfg(not(feature = "try-runtime"))]
+		Some(Subcommand::TryRuntime) => Err("TryRuntime wasn't enabled when building the node. \
+				You can enable it with `--features try-runtime`."
+			.into()),
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let erc20_indexer = cli.erc20_indexer;
 			runner.run_node_until_exit(|config| async move {
 				match config.role {
 					Role::Light => service::new_light(config),
-					_ => service::new_full(config),
+					_ => service::new_full(config, erc20_indexer),
 				}
 				.map_err(sc_cli::Error::Service)
 			})
@@ -0,0 +1,473 @@
+//! The `export-ledger` subcommand: the holders, balances, freezes, approvals and metadata of ERC20
+//! assets of one registry at a block, read from the local database.
+
+use codec::Decode;
+use frame_support::traits::{PalletInfoAccess, StorageVersion};
+use node_template_runtime::{
+	opaque::Block,
+	pallet_template::{self, FreezeReason},
+	AccountId, AssetBalance, AssetId, AssetRegistry, Balance, BlockNumber, Hash, StableAssets,
+	StableBalance, TemplateModule,
+};
+use sc_cli::{BlockNumberOrHash, CliConfiguration, PruningParams, SharedParams};
+use sc_client_api::{Backend, StorageProvider};
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_core::{storage::StorageKey, twox_128};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Saturating, Zero},
+};
+use std::{collections::BTreeMap, fs::File, io, path::PathBuf, sync::Arc};
+use structopt::StructOpt;
+
+type AssetDetails<B> = pallet_template::AssetDetails<B, AccountId, Balance>;
+type AccountData<B> = pallet_template::AssetBalance<B, Balance, (), AccountId>;
+type Approval<B> = pallet_template::Approval<B, Balance>;
+type OldApproval<B> = pallet_template::migration::v4::OldApproval<B, Balance>;
+type Metadata = pallet_template::AssetMetadata<Balance, Vec<u8>>;
+
+/// The storage entries under a prefix, with the prefix stripped from their keys.
+type Entries<'a> = dyn Fn(&str) -> sc_cli::Result<Vec<(Vec<u8>, Vec<u8>)>> + 'a;
+
+pub(crate) fn parse_registry(s: &str) -> Result<AssetRegistry, String> {
+	match s {
+		"community" => Ok(AssetRegistry::Community),
+		"stable" => Ok(AssetRegistry::Stable),
+		_ => Err(format!("unknown registry `{}`, expected `community` or `stable`", s)),
+	}
+}
+
+/// The format `export-ledger` writes.
+#[derive(Debug, Clone, Copy)]
+pub enum LedgerFormat {
+	/// One row per asset, holder, freeze and approval.
+	Csv,
+	/// One object per asset, holding its holders and approvals.
+	Json,
+}
+
+impl std::str::FromStr for LedgerFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, String> {
+		match s {
+			"csv" => Ok(LedgerFormat::Csv),
+			"json" => Ok(LedgerFormat::Json),
+			_ => Err(format!("unknown format `{}`, expected `csv` or `json`", s)),
+		}
+	}
+}
+
+/// The `export-ledger` command.
+#[derive(Debug, StructOpt)]
+pub struct ExportLedgerCmd {
+	/// The block hash or number to export the ledger at. Defaults to the best block.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// The registry to export, `community` or `stable`.
+	#[structopt(long, default_value = "community", parse(try_from_str = parse_registry))]
+	pub registry: AssetRegistry,
+
+	/// Export only this asset.
+	#[structopt(long)]
+	pub asset: Option<AssetId>,
+
+	/// The output format, `csv` or `json`.
+	#[structopt(long, default_value = "json")]
+	pub format: LedgerFormat,
+
+	/// The file to write to. Defaults to stdout.
+	#[structopt(long, short, parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+}
+
+/// A holder of an asset.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HolderExport {
+	/// The holder.
+	pub account: AccountId,
+	/// Its balance.
+	pub balance: AssetBalance,
+	/// Whether the account is frozen.
+	pub is_frozen: bool,
+	/// The amounts frozen on it by `freeze_amount`.
+	pub freezes: Vec<FreezeExport>,
+}
+
+/// An amount of an asset frozen on a holder.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FreezeExport {
+	/// The code of the reason it is frozen for.
+	pub reason: FreezeReason,
+	/// The amount frozen.
+	pub amount: AssetBalance,
+}
+
+/// An approval to spend an asset.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalExport {
+	/// The account whose assets may be spent.
+	pub owner: AccountId,
+	/// The account that may spend them.
+	pub delegate: AccountId,
+	/// The amount it may spend.
+	pub amount: AssetBalance,
+	/// Whether it may spend any amount, in which case `amount` is not spent down.
+	pub unlimited: bool,
+}
+
+/// One asset and everything about it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetExport {
+	/// The asset id.
+	pub id: AssetId,
+	/// The owner of the asset class.
+	pub owner: AccountId,
+	/// The account that may mint.
+	pub issuer: AccountId,
+	/// The account that may thaw and force transfer.
+	pub admin: AccountId,
+	/// The account that may freeze.
+	pub freezer: AccountId,
+	/// `Live`, `Frozen` or `Destroying`.
+	pub status: String,
+	/// Whether holding the asset alone keeps an account alive.
+	pub is_sufficient: bool,
+	/// The smallest balance of an account.
+	pub min_balance: AssetBalance,
+	/// The supply recorded by the asset.
+	pub supply: AssetBalance,
+	/// The sum of the balances of all holders, which should equal `supply`.
+	pub holder_total: AssetBalance,
+	/// The name from the asset's metadata.
+	pub name: Option<String>,
+	/// The symbol from the asset's metadata.
+	pub symbol: Option<String>,
+	/// The decimals from the asset's metadata.
+	pub decimals: Option<u8>,
+	/// The holders, in storage order.
+	pub holders: Vec<HolderExport>,
+	/// The approvals, in storage order.
+	pub approvals: Vec<ApprovalExport>,
+}
+
+/// The ledger at a block.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerExport {
+	/// The number of the block exported at.
+	pub block_number: BlockNumber,
+	/// The hash of the block exported at.
+	pub block_hash: Hash,
+	/// The registry exported.
+	pub registry: AssetRegistry,
+	/// The assets exported.
+	pub assets: Vec<AssetExport>,
+}
+
+/// One row of the CSV export.
+#[derive(Debug, Serialize)]
+struct CsvRow<'a> {
+	asset_id: AssetId,
+	/// `asset`, `holder`, `freeze` or `approval`.
+	kind: &'static str,
+	/// The owner of an asset or approval, or the holder.
+	account: &'a AccountId,
+	delegate: Option<&'a AccountId>,
+	/// The supply of an asset, the balance of a holder, or the amount of an approval or freeze.
+	amount: AssetBalance,
+	/// The asset's status, whether a holder is frozen, whether an approval is unlimited, or the
+	/// reason of a freeze.
+	status: Option<String>,
+	name: Option<&'a str>,
+	symbol: Option<&'a str>,
+	decimals: Option<u8>,
+}
+
+/// Decodes the `Blake2_128Concat` hashed keys of a storage map entry, one after another.
+struct MapKey<'a>(&'a [u8]);
+
+impl MapKey<'_> {
+	fn next<K: Decode>(&mut self) -> Result<K, codec::Error> {
+		self.0 = self.0.get(16..).ok_or("storage key is too short")?;
+		K::decode(&mut self.0)
+	}
+}
+
+impl ExportLedgerCmd {
+	/// Run the command against `client`.
+	pub fn run<C, B>(&self, client: Arc<C>) -> sc_cli::Result<()>
+	where
+		C: StorageProvider<Block, B> + HeaderBackend<Block>,
+		B: Backend<Block>,
+	{
+		let at = match &self.at {
+			Some(at) => at.parse::<Block>()?,
+			None => BlockId::Hash(client.info().best_hash),
+		};
+		let block_hash = client.block_hash_from_id(&at)?.ok_or("Unknown block")?;
+		let block_number = client.block_number_from_id(&at)?.ok_or("Unknown block")?;
+		let at = BlockId::Hash(block_hash);
+		let (pallet, version_key) = match self.registry {
+			AssetRegistry::Community =>
+				(TemplateModule::name(), StorageVersion::storage_key::<TemplateModule>()),
+			AssetRegistry::Stable =>
+				(StableAssets::name(), StorageVersion::storage_key::<StableAssets>()),
+		};
+		let version = client
+			.storage(&at, &StorageKey(version_key.to_vec()))
+			.map_err(|e| format!("Unable to read the storage version at {}: {}", block_hash, e))?
+			.map(|data| u16::decode(&mut &data.0[..]))
+			.transpose()?
+			.unwrap_or_default();
+
+		let entries = |item: &str| -> sc_cli::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+			let prefix = [twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat();
+			let pairs = client.storage_pairs(&at, &StorageKey(prefix.clone())).map_err(|e| {
+				format!(
+					"Unable to read {} at block {}, is its state pruned? {}",
+					item, block_hash, e
+				)
+			})?;
+			Ok(pairs
+				.into_iter()
+				.map(|(key, value)| (key.0[prefix.len()..].to_vec(), value.0))
+				.collect())
+		};
+		// Version 1 still has the community balances as `u64`, version 2 widened them, and
+		// version 4 added `unlimited` to approvals. Version 5 left the ledger as it was. Version 0
+		// has no asset status.
+		if version < 1 || version > 5 {
+			return Err(format!(
+				"Block {} is at storage version {} of the {} pallet, whose ledger this node \
+				 cannot decode",
+				block_hash, version, pallet
+			)
+			.into())
+		}
+		let assets = match self.registry {
+			AssetRegistry::Community if version < 2 =>
+				read_assets::<u64>(&entries, version, self.asset)?,
+			AssetRegistry::Community =>
+				read_assets::<AssetBalance>(&entries, version, self.asset)?,
+			AssetRegistry::Stable => read_assets::<StableBalance>(&entries, version, self.asset)?,
+		};
+		if let Some(asset) = self.asset {
+			if assets.is_empty() {
+				return Err(format!("Asset {} does not exist at block {}", asset, block_hash).into())
+			}
+		}
+		let mismatched = assets
+			.iter()
+			.filter(|asset| asset.holder_total != asset.supply)
+			.map(|asset| asset.id)
+			.collect::<Vec<_>>();
+		let ledger = LedgerExport { block_number, block_hash, registry: self.registry, assets };
+
+		let output: Box<dyn io::Write> = match &self.output {
+			Some(path) => Box::new(File::create(path)?),
+			None => Box::new(io::stdout()),
+		};
+		match self.format {
+			LedgerFormat::Json => serde_json::to_writer_pretty(output, &ledger)
+				.map_err(|e| format!("Unable to write the ledger: {}", e))?,
+			LedgerFormat::Csv => write_csv(output, &ledger)
+				.map_err(|e| format!("Unable to write the ledger: {}", e))?,
+		}
+
+		if !mismatched.is_empty() {
+			return Err(format!(
+				"The holder balances of assets {:?} do not add up to their supply",
+				mismatched
+			)
+			.into())
+		}
+		Ok(())
+	}
+}
+
+/// The assets of a registry whose balances are `B` at storage `version`, or only `asset`.
+fn read_assets<B>(
+	entries: &Entries,
+	version: u16,
+	asset: Option<AssetId>,
+) -> sc_cli::Result<Vec<AssetExport>>
+where
+	B: Decode + Into<AssetBalance>,
+{
+	let wanted = |id: &AssetId| asset.map_or(true, |asset| asset == *id);
+
+	let mut assets = BTreeMap::new();
+	for (key, value) in entries("Asset")? {
+		let id: AssetId = MapKey(&key).next()?;
+		if wanted(&id) {
+			assets.insert(id, (AssetDetails::<B>::decode(&mut &value[..])?, None));
+		}
+	}
+	for (key, value) in entries("Metadata")? {
+		let id: AssetId = MapKey(&key).next()?;
+		if let Some((_, metadata)) = assets.get_mut(&id) {
+			*metadata = Some(Metadata::decode(&mut &value[..])?);
+		}
+	}
+
+	let mut freezes = BTreeMap::<(AssetId, AccountId), Vec<FreezeExport>>::new();
+	for (key, value) in entries("Freezes")? {
+		let mut key = MapKey(&key);
+		let id: AssetId = key.next()?;
+		if wanted(&id) {
+			let amounts = Vec::<(FreezeReason, B)>::decode(&mut &value[..])?;
+			freezes.insert(
+				(id, key.next()?),
+				amounts
+					.into_iter()
+					.map(|(reason, amount)| FreezeExport { reason, amount: amount.into() })
+					.collect(),
+			);
+		}
+	}
+	let mut holders = BTreeMap::<AssetId, Vec<HolderExport>>::new();
+	for (key, value) in entries("Account")? {
+		let mut key = MapKey(&key);
+		let id: AssetId = key.next()?;
+		if wanted(&id) {
+			let account: AccountId = key.next()?;
+			let data = AccountData::<B>::decode(&mut &value[..])?;
+			holders.entry(id).or_default().push(HolderExport {
+				freezes: freezes.remove(&(id, account.clone())).unwrap_or_default(),
+				account,
+				balance: data.balance.into(),
+				is_frozen: data.is_frozen,
+			});
+		}
+	}
+	let mut approvals = BTreeMap::<AssetId, Vec<ApprovalExport>>::new();
+	for (key, value) in entries("Approvals")? {
+		let mut key = MapKey(&key);
+		let id: AssetId = key.next()?;
+		if wanted(&id) {
+			let approval = if version < 4 {
+				let old = OldApproval::<B>::decode(&mut &value[..])?;
+				Approval::<B> { amount: old.amount, deposit: old.deposit, unlimited: false }
+			} else {
+				Approval::<B>::decode(&mut &value[..])?
+			};
+			approvals.entry(id).or_default().push(ApprovalExport {
+				owner: key.next()?,
+				delegate: key.next()?,
+				amount: approval.amount.into(),
+				unlimited: approval.unlimited,
+			});
+		}
+	}
+
+	Ok(assets
+		.into_iter()
+		.map(|(id, (details, metadata))| {
+			let holders = holders.remove(&id).unwrap_or_default();
+			let holder_total = holders
+				.iter()
+				.fold(AssetBalance::zero(), |total, h| total.saturating_add(h.balance));
+			let text = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+			AssetExport {
+				id,
+				owner: details.owner,
+				issuer: details.issuer,
+				admin: details.admin,
+				freezer: details.freezer,
+				status: format!("{:?}", details.status),
+				is_sufficient: details.is_sufficient,
+				min_balance: details.min_balance.into(),
+				supply: details.supply.into(),
+				holder_total,
+				name: metadata.as_ref().map(|m| text(&m.name)),
+				symbol: metadata.as_ref().map(|m| text(&m.symbol)),
+				decimals: metadata.as_ref().map(|m| m.decimals),
+				holders,
+				approvals: approvals.remove(&id).unwrap_or_default(),
+			}
+		})
+		.collect())
+}
+
+fn write_csv(output: impl io::Write, ledger: &LedgerExport) -> Result<(), csv::Error> {
+	let mut writer = csv::Writer::from_writer(output);
+	for asset in ledger.assets.iter() {
+		writer.serialize(CsvRow {
+			asset_id: asset.id,
+			kind: "asset",
+			account: &asset.owner,
+			delegate: None,
+			amount: asset.supply,
+			status: Some(asset.status.clone()),
+			name: asset.name.as_deref(),
+			symbol: asset.symbol.as_deref(),
+			decimals: asset.decimals,
+		})?;
+		for holder in asset.holders.iter() {
+			writer.serialize(CsvRow {
+				asset_id: asset.id,
+				kind: "holder",
+				account: &holder.account,
+				delegate: None,
+				amount: holder.balance,
+				status: Some(if holder.is_frozen { "Frozen" } else { "Live" }.into()),
+				name: None,
+				symbol: None,
+				decimals: None,
+			})?;
+			for freeze in holder.freezes.iter() {
+				writer.serialize(CsvRow {
+					asset_id: asset.id,
+					kind: "freeze",
+					account: &holder.account,
+					delegate: None,
+					amount: freeze.amount,
+					status: Some(freeze.reason.to_string()),
+					name: None,
+					symbol: None,
+					decimals: None,
+				})?;
+			}
+		}
+		for approval in asset.approvals.iter() {
+			writer.serialize(CsvRow {
+				asset_id: asset.id,
+				kind: "approval",
+				account: &approval.owner,
+				delegate: Some(&approval.delegate),
+				amount: approval.amount,
+				status: approval.unlimited.then(|| "Unlimited".into()),
+				name: None,
+				symbol: None,
+				decimals: None,
+			})?;
+		}
+	}
+	writer.flush()?;
+	Ok(())
+}
+
+impl CliConfiguration for ExportLedgerCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+}
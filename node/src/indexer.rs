@@ -0,0 +1,492 @@
+//! An optional index of ERC20 asset history, kept in its own database next to the chain's.
+//!
+//! The indexer follows the best chain. The `pallet_template` events of every block that joins it,
+//! of both asset registries, are decoded from `System::Events` and stored once for every account
+//! they concern, so that the history of one account in one asset is a single prefix scan. Blocks
+//! that leave the best chain in a reorg have their records removed again.
+//!
+//! Only blocks whose state is still available can be indexed: run the node with
+//! `--pruning archive` to index history older than the pruning window. The index never skips a
+//! block. It stops before the first block whose state is unavailable or whose events it cannot
+//! decode, and tries that block again whenever a new best block is imported.
+//!
+//! Events are decoded with the current runtime's types, so blocks executed before both asset
+//! pallets reached storage version 3 cannot be indexed: their amounts were `u64` and their
+//! `ApprovedTransfer` did not carry the allowance left. The indexer stops at the first of them.
+
+use codec::{Decode, Encode};
+use futures::StreamExt;
+use kvdb::{DBTransaction, KeyValueDB};
+use frame_support::traits::{PalletInfoAccess, StorageVersion};
+use node_template_runtime::{
+	opaque::Block, pallet_template, AccountId, AssetBalance, AssetId, AssetRegistry, BlockNumber,
+	Event, Hash, Runtime, StableAssets, TemplateModule,
+};
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use serde::{Deserialize, Serialize};
+use sp_blockchain::{HeaderBackend, HeaderMetadata};
+use sp_core::{storage::StorageKey, twox_128};
+use sp_runtime::{generic::BlockId, traits::Zero};
+use std::{fmt, io, path::Path, sync::Arc};
+
+const LOG_TARGET: &str = "erc20-indexer";
+
+const NUM_COLUMNS: u32 = 3;
+/// Indexer state, currently only the best block indexed so far.
+const COL_META: u32 = 0;
+/// Records keyed by `history_key`.
+const COL_HISTORY: u32 = 1;
+/// The history keys each indexed block wrote, so that the block can be reverted.
+const COL_BLOCKS: u32 = 2;
+
+const BEST_KEY: &[u8] = b"best";
+const VERSION_KEY: &[u8] = b"version";
+
+/// The layout of the index. Version 1 added the registry to records and their keys, version 2
+/// widened amounts to 256 bits.
+const VERSION: u32 = 2;
+
+/// The storage version of the asset pallets from which their events decode as the current types.
+const EVENTS_STORAGE_VERSION: u16 = 3;
+
+type EventRecord = frame_system::EventRecord<Event, Hash>;
+
+/// What an indexed record describes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HistoryKind {
+	/// Assets moved from `from` to `to`.
+	Transfer,
+	/// Assets were minted to `to`.
+	Mint,
+	/// Assets were burned from `from`.
+	Burn,
+	/// `from` approved `delegate` to spend `amount` more.
+	Approval,
+	/// `from` cancelled the approval of `delegate`.
+	ApprovalCancelled,
+	/// `delegate` spent an approval of `from` to pay `to`. The movement itself is recorded as a
+	/// `Transfer` as well.
+	ApprovedTransfer,
+	/// `from` approved `delegate` to spend any amount.
+	UnlimitedApproval,
+}
+
+/// One indexed `pallet_template` event.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRecord {
+	/// The number of the block that emitted the event.
+	pub block_number: BlockNumber,
+	/// The hash of the block that emitted the event.
+	pub block_hash: Hash,
+	/// The position of the event in the block's events.
+	pub event_index: u32,
+	/// What happened.
+	pub kind: HistoryKind,
+	/// The registry of the asset concerned.
+	pub registry: AssetRegistry,
+	/// The asset concerned.
+	pub asset_id: AssetId,
+	/// The account assets left or that gave the approval, if any.
+	pub from: Option<AccountId>,
+	/// The account assets arrived at, if any.
+	pub to: Option<AccountId>,
+	/// The approved delegate, if any.
+	pub delegate: Option<AccountId>,
+	/// The amount moved or approved.
+	pub amount: AssetBalance,
+}
+
+impl HistoryRecord {
+	/// The accounts whose history contains this record.
+	fn accounts(&self) -> Vec<&AccountId> {
+		let mut accounts = Vec::with_capacity(3);
+		for who in [&self.from, &self.to, &self.delegate].iter().copied().flatten() {
+			if !accounts.contains(&who) {
+				accounts.push(who);
+			}
+		}
+		accounts
+	}
+}
+
+/// A position in the history of an account, as returned with each page.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryCursor {
+	/// The block number of the last record returned.
+	pub block_number: BlockNumber,
+	/// The event index of the last record returned.
+	pub event_index: u32,
+}
+
+/// A page of history, oldest record first.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage {
+	/// The records of this page.
+	pub records: Vec<HistoryRecord>,
+	/// Where the next page starts, or `None` if this is the last one.
+	pub next: Option<HistoryCursor>,
+}
+
+/// Why the index could not be read or updated.
+#[derive(Debug)]
+pub enum Error {
+	/// The index database failed.
+	Db(io::Error),
+	/// The client failed to provide a block or its state.
+	Client(sp_blockchain::Error),
+	/// Something stored could not be decoded.
+	Codec(codec::Error),
+	/// The state of a block to index is unavailable, most likely pruned.
+	State(Hash, sp_blockchain::Error),
+	/// The events of a block to index could not be decoded.
+	Events(Hash, codec::Error),
+	/// A block to index was executed by a runtime whose events have an older layout: the pallet
+	/// named was at the storage version given.
+	Layout(Hash, &'static str, u16),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Db(e) => write!(f, "index database error: {}", e),
+			Error::Client(e) => write!(f, "client error: {}", e),
+			Error::Codec(e) => write!(f, "decoding error: {}", e),
+			Error::State(hash, e) => write!(
+				f,
+				"state of block {} is unavailable ({}); run the node with `--pruning archive` \
+				 to index it",
+				hash, e
+			),
+			Error::Events(hash, e) => write!(f, "events of block {} do not decode: {}", hash, e),
+			Error::Layout(hash, pallet, version) => write!(
+				f,
+				"block {} predates the current event layout: {} is at storage version {}, the \
+				 indexer needs {}",
+				hash, pallet, version, EVENTS_STORAGE_VERSION
+			),
+		}
+	}
+}
+
+impl From<io::Error> for Error {
+	fn from(e: io::Error) -> Self {
+		Error::Db(e)
+	}
+}
+
+impl From<sp_blockchain::Error> for Error {
+	fn from(e: sp_blockchain::Error) -> Self {
+		Error::Client(e)
+	}
+}
+
+impl From<codec::Error> for Error {
+	fn from(e: codec::Error) -> Self {
+		Error::Codec(e)
+	}
+}
+
+/// The key prefix of the history of `who` in `asset_id` of `registry`.
+fn history_prefix(registry: AssetRegistry, asset_id: AssetId, who: &AccountId) -> Vec<u8> {
+	(registry, asset_id, who).encode()
+}
+
+/// The key of a record in the history of `who`. Block number and event index are big-endian so
+/// that a prefix scan returns the history in chain order.
+fn history_key(
+	registry: AssetRegistry,
+	asset_id: AssetId,
+	who: &AccountId,
+	block_number: BlockNumber,
+	index: u32,
+) -> Vec<u8> {
+	let mut key = history_prefix(registry, asset_id, who);
+	key.extend_from_slice(&block_number.to_be_bytes());
+	key.extend_from_slice(&index.to_be_bytes());
+	key
+}
+
+/// What an event of either registry says, with its amount widened to `AssetBalance`.
+type EventFields =
+	(HistoryKind, AssetId, Option<AccountId>, Option<AccountId>, Option<AccountId>, AssetBalance);
+
+fn event_fields<I: 'static>(event: pallet_template::Event<Runtime, I>) -> Option<EventFields>
+where
+	Runtime: pallet_template::Config<I, AssetId = AssetId>,
+	<Runtime as pallet_template::Config<I>>::Balance: Into<AssetBalance>,
+{
+	use pallet_template::Event as Erc20Event;
+
+	Some(match event {
+		Erc20Event::Transferred(id, from, to, amount) =>
+			(HistoryKind::Transfer, id, Some(from), Some(to), None, amount.into()),
+		Erc20Event::Issued(id, to, amount) =>
+			(HistoryKind::Mint, id, None, Some(to), None, amount.into()),
+		Erc20Event::Burned(id, from, amount) =>
+			(HistoryKind::Burn, id, Some(from), None, None, amount.into()),
+		Erc20Event::ApprovedTransfer(id, owner, delegate, amount, _) =>
+			(HistoryKind::Approval, id, Some(owner), None, Some(delegate), amount.into()),
+		Erc20Event::ApprovalCancelled(id, owner, delegate) =>
+			(HistoryKind::ApprovalCancelled, id, Some(owner), None, Some(delegate), Zero::zero()),
+		Erc20Event::ApprovedUnlimited(id, owner, delegate) =>
+			(HistoryKind::UnlimitedApproval, id, Some(owner), None, Some(delegate), Zero::zero()),
+		Erc20Event::ApprovalUsed(id, owner, delegate, to, amount, _) => (
+			HistoryKind::ApprovedTransfer,
+			id,
+			Some(owner),
+			Some(to),
+			Some(delegate),
+			amount.into(),
+		),
+		_ => return None,
+	})
+}
+
+/// Turn one event into a record, if it is one the index keeps.
+fn record(
+	block_number: BlockNumber,
+	block_hash: Hash,
+	event_index: u32,
+	event: Event,
+) -> Option<HistoryRecord> {
+	let (registry, (kind, asset_id, from, to, delegate, amount)) = match event {
+		Event::TemplateModule(event) => (AssetRegistry::Community, event_fields(event)?),
+		Event::StableAssets(event) => (AssetRegistry::Stable, event_fields(event)?),
+		_ => return None,
+	};
+	Some(HistoryRecord {
+		block_number,
+		block_hash,
+		event_index,
+		kind,
+		registry,
+		asset_id,
+		from,
+		to,
+		delegate,
+		amount,
+	})
+}
+
+/// The history database.
+pub struct Erc20Index {
+	db: Arc<dyn KeyValueDB>,
+}
+
+impl Erc20Index {
+	/// Open the index at `path`, creating it if needed. An index of an older layout has to be
+	/// deleted and rebuilt.
+	pub fn open(path: &Path) -> io::Result<Self> {
+		let config = kvdb_rocksdb::DatabaseConfig::with_columns(NUM_COLUMNS);
+		let db = kvdb_rocksdb::Database::open(&config, &path.to_string_lossy())?;
+		let version = match db.get(COL_META, VERSION_KEY)? {
+			Some(version) => u32::decode(&mut &version[..])
+				.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?,
+			None if db.get(COL_META, BEST_KEY)?.is_some() => 0,
+			None => {
+				let mut tx = DBTransaction::new();
+				tx.put(COL_META, VERSION_KEY, &VERSION.encode());
+				db.write(tx)?;
+				VERSION
+			},
+		};
+		if version != VERSION {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				format!(
+					"the index has layout version {} but this node writes version {}; delete it to \
+					 rebuild it",
+					version, VERSION
+				),
+			))
+		}
+		Ok(Erc20Index { db: Arc::new(db) })
+	}
+
+	/// The best block indexed so far.
+	pub fn best(&self) -> Result<Option<Hash>, Error> {
+		match self.db.get(COL_META, BEST_KEY)? {
+			Some(hash) => Ok(Some(Hash::decode(&mut &hash[..])?)),
+			None => Ok(None),
+		}
+	}
+
+	/// Up to `limit` records of `asset_id` of `registry` concerning `who`, oldest first, starting
+	/// after `after`.
+	pub fn history(
+		&self,
+		registry: AssetRegistry,
+		asset_id: AssetId,
+		who: &AccountId,
+		after: Option<HistoryCursor>,
+		limit: u32,
+	) -> Result<HistoryPage, Error> {
+		let prefix = history_prefix(registry, asset_id, who);
+		let start =
+			after.map(|c| history_key(registry, asset_id, who, c.block_number, c.event_index));
+		let mut records = Vec::new();
+		let mut next = None;
+		for (key, value) in self.db.iter_with_prefix(COL_HISTORY, &prefix) {
+			if start.as_ref().map_or(false, |start| &key[..] <= &start[..]) {
+				continue
+			}
+			if records.len() == limit as usize {
+				next = records.last().map(|r: &HistoryRecord| HistoryCursor {
+					block_number: r.block_number,
+					event_index: r.event_index,
+				});
+				break
+			}
+			records.push(HistoryRecord::decode(&mut &value[..])?);
+		}
+		Ok(HistoryPage { records, next })
+	}
+
+	/// Index the blocks needed to make `new_best` the best block, reverting any that left the
+	/// best chain.
+	fn follow<C, B>(&self, client: &C, new_best: Hash) -> Result<(), Error>
+	where
+		C: HeaderBackend<Block> + StorageProvider<Block, B>,
+		C: HeaderMetadata<Block, Error = sp_blockchain::Error>,
+		B: Backend<Block>,
+	{
+		let best = match self.best()? {
+			Some(best) => best,
+			None => client.info().genesis_hash,
+		};
+		if best == new_best {
+			return Ok(())
+		}
+
+		let route = sp_blockchain::tree_route(client, best, new_best)?;
+		for block in route.retracted() {
+			let parent = client.header_metadata(block.hash)?.parent;
+			self.revert(block.hash, parent)?;
+		}
+		if route.enacted().is_empty() {
+			self.set_best(route.common_block().hash)?;
+		}
+		for block in route.enacted() {
+			let records = block_events::<C, B>(client, block.hash)?
+				.into_iter()
+				.enumerate()
+				.filter_map(|(index, event)| {
+					record(block.number, block.hash, index as u32, event.event)
+				})
+				.collect::<Vec<_>>();
+			self.apply(block.hash, records)?;
+		}
+		Ok(())
+	}
+
+	/// Store the records of a block that joined the best chain and make it the best block.
+	fn apply(&self, hash: Hash, records: Vec<HistoryRecord>) -> Result<(), Error> {
+		let mut tx = DBTransaction::new();
+		let mut keys = Vec::new();
+		for record in records.iter() {
+			let value = record.encode();
+			for who in record.accounts() {
+				let key = history_key(
+					record.registry,
+					record.asset_id,
+					who,
+					record.block_number,
+					record.event_index,
+				);
+				tx.put(COL_HISTORY, &key, &value);
+				keys.push(key);
+			}
+		}
+		if !records.is_empty() {
+			log::debug!(target: LOG_TARGET, "Indexed {} records of block {}", records.len(), hash);
+		}
+		tx.put(COL_BLOCKS, hash.as_ref(), &keys.encode());
+		tx.put(COL_META, BEST_KEY, &hash.encode());
+		self.db.write(tx)?;
+		Ok(())
+	}
+
+	/// Remove the records of a block that left the best chain and make its parent the best
+	/// block.
+	fn revert(&self, hash: Hash, parent: Hash) -> Result<(), Error> {
+		let mut tx = DBTransaction::new();
+		if let Some(keys) = self.db.get(COL_BLOCKS, hash.as_ref())? {
+			for key in Vec::<Vec<u8>>::decode(&mut &keys[..])? {
+				tx.delete(COL_HISTORY, &key);
+			}
+		}
+		log::debug!(target: LOG_TARGET, "Reverted block {}", hash);
+		tx.delete(COL_BLOCKS, hash.as_ref());
+		tx.put(COL_META, BEST_KEY, &parent.encode());
+		self.db.write(tx)?;
+		Ok(())
+	}
+
+	fn set_best(&self, hash: Hash) -> Result<(), Error> {
+		let mut tx = DBTransaction::new();
+		tx.put(COL_META, BEST_KEY, &hash.encode());
+		self.db.write(tx)?;
+		Ok(())
+	}
+}
+
+/// The events of block `hash`. Blocks whose state or events cannot be read are an error rather
+/// than a block without events, so that the index never silently misses any, and so are blocks
+/// whose events have an older layout than the current types.
+fn block_events<C, B>(client: &C, hash: Hash) -> Result<Vec<EventRecord>, Error>
+where
+	C: StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	let at = BlockId::Hash(hash);
+	for (pallet, key) in [
+		(TemplateModule::name(), StorageVersion::storage_key::<TemplateModule>()),
+		(StableAssets::name(), StorageVersion::storage_key::<StableAssets>()),
+	] {
+		let version = client
+			.storage(&at, &StorageKey(key.to_vec()))
+			.map_err(|e| Error::State(hash, e))?
+			.map(|data| u16::decode(&mut &data.0[..]))
+			.transpose()?
+			.unwrap_or_default();
+		if version < EVENTS_STORAGE_VERSION {
+			return Err(Error::Layout(hash, pallet, version))
+		}
+	}
+
+	let key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+	let events = client.storage(&at, &key).map_err(|e| Error::State(hash, e))?;
+	match events {
+		Some(events) =>
+			Vec::<EventRecord>::decode(&mut &events.0[..]).map_err(|e| Error::Events(hash, e)),
+		None => Ok(Vec::new()),
+	}
+}
+
+/// Keep `index` in step with the best chain of `client`, starting with whatever was imported
+/// while the indexer was not running.
+pub async fn run<C, B>(client: Arc<C>, index: Arc<Erc20Index>)
+where
+	C: BlockchainEvents<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+	C: HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	B: Backend<Block>,
+{
+	let mut imports = client.import_notification_stream();
+	let mut new_best = Some(client.info().best_hash);
+	loop {
+		if let Some(hash) = new_best {
+			if let Err(e) = index.follow::<C, B>(&*client, hash) {
+				log::error!(target: LOG_TARGET, "Failed to index up to block {}: {}", hash, e);
+			}
+		}
+		new_best = match imports.next().await {
+			Some(notification) if notification.is_new_best => Some(notification.hash),
+			Some(_) => None,
+			None => return,
+		};
+	}
+}
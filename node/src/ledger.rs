@@ -0,0 +1,338 @@
+//! Token ledgers that seed the genesis of test networks with native balances and ERC20 assets.
+//!
+//! A ledger is read from a JSON file, or from a CSV file (by its `.csv` extension) with one entry
+//! per row, the first column saying what the row holds:
+//!
+//! ```text
+//! # Lines starting with `#` are ignored.
+//! account,  <address>, <native balance>
+//! asset,    <asset id>, <owner address>, <is sufficient>, <min balance>
+//! metadata, <asset id>, <name>, <symbol>, <decimals>
+//! balance,  <asset id>, <holder address>, <balance>
+//! ```
+//!
+//! The `asset`, `metadata` and `balance` rows are of the community registry. Their
+//! `stable_asset`, `stable_metadata` and `stable_balance` forms are of the stable registry.
+//!
+//! The JSON form has the same four lists, whose assets, metadata and balances may name their
+//! `registry`, `community` by default:
+//!
+//! ```json
+//! {
+//!   "accounts": [{ "account": "5Grw...", "balance": 1000000 }],
+//!   "assets": [{ "id": 1, "owner": "5Grw...", "isSufficient": true, "minBalance": 1 }],
+//!   "metadata": [{ "id": 1, "name": "Tether", "symbol": "USDT", "decimals": 6 }],
+//!   "balances": [{ "asset": 1, "account": "5Grw...", "balance": 100, "registry": "stable" }]
+//! }
+//! ```
+//!
+//! Asset amounts are numbers or, as they may exceed what JSON numbers hold exactly, decimal
+//! strings.
+//!
+//! Holders of assets that are not sufficient must have a native balance to exist.
+
+use node_template_runtime::{
+	AccountId, AssetBalance, AssetId, AssetRegistry, Balance, StableAssetsConfig, StableBalance,
+	TemplateModuleConfig, TOKEN_DECIMALS, TOKEN_SYMBOL,
+};
+use sc_service::Properties;
+use serde::Deserialize;
+use sp_core::crypto::Ss58Codec;
+use std::{
+	collections::BTreeSet, convert::TryInto, fmt::Display, fs::File, path::Path, str::FromStr,
+};
+
+/// A native balance.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LedgerAccount {
+	/// The account funded.
+	pub account: AccountId,
+	/// Its native balance.
+	pub balance: Balance,
+}
+
+/// An asset created at genesis.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LedgerAsset {
+	/// The registry to create the asset in.
+	#[serde(default)]
+	pub registry: AssetRegistry,
+	/// The asset id.
+	pub id: AssetId,
+	/// The owner, who is also its issuer, admin and freezer.
+	pub owner: AccountId,
+	/// Whether holding the asset alone keeps an account alive.
+	pub is_sufficient: bool,
+	/// The smallest balance of an account of the asset.
+	pub min_balance: AssetBalance,
+}
+
+/// The metadata of a genesis asset.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LedgerMetadata {
+	/// The registry of the asset.
+	#[serde(default)]
+	pub registry: AssetRegistry,
+	/// The asset id.
+	pub id: AssetId,
+	/// The asset's name.
+	pub name: String,
+	/// The asset's ticker symbol.
+	pub symbol: String,
+	/// The asset's decimals.
+	pub decimals: u8,
+}
+
+/// A balance of a genesis asset.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LedgerBalance {
+	/// The registry of the asset.
+	#[serde(default)]
+	pub registry: AssetRegistry,
+	/// The asset id.
+	pub asset: AssetId,
+	/// The holder.
+	pub account: AccountId,
+	/// The holder's balance.
+	pub balance: AssetBalance,
+}
+
+/// Native balances, assets, their metadata and balances to start a chain with.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TokenLedger {
+	/// Native balances.
+	#[serde(default)]
+	pub accounts: Vec<LedgerAccount>,
+	/// Assets to create.
+	#[serde(default)]
+	pub assets: Vec<LedgerAsset>,
+	/// Metadata of the assets.
+	#[serde(default)]
+	pub metadata: Vec<LedgerMetadata>,
+	/// Balances of the assets.
+	#[serde(default)]
+	pub balances: Vec<LedgerBalance>,
+}
+
+impl TokenLedger {
+	/// Load the ledger at `path`, as CSV if it has a `csv` extension and as JSON otherwise.
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let file = File::open(path)
+			.map_err(|e| format!("Error opening token ledger {}: {}", path.display(), e))?;
+		let ledger = if path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("csv")) {
+			Self::from_csv(file)
+		} else {
+			serde_json::from_reader(file).map_err(|e| e.to_string())
+		}
+		.map_err(|e| format!("Error parsing token ledger {}: {}", path.display(), e))?;
+		ledger.validate()?;
+		Ok(ledger)
+	}
+
+	fn from_csv(reader: impl std::io::Read) -> Result<Self, String> {
+		let mut reader = csv::ReaderBuilder::new()
+			.has_headers(false)
+			.flexible(true)
+			.comment(Some(b'#'))
+			.trim(csv::Trim::All)
+			.from_reader(reader);
+		let mut ledger = TokenLedger::default();
+		for record in reader.records() {
+			let record = record.map_err(|e| e.to_string())?;
+			let line = record.position().map_or(0, |p| p.line());
+			let row = Row { record: &record, line };
+			let (registry, kind) = match record[0].strip_prefix("stable_") {
+				Some(kind) => (AssetRegistry::Stable, kind),
+				None => (AssetRegistry::Community, &record[0]),
+			};
+			match kind {
+				"account" if registry == AssetRegistry::Community => {
+					row.expect_len(3)?;
+					ledger
+						.accounts
+						.push(LedgerAccount { account: row.account(1)?, balance: row.parse(2)? });
+				},
+				"asset" => {
+					row.expect_len(5)?;
+					ledger.assets.push(LedgerAsset {
+						registry,
+						id: row.parse(1)?,
+						owner: row.account(2)?,
+						is_sufficient: row.parse(3)?,
+						min_balance: row.parse(4)?,
+					});
+				},
+				"metadata" => {
+					row.expect_len(5)?;
+					ledger.metadata.push(LedgerMetadata {
+						registry,
+						id: row.parse(1)?,
+						name: record[2].to_string(),
+						symbol: record[3].to_string(),
+						decimals: row.parse(4)?,
+					});
+				},
+				"balance" => {
+					row.expect_len(4)?;
+					ledger.balances.push(LedgerBalance {
+						registry,
+						asset: row.parse(1)?,
+						account: row.account(2)?,
+						balance: row.parse(3)?,
+					});
+				},
+				_ => return Err(format!("line {}: unknown entry `{}`", line, &record[0])),
+			}
+		}
+		Ok(ledger)
+	}
+
+	/// Check that asset ids are unique within their registry, that metadata and balances are of
+	/// ledger assets, and that amounts fit the balances of their registry.
+	fn validate(&self) -> Result<(), String> {
+		let fits = |registry: AssetRegistry, amount: AssetBalance| {
+			registry == AssetRegistry::Community ||
+				TryInto::<StableBalance>::try_into(amount).is_ok()
+		};
+		let mut ids = BTreeSet::new();
+		for asset in self.assets.iter() {
+			if !ids.insert((asset.registry, asset.id)) {
+				return Err(format!(
+					"Asset {} of the {:?} registry is created twice in the token ledger",
+					asset.id, asset.registry
+				))
+			}
+			if !fits(asset.registry, asset.min_balance) {
+				return Err(format!("Minimum balance of asset {} is too large", asset.id))
+			}
+		}
+		let mut with_metadata = BTreeSet::new();
+		for metadata in self.metadata.iter() {
+			let key = (metadata.registry, metadata.id);
+			if !ids.contains(&key) || !with_metadata.insert(key) {
+				return Err(format!(
+					"Metadata of asset {} of the {:?} registry is unknown or repeated",
+					metadata.id, metadata.registry
+				))
+			}
+		}
+		for balance in self.balances.iter() {
+			if !ids.contains(&(balance.registry, balance.asset)) {
+				return Err(format!(
+					"Balance of unknown asset {} of the {:?} registry",
+					balance.asset, balance.registry
+				))
+			}
+			if !fits(balance.registry, balance.balance) {
+				return Err(format!("Balance of asset {} is too large", balance.asset))
+			}
+		}
+		Ok(())
+	}
+
+	/// The chain spec properties naming the tokens for wallets: the native token, followed by
+	/// every asset with metadata.
+	pub fn properties(&self) -> Properties {
+		let mut symbols = vec![serde_json::Value::from(TOKEN_SYMBOL)];
+		let mut decimals = vec![serde_json::Value::from(TOKEN_DECIMALS)];
+		for metadata in self.metadata.iter() {
+			symbols.push(metadata.symbol.clone().into());
+			decimals.push(metadata.decimals.into());
+		}
+
+		let mut properties = Properties::new();
+		if symbols.len() == 1 {
+			properties.insert("tokenSymbol".into(), symbols.remove(0));
+			properties.insert("tokenDecimals".into(), decimals.remove(0));
+		} else {
+			properties.insert("tokenSymbol".into(), symbols.into());
+			properties.insert("tokenDecimals".into(), decimals.into());
+		}
+		properties
+	}
+
+	/// The genesis config of the community assets.
+	pub fn template_config(&self) -> TemplateModuleConfig {
+		let (assets, metadata, accounts) = self.genesis(AssetRegistry::Community, |amount| amount);
+		TemplateModuleConfig { assets, metadata, accounts }
+	}
+
+	/// The genesis config of the stable assets.
+	pub fn stable_assets_config(&self) -> StableAssetsConfig {
+		let (assets, metadata, accounts) = self.genesis(AssetRegistry::Stable, |amount| {
+			amount.try_into().expect("amounts are checked to fit when loading; qed")
+		});
+		StableAssetsConfig { assets, metadata, accounts }
+	}
+
+	/// The assets, metadata and balances of `registry`, with amounts converted by `amount`.
+	fn genesis<B>(
+		&self,
+		registry: AssetRegistry,
+		amount: impl Fn(AssetBalance) -> B,
+	) -> (GenesisAssets<B>, GenesisMetadata, GenesisAccounts<B>) {
+		let assets = self
+			.assets
+			.iter()
+			.filter(|a| a.registry == registry)
+			.map(|a| (a.id, a.owner.clone(), a.is_sufficient, amount(a.min_balance)))
+			.collect();
+		let metadata = self
+			.metadata
+			.iter()
+			.filter(|m| m.registry == registry)
+			.map(|m| (m.id, m.name.clone().into_bytes(), m.symbol.clone().into_bytes(), m.decimals))
+			.collect();
+		let accounts = self
+			.balances
+			.iter()
+			.filter(|b| b.registry == registry)
+			.map(|b| (b.asset, b.account.clone(), amount(b.balance)))
+			.collect();
+		(assets, metadata, accounts)
+	}
+}
+
+type GenesisAssets<B> = Vec<(AssetId, AccountId, bool, B)>;
+type GenesisMetadata = Vec<(AssetId, Vec<u8>, Vec<u8>, u8)>;
+type GenesisAccounts<B> = Vec<(AssetId, AccountId, B)>;
+
+/// A row of a CSV ledger.
+struct Row<'a> {
+	record: &'a csv::StringRecord,
+	line: u64,
+}
+
+impl Row<'_> {
+	fn expect_len(&self, len: usize) -> Result<(), String> {
+		if self.record.len() != len {
+			return Err(format!(
+				"line {}: expected {} columns, found {}",
+				self.line,
+				len,
+				self.record.len()
+			))
+		}
+		Ok(())
+	}
+
+	fn parse<T: FromStr>(&self, column: usize) -> Result<T, String>
+	where
+		T::Err: Display,
+	{
+		self.record[column]
+			.parse()
+			.map_err(|e| format!("line {}, column {}: {}", self.line, column + 1, e))
+	}
+
+	fn account(&self, column: usize) -> Result<AccountId, String> {
+		AccountId::from_ss58check(&self.record[column]).map_err(|e| {
+			format!("line {}, column {}: invalid address: {:?}", self.line, column + 1, e)
+		})
+	}
+}
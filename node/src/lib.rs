@@ -1,3 +1,7 @@
+pub mod airdrop;
 pub mod chain_spec;
+pub mod export;
+pub mod indexer;
+pub mod ledger;
 pub mod rpc;
 pub mod service;
@@ -7,44 +7,412 @@
 
 use std::sync::Arc;
 
-use node_template_runtime::{opaque::Block, AccountId, Balance, Index};
+use crate::indexer::{Erc20Index, HistoryCursor, HistoryPage};
+use codec::Decode;
+use frame_support::traits::PalletInfoAccess;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result as RpcResult};
+use jsonrpc_derive::rpc;
+use node_template_runtime::{
+	opaque::Block, pallet_template, AccountId, AssetBalance, AssetId, AssetRegistry, Balance,
+	BlockNumber, Hash, Index, StableAssets, StableBalance, TemplateModule,
+};
+use pallet_template_runtime_api::Erc20HoldersApi as Erc20HoldersRuntimeApi;
 pub use sc_rpc_api::DenyUnsafe;
 use sc_transaction_pool_api::TransactionPool;
+use serde::{Deserialize, Serialize};
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_core::offchain::OffchainStorage;
+use sp_runtime::generic::BlockId;
 
 /// Full client dependencies.
-pub struct FullDeps<C, P> {
+pub struct FullDeps<C, P, B> {
 	/// The client instance to use.
 	pub client: Arc<C>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
+	/// The backend, for reading offchain storage.
+	pub backend: Arc<B>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// The ERC20 history index, if the node keeps one.
+	pub erc20_index: Option<Arc<Erc20Index>>,
+}
+
+/// The most records `erc20_transferHistory` returns at once.
+const MAX_HISTORY_PAGE: u32 = 100;
+
+/// ERC20 asset history, served from the node's index.
+#[rpc]
+pub trait Erc20HistoryApi {
+	/// Transfers, mints, burns and approvals of `asset_id` of `registry`, the community assets by
+	/// default, concerning `who`, oldest first, at most `limit` at a time. Pass the `next` cursor
+	/// of a page as `after` to get the next one.
+	#[rpc(name = "erc20_transferHistory")]
+	fn transfer_history(
+		&self,
+		asset_id: AssetId,
+		who: AccountId,
+		after: Option<HistoryCursor>,
+		limit: Option<u32>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<HistoryPage>;
+
+	/// The best block the index has caught up with, or `None` before the first one.
+	#[rpc(name = "erc20_transferHistoryBestBlock")]
+	fn best_block(&self) -> RpcResult<Option<Hash>>;
+}
+
+/// Serves `Erc20HistoryApi` from an `Erc20Index`.
+pub struct Erc20History {
+	index: Arc<Erc20Index>,
+}
+
+impl Erc20History {
+	/// Create a new `Erc20History` reading from `index`.
+	pub fn new(index: Arc<Erc20Index>) -> Self {
+		Erc20History { index }
+	}
+}
+
+fn index_error(e: crate::indexer::Error) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(1),
+		message: "Unable to read the ERC20 index.".into(),
+		data: Some(e.to_string().into()),
+	}
+}
+
+impl Erc20HistoryApi for Erc20History {
+	fn transfer_history(
+		&self,
+		asset_id: AssetId,
+		who: AccountId,
+		after: Option<HistoryCursor>,
+		limit: Option<u32>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<HistoryPage> {
+		let limit = limit.unwrap_or(MAX_HISTORY_PAGE).clamp(1, MAX_HISTORY_PAGE);
+		self.index
+			.history(registry.unwrap_or_default(), asset_id, &who, after, limit)
+			.map_err(index_error)
+	}
+
+	fn best_block(&self) -> RpcResult<Option<Hash>> {
+		self.index.best().map_err(index_error)
+	}
+}
+
+/// The most accounts `erc20_holders` and `erc20_approvalsOf` return at once, the most approvals
+/// `erc20_approvalsByOwner` and `erc20_approvalsByDelegate` return at once, and the most holders
+/// `erc20_topHolders` returns.
+const MAX_ACCOUNT_PAGE: u32 = 100;
+
+/// A page of accounts and their balances or approved amounts, in storage order.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountPage {
+	/// The accounts of this page and their amounts.
+	pub accounts: Vec<(AccountId, AssetBalance)>,
+	/// The account to pass as `start_after` for the next page, or `None` if this is the last one.
+	pub next: Option<AccountId>,
+}
+
+impl AccountPage {
+	/// The page of at most `limit` accounts out of `accounts`, which holds one more account than
+	/// the page if there is a next one.
+	fn new(mut accounts: Vec<(AccountId, AssetBalance)>, limit: u32) -> Self {
+		let next = if accounts.len() > limit as usize {
+			accounts.truncate(limit as usize);
+			accounts.last().map(|(who, _)| who.clone())
+		} else {
+			None
+		};
+		AccountPage { accounts, next }
+	}
+}
+
+/// A page of approvals across assets, in storage order.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalPage {
+	/// The asset, the other account and the approved amount of each approval of this page.
+	pub approvals: Vec<(AssetId, AccountId, AssetBalance)>,
+	/// The asset and account to pass as `start_after` for the next page, or `None` if this is the
+	/// last one.
+	pub next: Option<(AssetId, AccountId)>,
+}
+
+impl ApprovalPage {
+	/// The page of at most `limit` approvals out of `approvals`, which holds one more approval
+	/// than the page if there is a next one.
+	fn new(mut approvals: Vec<(AssetId, AccountId, AssetBalance)>, limit: u32) -> Self {
+		let next = if approvals.len() > limit as usize {
+			approvals.truncate(limit as usize);
+			approvals.last().map(|(id, who, _)| (*id, who.clone()))
+		} else {
+			None
+		};
+		ApprovalPage { approvals, next }
+	}
+}
+
+/// The largest holders of an asset, as last computed by the offchain worker.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopHolders {
+	/// The block whose state the snapshot was taken of.
+	pub block_number: BlockNumber,
+	/// The largest holders and their balances, largest first.
+	pub holders: Vec<(AccountId, AssetBalance)>,
+}
+
+/// Holders and approvals of ERC20 assets.
+///
+/// Every method takes the `registry` of the asset last, and defaults to the community assets.
+/// Amounts are decimal strings, as they may exceed what JSON numbers hold exactly.
+#[rpc]
+pub trait Erc20HoldersApi<BlockHash> {
+	/// Up to `limit` holders of `asset_id` and their balances at block `at`, in storage order,
+	/// starting after the holder `start_after`.
+	#[rpc(name = "erc20_holders")]
+	fn holders(
+		&self,
+		asset_id: AssetId,
+		start_after: Option<AccountId>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<AccountPage>;
+
+	/// Up to `limit` delegates approved by `owner` to spend `asset_id` and their approved amounts
+	/// at block `at`, in storage order, starting after the delegate `start_after`.
+	#[rpc(name = "erc20_approvalsOf")]
+	fn approvals_of(
+		&self,
+		asset_id: AssetId,
+		owner: AccountId,
+		start_after: Option<AccountId>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<AccountPage>;
+
+	/// Up to `limit` approvals by `owner` across all assets at block `at`, as the asset, the
+	/// delegate and the approved amount, in storage order, starting after the asset and delegate
+	/// `start_after`.
+	#[rpc(name = "erc20_approvalsByOwner")]
+	fn approvals_by_owner(
+		&self,
+		owner: AccountId,
+		start_after: Option<(AssetId, AccountId)>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<ApprovalPage>;
+
+	/// Up to `limit` approvals to `delegate` across all assets at block `at`, as the asset, the
+	/// owner and the approved amount, in storage order, starting after the asset and owner
+	/// `start_after`.
+	#[rpc(name = "erc20_approvalsByDelegate")]
+	fn approvals_by_delegate(
+		&self,
+		delegate: AccountId,
+		start_after: Option<(AssetId, AccountId)>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<ApprovalPage>;
+
+	/// The `n` largest holders of `asset_id` from the latest snapshot of the offchain worker, or
+	/// `None` if it has taken none yet.
+	#[rpc(name = "erc20_topHolders")]
+	fn top_holders(
+		&self,
+		asset_id: AssetId,
+		n: Option<u32>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<Option<TopHolders>>;
+}
+
+/// Serves `Erc20HoldersApi` through the runtime API and offchain storage.
+pub struct Erc20Holders<C, B> {
+	client: Arc<C>,
+	backend: Arc<B>,
+}
+
+impl<C, B> Erc20Holders<C, B> {
+	/// Create a new `Erc20Holders` querying `client` and the offchain storage of `backend`.
+	pub fn new(client: Arc<C>, backend: Arc<B>) -> Self {
+		Erc20Holders { client, backend }
+	}
+}
+
+type TopHoldersSnapshot<Balance> =
+	pallet_template::TopHoldersSnapshot<BlockNumber, AccountId, Balance>;
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(2),
+		message: "Unable to query ERC20 holders.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, B> Erc20HoldersApi<Hash> for Erc20Holders<C, B>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: Erc20HoldersRuntimeApi<Block, AssetRegistry, AssetId, AccountId, AssetBalance>,
+	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+{
+	fn holders(
+		&self,
+		asset_id: AssetId,
+		start_after: Option<AccountId>,
+		limit: Option<u32>,
+		at: Option<Hash>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<AccountPage> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let limit = limit.unwrap_or(MAX_ACCOUNT_PAGE).clamp(1, MAX_ACCOUNT_PAGE);
+		let accounts = self
+			.client
+			.runtime_api()
+			.holders(&at, registry.unwrap_or_default(), asset_id, start_after, limit + 1)
+			.map_err(runtime_error)?;
+		Ok(AccountPage::new(accounts, limit))
+	}
+
+	fn approvals_of(
+		&self,
+		asset_id: AssetId,
+		owner: AccountId,
+		start_after: Option<AccountId>,
+		limit: Option<u32>,
+		at: Option<Hash>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<AccountPage> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let limit = limit.unwrap_or(MAX_ACCOUNT_PAGE).clamp(1, MAX_ACCOUNT_PAGE);
+		let registry = registry.unwrap_or_default();
+		let accounts = self
+			.client
+			.runtime_api()
+			.approvals_of(&at, registry, asset_id, owner, start_after, limit + 1)
+			.map_err(runtime_error)?;
+		Ok(AccountPage::new(accounts, limit))
+	}
+
+	fn approvals_by_owner(
+		&self,
+		owner: AccountId,
+		start_after: Option<(AssetId, AccountId)>,
+		limit: Option<u32>,
+		at: Option<Hash>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<ApprovalPage> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let limit = limit.unwrap_or(MAX_ACCOUNT_PAGE).clamp(1, MAX_ACCOUNT_PAGE);
+		let registry = registry.unwrap_or_default();
+		let approvals = self
+			.client
+			.runtime_api()
+			.approvals_by_owner(&at, registry, owner, start_after, limit + 1)
+			.map_err(runtime_error)?;
+		Ok(ApprovalPage::new(approvals, limit))
+	}
+
+	fn approvals_by_delegate(
+		&self,
+		delegate: AccountId,
+		start_after: Option<(AssetId, AccountId)>,
+		limit: Option<u32>,
+		at: Option<Hash>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<ApprovalPage> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let limit = limit.unwrap_or(MAX_ACCOUNT_PAGE).clamp(1, MAX_ACCOUNT_PAGE);
+		let registry = registry.unwrap_or_default();
+		let approvals = self
+			.client
+			.runtime_api()
+			.approvals_by_delegate(&at, registry, delegate, start_after, limit + 1)
+			.map_err(runtime_error)?;
+		Ok(ApprovalPage::new(approvals, limit))
+	}
+
+	fn top_holders(
+		&self,
+		asset_id: AssetId,
+		n: Option<u32>,
+		registry: Option<AssetRegistry>,
+	) -> RpcResult<Option<TopHolders>> {
+		let registry = registry.unwrap_or_default();
+		let pallet = match registry {
+			AssetRegistry::Community => TemplateModule::name(),
+			AssetRegistry::Stable => StableAssets::name(),
+		};
+		let key = pallet_template::top_holders_key(pallet, &asset_id);
+		let snapshot = match self
+			.backend
+			.offchain_storage()
+			.and_then(|storage| storage.get(sp_offchain::STORAGE_PREFIX, &key))
+		{
+			Some(snapshot) => snapshot,
+			None => return Ok(None),
+		};
+		let (block_number, holders) = match registry {
+			AssetRegistry::Community => {
+				let snapshot = TopHoldersSnapshot::<AssetBalance>::decode(&mut &snapshot[..])
+					.map_err(runtime_error)?;
+				(snapshot.block_number, snapshot.holders)
+			},
+			AssetRegistry::Stable => {
+				let snapshot = TopHoldersSnapshot::<StableBalance>::decode(&mut &snapshot[..])
+					.map_err(runtime_error)?;
+				let holders = snapshot
+					.holders
+					.into_iter()
+					.map(|(who, balance)| (who, balance.into()))
+					.collect::<Vec<_>>();
+				(snapshot.block_number, holders)
+			},
+		};
+		let n = n.unwrap_or(MAX_ACCOUNT_PAGE).min(MAX_ACCOUNT_PAGE) as usize;
+		Ok(Some(TopHolders { block_number, holders: holders.into_iter().take(n).collect() }))
+	}
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P>(deps: FullDeps<C, P>) -> jsonrpc_core::IoHandler<sc_rpc::Metadata>
+pub fn create_full<C, P, B>(deps: FullDeps<C, P, B>) -> jsonrpc_core::IoHandler<sc_rpc::Metadata>
 where
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: Erc20HoldersRuntimeApi<Block, AssetRegistry, AssetId, AccountId, AssetBalance>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
+	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 {
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, pool, backend, deny_unsafe, erc20_index } = deps;
 
 	io.extend_with(SystemApi::to_delegate(FullSystem::new(client.clone(), pool, deny_unsafe)));
 
 	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone())));
 
+	io.extend_with(Erc20HoldersApi::to_delegate(Erc20Holders::new(client.clone(), backend)));
+
+	if let Some(index) = erc20_index {
+		io.extend_with(Erc20HistoryApi::to_delegate(Erc20History::new(index)));
+	}
+
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
 	// to call into the runtime.
@@ -137,8 +137,11 @@ fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
 	Err("Remote Keystore not supported.")
 }
 
-/// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+/// Builds a new service for a full client, indexing ERC20 history if `erc20_indexer` is set.
+pub fn new_full(
+	mut config: Configuration,
+	erc20_indexer: bool,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -161,6 +164,30 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		};
 	}
 
+	let erc20_index = if erc20_indexer {
+		let path = config
+			.base_path
+			.as_ref()
+			.ok_or_else(|| ServiceError::Other("The ERC20 indexer needs a base path.".into()))?
+			.config_dir(config.chain_spec.id())
+			.join("erc20_index");
+		let index = crate::indexer::Erc20Index::open(&path).map_err(|e| {
+			ServiceError::Other(format!(
+				"Error opening the ERC20 index at {}: {}",
+				path.display(),
+				e
+			))
+		})?;
+		let index = Arc::new(index);
+		task_manager.spawn_handle().spawn_blocking(
+			"erc20-indexer",
+			crate::indexer::run::<_, FullBackend>(client.clone(), index.clone()),
+		);
+		Some(index)
+	} else {
+		None
+	};
+
 	config.network.extra_sets.push(sc_finality_grandpa::grandpa_peers_set_config());
 
 	let (network, system_rpc_tx, network_starter) =
@@ -193,10 +220,16 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let backend = backend.clone();
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				backend: backend.clone(),
+				deny_unsafe,
+				erc20_index: erc20_index.clone(),
+			};
 
 			crate::rpc::create_full(deps)
 		})
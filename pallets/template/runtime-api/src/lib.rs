@@ -0,0 +1,124 @@
+//! Runtime API definition for the ERC20 assets pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+// A runtime may run several instances of the pallet, each a registry of its own assets. Every call
+// names the `Registry` it queries, and `Balance` must hold the balances of all of them.
+sp_api::decl_runtime_apis! {
+	/// Quotes and asset ids of ERC20 assets.
+	///
+	/// Version 2 added the `registry` to every call, and version 3 added `call_abi`.
+	#[api_version(3)]
+	pub trait Erc20Api<Registry, AssetId, Balance>
+	where
+		Registry: Codec,
+		AssetId: Codec,
+		Balance: Codec,
+	{
+		/// The amount of `asset_out` received for selling exactly `amount_in` of `asset_in`
+		/// through their pool, or `None` if there is no pool able to fill the trade.
+		fn quote_exact_in(
+			registry: Registry,
+			asset_in: AssetId,
+			asset_out: AssetId,
+			amount_in: Balance,
+		) -> Option<Balance>;
+
+		/// The amount of `asset_in` required to buy exactly `amount_out` of `asset_out` through
+		/// their pool, or `None` if there is no pool able to fill the trade.
+		fn quote_exact_out(
+			registry: Registry,
+			asset_in: AssetId,
+			asset_out: AssetId,
+			amount_out: Balance,
+		) -> Option<Balance>;
+
+		/// The id the next `create_auto` call will allocate.
+		fn next_asset_id(registry: Registry) -> AssetId;
+
+		/// The ABI encoded result of the ERC20 view function call `input` on `asset`: `name`,
+		/// `symbol`, `decimals`, `totalSupply`, `balanceOf` or `allowance`.
+		fn call_abi(
+			registry: Registry,
+			asset: AssetId,
+			input: Vec<u8>,
+		) -> Result<Vec<u8>, DispatchError>;
+	}
+
+	/// Paged enumeration of the holders and approvals of ERC20 assets.
+	///
+	/// Version 2 added the `registry` to every call, version 3 `approvals_by_owner` and
+	/// `approvals_by_delegate`, and version 4 `allowance`.
+	#[api_version(4)]
+	pub trait Erc20HoldersApi<Registry, AssetId, AccountId, Balance>
+	where
+		Registry: Codec,
+		AssetId: Codec,
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// Up to `limit` holders of `asset` and their balances, in storage order, starting after
+		/// the holder `start_after`.
+		fn holders(
+			registry: Registry,
+			asset: AssetId,
+			start_after: Option<AccountId>,
+			limit: u32,
+		) -> Vec<(AccountId, Balance)>;
+
+		/// Up to `limit` delegates approved by `owner` to spend `asset` and their approved amounts,
+		/// in storage order, starting after the delegate `start_after`.
+		fn approvals_of(
+			registry: Registry,
+			asset: AssetId,
+			owner: AccountId,
+			start_after: Option<AccountId>,
+			limit: u32,
+		) -> Vec<(AccountId, Balance)>;
+
+		/// Up to `limit` approvals by `owner` across all assets, as the asset, the delegate and the
+		/// approved amount, in storage order, starting after the `(asset, delegate)` `start_after`.
+		#[api_version(3)]
+		fn approvals_by_owner(
+			registry: Registry,
+			owner: AccountId,
+			start_after: Option<(AssetId, AccountId)>,
+			limit: u32,
+		) -> Vec<(AssetId, AccountId, Balance)>;
+
+		/// Up to `limit` approvals to `delegate` across all assets, as the asset, the owner and the
+		/// approved amount, in storage order, starting after the `(asset, owner)` `start_after`.
+		#[api_version(3)]
+		fn approvals_by_delegate(
+			registry: Registry,
+			delegate: AccountId,
+			start_after: Option<(AssetId, AccountId)>,
+			limit: u32,
+		) -> Vec<(AssetId, AccountId, Balance)>;
+
+		/// The amount of `asset` that `delegate` may still transfer from `owner`, and whether the
+		/// approval is unlimited, in which case the amount is the largest balance of the registry.
+		#[api_version(4)]
+		fn allowance(
+			registry: Registry,
+			asset: AssetId,
+			owner: AccountId,
+			delegate: AccountId,
+		) -> (Balance, bool);
+	}
+
+	/// Diagnostics for the ERC20 assets pallet. Only development runtimes implement this, as the
+	/// calls read the whole ledger.
+	pub trait Erc20DevApi<Report>
+	where
+		Report: Codec,
+	{
+		/// Check every asset, account, approval and metadata entry of every registry for
+		/// inconsistencies.
+		fn integrity_report() -> Report;
+	}
+}
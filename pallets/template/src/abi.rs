@@ -0,0 +1,238 @@
+//! The Ethereum ABI of the ERC20 functions, for clients that speak it rather than SCALE.
+//!
+//! `dispatch_abi` routes `transfer`, `approve` and `transferFrom` to the matching dispatchables,
+//! and `call_abi` answers `name`, `symbol`, `decimals`, `totalSupply`, `balanceOf` and `allowance`.
+//! Addresses are mapped to accounts by `Config::AddressMapping`, and amounts are `uint256`.
+
+use super::*;
+use frame_support::{traits::Get, transactional, weights::Weight};
+use sp_runtime::RuntimeDebug;
+
+pub const SELECTOR_NAME: [u8; 4] = [0x06, 0xfd, 0xde, 0x03];
+pub const SELECTOR_SYMBOL: [u8; 4] = [0x95, 0xd8, 0x9b, 0x41];
+pub const SELECTOR_DECIMALS: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+pub const SELECTOR_TOTAL_SUPPLY: [u8; 4] = [0x18, 0x16, 0x0d, 0xdd];
+pub const SELECTOR_BALANCE_OF: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+pub const SELECTOR_TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+pub const SELECTOR_APPROVE: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+pub const SELECTOR_ALLOWANCE: [u8; 4] = [0xdd, 0x62, 0xed, 0x3e];
+pub const SELECTOR_TRANSFER_FROM: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
+
+/// `keccak256("Transfer(address,address,uint256)")`
+pub const TOPIC_TRANSFER: [u8; 32] = [
+	0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa,
+	0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef,
+];
+/// `keccak256("Approval(address,address,uint256)")`
+pub const TOPIC_APPROVAL: [u8; 32] = [
+	0x8c, 0x5b, 0xe1, 0xe5, 0xeb, 0xec, 0x7d, 0x5b, 0xd1, 0x4d, 0x71, 0x42, 0x7d, 0x1e, 0x84, 0xf3,
+	0xdd, 0x03, 0x14, 0xc0, 0xf7, 0xb2, 0x29, 0x1e, 0x5b, 0x20, 0x0a, 0xc8, 0xc7, 0xc3, 0xb9, 0x25,
+];
+
+/// A call of an ERC20 function, decoded from its ABI encoding.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum Erc20Call {
+	/// `name()`
+	Name,
+	/// `symbol()`
+	Symbol,
+	/// `decimals()`
+	Decimals,
+	/// `totalSupply()`
+	TotalSupply,
+	/// `balanceOf(address owner)`
+	BalanceOf(H160),
+	/// `allowance(address owner, address spender)`
+	Allowance(H160, H160),
+	/// `transfer(address to, uint256 amount)`
+	Transfer(H160, U256),
+	/// `approve(address spender, uint256 amount)`
+	Approve(H160, U256),
+	/// `transferFrom(address from, address to, uint256 amount)`
+	TransferFrom(H160, H160, U256),
+}
+
+/// Why the ABI encoding of a call could not be decoded.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum AbiError {
+	/// The input is shorter than a selector, or its selector is not one of an ERC20 function.
+	UnsupportedSelector,
+	/// The arguments are missing or not valid for the function.
+	InvalidInput,
+}
+
+impl<T, I> From<AbiError> for Error<T, I> {
+	fn from(error: AbiError) -> Self {
+		match error {
+			AbiError::UnsupportedSelector => Error::UnsupportedSelector,
+			AbiError::InvalidInput => Error::InvalidAbiInput,
+		}
+	}
+}
+
+impl Erc20Call {
+	/// Decode `input`: a function selector, then its arguments one 32 byte word each.
+	pub fn decode(input: &[u8]) -> Result<Self, AbiError> {
+		if input.len() < 4 {
+			return Err(AbiError::UnsupportedSelector)
+		}
+		let args = Args(&input[4..]);
+		Ok(match [input[0], input[1], input[2], input[3]] {
+			SELECTOR_NAME => Erc20Call::Name,
+			SELECTOR_SYMBOL => Erc20Call::Symbol,
+			SELECTOR_DECIMALS => Erc20Call::Decimals,
+			SELECTOR_TOTAL_SUPPLY => Erc20Call::TotalSupply,
+			SELECTOR_BALANCE_OF => Erc20Call::BalanceOf(args.address(0)?),
+			SELECTOR_ALLOWANCE => Erc20Call::Allowance(args.address(0)?, args.address(1)?),
+			SELECTOR_TRANSFER => Erc20Call::Transfer(args.address(0)?, args.uint(1)?),
+			SELECTOR_APPROVE => Erc20Call::Approve(args.address(0)?, args.uint(1)?),
+			SELECTOR_TRANSFER_FROM =>
+				Erc20Call::TransferFrom(args.address(0)?, args.address(1)?, args.uint(2)?),
+			_ => return Err(AbiError::UnsupportedSelector),
+		})
+	}
+
+	/// Whether the function only reads state.
+	pub fn is_view(&self) -> bool {
+		!matches!(
+			self,
+			Erc20Call::Transfer(..) | Erc20Call::Approve(..) | Erc20Call::TransferFrom(..)
+		)
+	}
+}
+
+/// The ABI encoded arguments of a call.
+struct Args<'a>(&'a [u8]);
+
+impl Args<'_> {
+	fn word(&self, index: usize) -> Result<&[u8], AbiError> {
+		self.0.get(index * 32..(index + 1) * 32).ok_or(AbiError::InvalidInput)
+	}
+
+	fn address(&self, index: usize) -> Result<H160, AbiError> {
+		let word = self.word(index)?;
+		if word[..12].iter().any(|b| *b != 0) {
+			return Err(AbiError::InvalidInput)
+		}
+		Ok(H160::from_slice(&word[12..]))
+	}
+
+	fn uint(&self, index: usize) -> Result<U256, AbiError> {
+		Ok(U256::from_big_endian(self.word(index)?))
+	}
+}
+
+/// The ABI encoding of the `uint256` `value`.
+pub fn encode_uint(value: U256) -> Vec<u8> {
+	let mut word = [0u8; 32];
+	value.to_big_endian(&mut word);
+	word.to_vec()
+}
+
+/// The ABI encoding of the `bool` `value`.
+pub fn encode_bool(value: bool) -> Vec<u8> {
+	encode_uint(if value { U256::one() } else { U256::zero() })
+}
+
+/// The ABI encoding of a single `string` or `bytes` return value of `bytes`.
+pub fn encode_string(bytes: &[u8]) -> Vec<u8> {
+	let mut output = encode_uint(U256::from(32u32));
+	output.extend(encode_uint(bytes.len().into()));
+	output.extend_from_slice(bytes);
+	output.resize(output.len() + (32 - bytes.len() % 32) % 32, 0);
+	output
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// The ABI encoded result of the ERC20 view function whose call `input` encodes, on asset
+	/// `id`.
+	pub fn call_abi(id: T::AssetId, input: &[u8]) -> Result<Vec<u8>, DispatchError> {
+		let call = Erc20Call::decode(input).map_err(Error::<T, I>::from)?;
+		Self::query_erc20(id, call)
+	}
+
+	/// The ABI encoded result of the ERC20 view function `call` on asset `id`.
+	pub fn query_erc20(id: T::AssetId, call: Erc20Call) -> Result<Vec<u8>, DispatchError> {
+		let account = T::AddressMapping::convert;
+		Ok(match call {
+			Erc20Call::Name => encode_string(&Metadata::<T, I>::get(id).name),
+			Erc20Call::Symbol => encode_string(&Metadata::<T, I>::get(id).symbol),
+			Erc20Call::Decimals => encode_uint(Metadata::<T, I>::get(id).decimals.into()),
+			Erc20Call::TotalSupply => encode_uint(Self::total_supply(id).into()),
+			Erc20Call::BalanceOf(owner) => encode_uint(Self::balance(id, account(owner)).into()),
+			Erc20Call::Allowance(owner, spender) => {
+				let approval = Approvals::<T, I>::get((id, account(owner), account(spender)));
+				encode_uint(match approval {
+					Some(approval) if approval.unlimited => U256::MAX,
+					Some(approval) => approval.amount.into(),
+					None => U256::zero(),
+				})
+			},
+			_ => return Err(Error::<T, I>::StateChangingFunction.into()),
+		})
+	}
+
+	/// Dispatch the state changing ERC20 function `call` on asset `id` with `who` as the signed
+	/// origin, and return its ABI encoded result.
+	///
+	/// `approve` replaces an existing approval, as in ERC20, where `approve_transfer` adds to it.
+	/// An `approve` of the maximum `uint256` is unlimited, and `allowance` reports it as such.
+	#[transactional]
+	pub fn dispatch_erc20(
+		who: T::AccountId,
+		id: T::AssetId,
+		call: Erc20Call,
+	) -> Result<Vec<u8>, DispatchError> {
+		let origin = || frame_system::RawOrigin::Signed(who.clone()).into();
+		let lookup = |address| T::Lookup::unlookup(T::AddressMapping::convert(address));
+		match call {
+			Erc20Call::Transfer(to, amount) =>
+				Self::transfer(origin(), id, lookup(to), Self::abi_amount(amount)?)?,
+			Erc20Call::Approve(spender, amount) => {
+				let delegate = T::AddressMapping::convert(spender);
+				if Approvals::<T, I>::contains_key((id, &who, &delegate)) {
+					Self::cancel_approval(origin(), id, T::Lookup::unlookup(delegate.clone()))?;
+				}
+				if amount == U256::MAX {
+					Self::approve_unlimited(origin(), id, T::Lookup::unlookup(delegate))?;
+				} else {
+					let amount = Self::abi_amount(amount)?;
+					if !amount.is_zero() {
+						let delegate = T::Lookup::unlookup(delegate);
+						Self::approve_transfer(origin(), id, delegate, amount)?;
+					}
+				}
+			},
+			Erc20Call::TransferFrom(from, to, amount) => {
+				let amount = Self::abi_amount(amount)?;
+				Self::transfer_approved(origin(), id, lookup(from), lookup(to), amount)
+					.map_err(|e| e.error)?;
+			},
+			_ => return Err(Error::<T, I>::ReadOnlyFunction.into()),
+		}
+		Ok(encode_bool(true))
+	}
+
+	/// The weight of the ERC20 function call `input` encodes: that of the dispatchable it is
+	/// routed to, or of a single read for views and input that does not decode.
+	pub fn abi_weight(input: &[u8]) -> Weight {
+		match Erc20Call::decode(input) {
+			Ok(Erc20Call::Transfer(..)) => T::WeightInfo::transfer(),
+			Ok(Erc20Call::Approve(_, amount)) => T::DbWeight::get()
+				.reads(1)
+				.saturating_add(T::WeightInfo::cancel_approval())
+				.saturating_add(if amount == U256::MAX {
+					T::WeightInfo::approve_unlimited()
+				} else {
+					T::WeightInfo::approve_transfer()
+				}),
+			Ok(Erc20Call::TransferFrom(..)) => T::WeightInfo::transfer_approved(),
+			_ => T::DbWeight::get().reads(1),
+		}
+	}
+
+	/// The `uint256` `amount` as a balance, if it fits.
+	fn abi_amount(amount: U256) -> Result<T::Balance, DispatchError> {
+		T::Balance::try_from(amount).map_err(|_| ArithmeticError::Overflow.into())
+	}
+}
@@ -0,0 +1,287 @@
+//! Airdrops of assets to many accounts, committed to by the root of a Merkle tree.
+//!
+//! The creator of an airdrop moves its budget, by minting or from their own balance, into a
+//! keyless account derived from `T::PalletId` and the airdrop id. Every recipient `who` entitled
+//! to `amount` is a leaf `airdrop_leaf(index, who, amount)` of the tree, and claims it with an
+//! unsigned `claim_airdrop` carrying the path from the leaf to the root, so that recipients need
+//! no native balance to pay fees. Claimed leaves are kept in a bitmap of 128 leaves per word.
+//! Once the airdrop expires, its creator reclaims what was not claimed.
+//!
+//! Inner nodes hash the concatenation of their children in ascending order, so that proofs need
+//! not say on which side each sibling sits. The last node of a level without a sibling moves up a
+//! level unchanged. [`MerkleTree`] builds trees and proofs the same way.
+
+use super::*;
+use codec::Encode;
+use frame_support::traits::PalletInfoAccess;
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+	traits::{AccountIdConversion, UniqueSaturatedInto},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+
+/// A node of an airdrop's Merkle tree.
+pub type MerkleHash = [u8; 32];
+
+/// The longest proof a claim can carry. Leaf indices are `u32`, so no tree is deeper.
+pub const MAX_PROOF_LENGTH: usize = 32;
+
+/// The number of leaves whose claims are kept in one word of the claimed bitmap.
+const LEAVES_PER_WORD: u32 = 128;
+
+/// The leaf entitling `who` to `amount`. Leaves and inner nodes are hashed under different
+/// prefixes, so that no inner node can pass for a leaf.
+pub fn airdrop_leaf<AccountId: Encode, Balance: Encode>(
+	index: u32,
+	who: &AccountId,
+	amount: Balance,
+) -> MerkleHash {
+	blake2_256(&(0u8, index, who, amount).encode())
+}
+
+fn merkle_parent(a: &MerkleHash, b: &MerkleHash) -> MerkleHash {
+	let (low, high) = if a <= b { (a, b) } else { (b, a) };
+	blake2_256(&(1u8, low, high).encode())
+}
+
+/// The root of the tree that `proof` proves `leaf` to be part of.
+pub fn merkle_root_from_proof(leaf: MerkleHash, proof: &[MerkleHash]) -> MerkleHash {
+	proof.iter().fold(leaf, |node, sibling| merkle_parent(&node, sibling))
+}
+
+/// A Merkle tree of airdrop leaves, with all of its levels.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct MerkleTree {
+	/// The levels of the tree, from the leaves up to the root.
+	levels: Vec<Vec<MerkleHash>>,
+}
+
+impl MerkleTree {
+	/// Build the tree of `leaves`, in order.
+	pub fn new(leaves: Vec<MerkleHash>) -> Self {
+		let mut levels = vec![leaves];
+		while levels[levels.len() - 1].len() > 1 {
+			let level = levels[levels.len() - 1]
+				.chunks(2)
+				.map(|pair| match pair {
+					[a, b] => merkle_parent(a, b),
+					[a] => *a,
+					_ => unreachable!("chunks are of one or two nodes; qed"),
+				})
+				.collect();
+			levels.push(level);
+		}
+		Self { levels }
+	}
+
+	/// The root of the tree, or `None` if it has no leaves.
+	pub fn root(&self) -> Option<MerkleHash> {
+		self.levels[self.levels.len() - 1].first().copied()
+	}
+
+	/// The proof that the leaf at `index` is part of the tree, or `None` if there is no such leaf.
+	pub fn proof(&self, index: usize) -> Option<Vec<MerkleHash>> {
+		if index >= self.levels[0].len() {
+			return None
+		}
+		let mut proof = Vec::new();
+		let mut index = index;
+		for level in &self.levels[..self.levels.len() - 1] {
+			proof.extend(level.get(index ^ 1).copied());
+			index /= 2;
+		}
+		Some(proof)
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// The account holding the unclaimed budget of airdrop `id`.
+	pub fn airdrop_account(id: AirdropId) -> T::AccountId {
+		T::PalletId::get().into_sub_account((*b"airdrop", id))
+	}
+
+	/// Whether the leaf at `index` of airdrop `id` was claimed.
+	pub fn is_claimed(id: AirdropId, index: u32) -> bool {
+		let word = AirdropClaims::<T, I>::get(id, index / LEAVES_PER_WORD);
+		word & (1u128 << (index % LEAVES_PER_WORD)) != 0
+	}
+
+	/// The number of words of the claimed bitmap of an airdrop to `recipients` accounts.
+	pub fn claim_words(recipients: u32) -> u32 {
+		recipients / LEAVES_PER_WORD + (recipients % LEAVES_PER_WORD != 0) as u32
+	}
+
+	pub(super) fn do_create_airdrop(
+		creator: T::AccountId,
+		asset: T::AssetId,
+		merkle_root: MerkleHash,
+		recipients: u32,
+		total: T::Balance,
+		expires_at: T::BlockNumber,
+		mint: bool,
+	) -> DispatchResult {
+		ensure!(!total.is_zero(), Error::<T, I>::BalanceZero);
+		ensure!(
+			expires_at > frame_system::Pallet::<T>::block_number(),
+			Error::<T, I>::AirdropExpired
+		);
+		let details = Asset::<T, I>::get(asset).ok_or(Error::<T, I>::Unknown)?;
+		ensure!(details.status == AssetStatus::Live, Error::<T, I>::AssetNotLive);
+		ensure!(details.is_sufficient, Error::<T, I>::AirdropNotSufficient);
+		ensure!(!mint || creator == details.issuer, Error::<T, I>::NoPermission);
+
+		let id = NextAirdropId::<T, I>::get();
+		let next_id = id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+		let deposit = T::AirdropDeposit::get();
+		T::Currency::reserve(&creator, deposit)?;
+
+		let account = Self::airdrop_account(id);
+		// Like a pool account, the airdrop account has no native balance; its provider reference
+		// keeps it holding the budget should the asset stop being sufficient.
+		frame_system::Pallet::<T>::inc_providers(&account);
+		if mint {
+			Self::do_mint(asset, &account, total, Some(creator.clone()))?;
+		} else {
+			let f = TransferFlags { keep_alive: false, best_effort: false, burn_dust: false };
+			Self::do_transfer(asset, &creator, &account, total, None, Some(&creator), f)?;
+		}
+
+		NextAirdropId::<T, I>::put(next_id);
+		Airdrops::<T, I>::insert(
+			id,
+			AirdropInfo {
+				creator: creator.clone(),
+				asset,
+				merkle_root,
+				recipients,
+				total,
+				claimed: Zero::zero(),
+				deposit,
+				expires_at,
+			},
+		);
+		Self::deposit_event(Event::AirdropCreated(id, asset, creator, merkle_root, total));
+		Ok(())
+	}
+
+	/// Check that `who` may claim `amount` of airdrop `id` as the leaf at `index`, and that the
+	/// transfer of the claim would succeed.
+	pub(super) fn check_claim(
+		id: AirdropId,
+		index: u32,
+		who: &T::AccountId,
+		amount: T::Balance,
+		proof: &[MerkleHash],
+	) -> Result<AirdropInfoOf<T, I>, DispatchError> {
+		let info = Airdrops::<T, I>::get(id).ok_or(Error::<T, I>::UnknownAirdrop)?;
+		ensure!(
+			frame_system::Pallet::<T>::block_number() < info.expires_at,
+			Error::<T, I>::AirdropExpired
+		);
+		ensure!(
+			index < info.recipients && proof.len() <= MAX_PROOF_LENGTH,
+			Error::<T, I>::InvalidProof
+		);
+		ensure!(!Self::is_claimed(id, index), Error::<T, I>::AlreadyClaimed);
+		ensure!(
+			merkle_root_from_proof(airdrop_leaf(index, who, amount), proof) == info.merkle_root,
+			Error::<T, I>::InvalidProof
+		);
+		ensure!(!amount.is_zero(), Error::<T, I>::BalanceZero);
+
+		let account = Self::airdrop_account(id);
+		let f = DebitFlags { keep_alive: false, best_effort: false };
+		let debit = Self::prep_debit(info.asset, &account, amount, f)?;
+		Self::prep_credit(info.asset, who, amount, debit, false)?;
+		Ok(info)
+	}
+
+	pub(super) fn do_claim_airdrop(
+		id: AirdropId,
+		index: u32,
+		who: T::AccountId,
+		amount: T::Balance,
+		proof: &[MerkleHash],
+	) -> DispatchResult {
+		let mut info = Self::check_claim(id, index, &who, amount, proof)?;
+		let f = TransferFlags { keep_alive: false, best_effort: false, burn_dust: false };
+		Self::do_transfer(info.asset, &Self::airdrop_account(id), &who, amount, None, None, f)?;
+
+		AirdropClaims::<T, I>::mutate(id, index / LEAVES_PER_WORD, |word| {
+			*word |= 1u128 << (index % LEAVES_PER_WORD)
+		});
+		info.claimed = info.claimed.saturating_add(amount);
+		let asset = info.asset;
+		Airdrops::<T, I>::insert(id, info);
+		Self::deposit_event(Event::AirdropClaimed(id, asset, who, amount));
+		Ok(())
+	}
+
+	pub(super) fn do_reclaim_airdrop(
+		who: T::AccountId,
+		id: AirdropId,
+		claim_words: u32,
+	) -> DispatchResult {
+		let info = Airdrops::<T, I>::get(id).ok_or(Error::<T, I>::UnknownAirdrop)?;
+		ensure!(who == info.creator, Error::<T, I>::NoPermission);
+		ensure!(
+			frame_system::Pallet::<T>::block_number() >= info.expires_at,
+			Error::<T, I>::AirdropNotExpired
+		);
+		ensure!(Self::claim_words(info.recipients) <= claim_words, Error::<T, I>::BadWitness);
+
+		let account = Self::airdrop_account(id);
+		// An asset being destroyed takes the remaining budget with it; the account is removed
+		// with the other accounts of the asset.
+		let destroying = Asset::<T, I>::get(info.asset)
+			.map_or(true, |details| details.status == AssetStatus::Destroying);
+		let mut remaining = Self::balance(info.asset, &account);
+		if destroying {
+			remaining = Zero::zero();
+		} else if !remaining.is_zero() {
+			let f = TransferFlags { keep_alive: false, best_effort: false, burn_dust: false };
+			Self::do_transfer(info.asset, &account, &info.creator, remaining, None, None, f)?;
+		}
+		// Nothing depends on the provider of the emptied account any more.
+		let _ = frame_system::Pallet::<T>::dec_providers(&account);
+
+		T::Currency::unreserve(&info.creator, info.deposit);
+		AirdropClaims::<T, I>::remove_prefix(id, None);
+		Airdrops::<T, I>::remove(id);
+		Self::deposit_event(Event::AirdropReclaimed(id, info.asset, info.creator, remaining));
+		Ok(())
+	}
+
+	/// Validate an unsigned `claim_airdrop`, which is only valid while it would succeed.
+	pub(super) fn validate_claim(
+		id: AirdropId,
+		index: u32,
+		who: &T::AccountId,
+		amount: T::Balance,
+		proof: &[MerkleHash],
+	) -> TransactionValidity {
+		let info = Self::check_claim(id, index, who, amount, proof).map_err(|e| {
+			let invalid = if e == Error::<T, I>::AirdropExpired.into() ||
+				e == Error::<T, I>::AlreadyClaimed.into()
+			{
+				InvalidTransaction::Stale
+			} else if e == Error::<T, I>::InvalidProof.into() {
+				InvalidTransaction::BadProof
+			} else {
+				InvalidTransaction::Call
+			};
+			TransactionValidityError::from(invalid)
+		})?;
+		let now = frame_system::Pallet::<T>::block_number();
+		let longevity: u64 = info.expires_at.saturating_sub(now).unique_saturated_into();
+
+		ValidTransaction::with_tag_prefix("Erc20Airdrop")
+			.priority(T::AirdropUnsignedPriority::get())
+			.and_provides((<Self as PalletInfoAccess>::name(), id, index))
+			.longevity(longevity)
+			.propagate(true)
+			.build()
+	}
+}
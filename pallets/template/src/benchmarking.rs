@@ -0,0 +1,855 @@
+//! Benchmarks for the ERC20 assets pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{
+	account, benchmarks_instance_pallet, impl_benchmark_test_suite, whitelist_account,
+	whitelisted_caller,
+};
+use frame_support::{
+	dispatch::UnfilteredDispatchable,
+	traits::{EnsureOrigin, Get},
+};
+use frame_system::RawOrigin as SystemOrigin;
+use sp_std::prelude::*;
+
+use crate::Pallet as Assets;
+
+const SEED: u32 = 0;
+/// The most accounts or approvals removed by a single `destroy_*` call that is benchmarked.
+const MAX_DESTROY_ITEMS: u32 = 1_000;
+
+fn create_default_asset<T: Config<I>, I: 'static>(
+	is_sufficient: bool,
+) -> (T::AccountId, <T::Lookup as StaticLookup>::Source) {
+	let caller: T::AccountId = whitelisted_caller();
+	let caller_lookup = T::Lookup::unlookup(caller.clone());
+	let root = SystemOrigin::Root.into();
+	assert!(Assets::<T, I>::force_create(
+		root,
+		Default::default(),
+		caller_lookup.clone(),
+		is_sufficient,
+		1u32.into(),
+	)
+	.is_ok());
+	(caller, caller_lookup)
+}
+
+fn create_default_minted_asset<T: Config<I>, I: 'static>(
+	is_sufficient: bool,
+	amount: T::Balance,
+) -> (T::AccountId, <T::Lookup as StaticLookup>::Source) {
+	let (caller, caller_lookup) = create_default_asset::<T, I>(is_sufficient);
+	if !is_sufficient {
+		fund_account::<T, I>(&caller);
+	}
+	assert!(Assets::<T, I>::mint(
+		SystemOrigin::Signed(caller.clone()).into(),
+		Default::default(),
+		caller_lookup.clone(),
+		amount,
+	)
+	.is_ok());
+	(caller, caller_lookup)
+}
+
+/// Give `who` just enough to hold an account of a non-sufficient asset.
+fn fund_account<T: Config<I>, I: 'static>(who: &T::AccountId) {
+	T::Currency::make_free_balance_be(
+		who,
+		T::Currency::minimum_balance().saturating_add(T::AssetAccountDeposit::get()),
+	);
+}
+
+fn swap_is_sufficient<T: Config<I>, I: 'static>(s: &mut bool) {
+	Asset::<T, I>::mutate(&T::AssetId::default(), |maybe_a| {
+		if let Some(ref mut a) = maybe_a {
+			sp_std::mem::swap(s, &mut a.is_sufficient)
+		}
+	});
+}
+
+/// Add `n` accounts holding a deposit, the most expensive kind to remove.
+fn add_consumers<T: Config<I>, I: 'static>(minter: T::AccountId, n: u32) {
+	let origin = SystemOrigin::Signed(minter);
+	let mut s = false;
+	swap_is_sufficient::<T, I>(&mut s);
+	for i in 0..n {
+		let target = account("consumer", i, SEED);
+		fund_account::<T, I>(&target);
+		let target_lookup = T::Lookup::unlookup(target);
+		assert!(Assets::<T, I>::mint(
+			origin.clone().into(),
+			Default::default(),
+			target_lookup,
+			100u32.into()
+		)
+		.is_ok());
+	}
+	swap_is_sufficient::<T, I>(&mut s);
+}
+
+fn add_approvals<T: Config<I>, I: 'static>(minter: T::AccountId, n: u32) {
+	T::Currency::deposit_creating(
+		&minter,
+		T::ApprovalDeposit::get() * n.into() + T::Currency::minimum_balance(),
+	);
+	let minter_lookup = T::Lookup::unlookup(minter.clone());
+	let origin = SystemOrigin::Signed(minter);
+	Assets::<T, I>::mint(
+		origin.clone().into(),
+		Default::default(),
+		minter_lookup,
+		(100 * (n + 1)).into(),
+	)
+	.unwrap();
+	for i in 0..n {
+		let target = account("approval", i, SEED);
+		T::Currency::make_free_balance_be(&target, T::Currency::minimum_balance());
+		let target_lookup = T::Lookup::unlookup(target);
+		Assets::<T, I>::approve_transfer(
+			origin.clone().into(),
+			Default::default(),
+			target_lookup,
+			100u32.into(),
+		)
+		.unwrap();
+	}
+}
+
+/// Split `n` bytes of extended metadata over the description, URI and website, filling them in
+/// that order.
+fn extended_metadata<T: Config<I>, I: 'static>(n: u32) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+	let description = n.min(T::DescriptionLimit::get());
+	let uri = (n - description).min(T::UrlLimit::get());
+	let website = n - description - uri;
+	(vec![0u8; description as usize], vec![0u8; uri as usize], vec![0u8; website as usize])
+}
+
+/// A proof of `p` arbitrary nodes.
+fn airdrop_proof(p: u32) -> Vec<MerkleHash> {
+	(0..p).map(|i| [i as u8; 32]).collect()
+}
+
+/// Create an airdrop of 1000 of the default asset to the `recipients` leaves of the tree with
+/// root `root`, expiring at block 10. Returns its creator.
+fn create_default_airdrop<T: Config<I>, I: 'static>(
+	root: MerkleHash,
+	recipients: u32,
+) -> T::AccountId {
+	let (caller, _) = create_default_minted_asset::<T, I>(true, 1_000u32.into());
+	T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+	assert!(Assets::<T, I>::create_airdrop(
+		SystemOrigin::Signed(caller.clone()).into(),
+		Default::default(),
+		root,
+		recipients,
+		1_000u32.into(),
+		10u32.into(),
+		false,
+	)
+	.is_ok());
+	caller
+}
+
+/// Create two assets, a pool for them and seed it with liquidity from the caller. Returns the
+/// caller and the pool's assets and LP asset.
+fn create_default_pool<T: Config<I>, I: 'static>(
+) -> (T::AccountId, T::AssetId, T::AssetId, T::AssetId) {
+	let (caller, caller_lookup) = create_default_minted_asset::<T, I>(true, 10_000_000u32.into());
+	T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+	let asset_a = T::AssetId::default();
+	let asset_b = asset_a + One::one();
+	let lp_asset = T::ReservedAssetIds::get();
+	Assets::<T, I>::force_create(
+		SystemOrigin::Root.into(),
+		asset_b,
+		caller_lookup.clone(),
+		true,
+		1u32.into(),
+	)
+	.unwrap();
+	let origin = SystemOrigin::Signed(caller.clone());
+	Assets::<T, I>::mint(origin.clone().into(), asset_b, caller_lookup, 10_000_000u32.into())
+		.unwrap();
+	Assets::<T, I>::create_pool(origin.clone().into(), asset_a, asset_b, lp_asset).unwrap();
+	let liquidity = T::Balance::from(1_000_000u32);
+	Assets::<T, I>::add_liquidity(
+		origin.into(),
+		asset_a,
+		asset_b,
+		liquidity,
+		liquidity,
+		Zero::zero(),
+		Zero::zero(),
+	)
+	.unwrap();
+	(caller, asset_a, asset_b, lp_asset)
+}
+
+fn assert_last_event<T: Config<I>, I: 'static>(generic_event: <T as Config<I>>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn assert_event<T: Config<I>, I: 'static>(generic_event: <T as Config<I>>::Event) {
+	let system_event: <T as frame_system::Config>::Event = generic_event.into();
+	let events = frame_system::Pallet::<T>::events();
+	assert!(events.iter().any(|event_record| {
+		matches!(&event_record, frame_system::EventRecord { event, .. } if &system_event == event)
+	}));
+}
+
+benchmarks_instance_pallet! {
+	create {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_lookup = T::Lookup::unlookup(caller.clone());
+		let id = T::ReservedAssetIds::get();
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+	}: _(SystemOrigin::Signed(caller.clone()), id, caller_lookup, 1u32.into())
+	verify {
+		assert_last_event::<T, I>(Event::Created(id, caller.clone(), caller).into());
+	}
+
+	create_auto {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_lookup = T::Lookup::unlookup(caller.clone());
+		let id = Assets::<T, I>::next_asset_id();
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+	}: _(SystemOrigin::Signed(caller.clone()), caller_lookup, 1u32.into())
+	verify {
+		assert_last_event::<T, I>(Event::Created(id, caller.clone(), caller).into());
+	}
+
+	force_create {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_lookup = T::Lookup::unlookup(caller.clone());
+	}: _(SystemOrigin::Root, Default::default(), caller_lookup, true, 1u32.into())
+	verify {
+		assert_last_event::<T, I>(Event::ForceCreated(Default::default(), caller).into());
+	}
+
+	start_destroy {
+		let (caller, _) = create_default_minted_asset::<T, I>(true, 100u32.into());
+	}: _(SystemOrigin::Signed(caller), Default::default())
+	verify {
+		assert_last_event::<T, I>(Event::DestructionStarted(Default::default()).into());
+	}
+
+	destroy_accounts {
+		let c in 0 .. MAX_DESTROY_ITEMS;
+		let (caller, _) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		add_consumers::<T, I>(caller.clone(), c);
+		Assets::<T, I>::start_destroy(
+			SystemOrigin::Signed(caller.clone()).into(),
+			Default::default(),
+		)?;
+	}: _(SystemOrigin::Signed(caller), Default::default(), c)
+	verify {
+		assert_last_event::<T, I>(Event::AccountsDestroyed(Default::default(), c, 1).into());
+	}
+
+	destroy_approvals {
+		let a in 0 .. MAX_DESTROY_ITEMS;
+		let (caller, _) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		add_approvals::<T, I>(caller.clone(), a);
+		Assets::<T, I>::start_destroy(
+			SystemOrigin::Signed(caller.clone()).into(),
+			Default::default(),
+		)?;
+	}: _(SystemOrigin::Signed(caller), Default::default(), a)
+	verify {
+		assert_last_event::<T, I>(Event::ApprovalsDestroyed(Default::default(), a, 0).into());
+	}
+
+	finish_destroy {
+		let (caller, _) = create_default_asset::<T, I>(true);
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+		let origin: <T as frame_system::Config>::Origin = SystemOrigin::Signed(caller.clone()).into();
+		let dummy = vec![0u8; T::StringLimit::get() as usize];
+		Assets::<T, I>::set_metadata(origin.clone(), Default::default(), dummy.clone(), dummy, 12)?;
+		let (description, uri, website) = extended_metadata::<T, I>(
+			T::DescriptionLimit::get() + 2 * T::UrlLimit::get(),
+		);
+		Assets::<T, I>::set_extended_metadata(
+			origin.clone(),
+			Default::default(),
+			description,
+			uri,
+			Some([0u8; 32]),
+			website,
+		)?;
+		Assets::<T, I>::start_destroy(origin, Default::default())?;
+	}: _(SystemOrigin::Signed(caller), Default::default())
+	verify {
+		assert_last_event::<T, I>(Event::Destroyed(Default::default()).into());
+	}
+
+	mint {
+		let (caller, caller_lookup) = create_default_asset::<T, I>(true);
+		let amount = T::Balance::from(100u32);
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), caller_lookup, amount)
+	verify {
+		assert_last_event::<T, I>(Event::Issued(Default::default(), caller, amount).into());
+	}
+
+	burn {
+		let amount = T::Balance::from(100u32);
+		let (caller, caller_lookup) = create_default_minted_asset::<T, I>(true, amount);
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), caller_lookup, amount)
+	verify {
+		assert_last_event::<T, I>(Event::Burned(Default::default(), caller, amount).into());
+	}
+
+	transfer {
+		let amount = T::Balance::from(100u32);
+		let (caller, caller_lookup) = create_default_minted_asset::<T, I>(true, amount);
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), target_lookup, amount)
+	verify {
+		assert_last_event::<T, I>(Event::Transferred(Default::default(), caller, target, amount).into());
+	}
+
+	transfer_keep_alive {
+		let mint_amount = T::Balance::from(200u32);
+		let amount = T::Balance::from(100u32);
+		let (caller, caller_lookup) = create_default_minted_asset::<T, I>(true, mint_amount);
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), target_lookup, amount)
+	verify {
+		assert!(frame_system::Pallet::<T>::account_exists(&caller));
+		assert_last_event::<T, I>(Event::Transferred(Default::default(), caller, target, amount).into());
+	}
+
+	force_transfer {
+		let amount = T::Balance::from(100u32);
+		let (caller, caller_lookup) = create_default_minted_asset::<T, I>(true, amount);
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), caller_lookup, target_lookup, amount)
+	verify {
+		assert_last_event::<T, I>(
+			Event::Transferred(Default::default(), caller, target, amount).into()
+		);
+	}
+
+	freeze {
+		let (caller, caller_lookup) = create_default_minted_asset::<T, I>(true, 100u32.into());
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), caller_lookup)
+	verify {
+		assert_last_event::<T, I>(Event::Frozen(Default::default(), caller).into());
+	}
+
+	thaw {
+		let (caller, caller_lookup) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		Assets::<T, I>::freeze(
+			SystemOrigin::Signed(caller.clone()).into(),
+			Default::default(),
+			caller_lookup.clone(),
+		)?;
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), caller_lookup)
+	verify {
+		assert_last_event::<T, I>(Event::Thawed(Default::default(), caller).into());
+	}
+
+	freeze_amount {
+		let (caller, caller_lookup) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		// Fill all but one of the reasons, so that the new one is searched for and pushed last.
+		let max = T::MaxFreezes::get();
+		for reason in 1..max {
+			Assets::<T, I>::freeze_amount(
+				SystemOrigin::Signed(caller.clone()).into(),
+				Default::default(),
+				caller_lookup.clone(),
+				1u32.into(),
+				reason,
+			)?;
+		}
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), caller_lookup, 10u32.into(), max)
+	verify {
+		assert_last_event::<T, I>(
+			Event::AmountFrozen(Default::default(), caller, max, 10u32.into()).into()
+		);
+	}
+
+	thaw_amount {
+		let (caller, caller_lookup) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		let max = T::MaxFreezes::get();
+		for reason in 1..=max {
+			Assets::<T, I>::freeze_amount(
+				SystemOrigin::Signed(caller.clone()).into(),
+				Default::default(),
+				caller_lookup.clone(),
+				1u32.into(),
+				reason,
+			)?;
+		}
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), caller_lookup, max)
+	verify {
+		assert_last_event::<T, I>(
+			Event::AmountThawed(Default::default(), caller, max, 1u32.into()).into()
+		);
+	}
+
+	freeze_asset {
+		let (caller, _) = create_default_minted_asset::<T, I>(true, 100u32.into());
+	}: _(SystemOrigin::Signed(caller), Default::default())
+	verify {
+		assert_last_event::<T, I>(Event::AssetFrozen(Default::default()).into());
+	}
+
+	thaw_asset {
+		let (caller, _) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		Assets::<T, I>::freeze_asset(
+			SystemOrigin::Signed(caller.clone()).into(),
+			Default::default(),
+		)?;
+	}: _(SystemOrigin::Signed(caller), Default::default())
+	verify {
+		assert_last_event::<T, I>(Event::AssetThawed(Default::default()).into());
+	}
+
+	transfer_ownership {
+		let (caller, _) = create_default_asset::<T, I>(true);
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(SystemOrigin::Signed(caller), Default::default(), target_lookup)
+	verify {
+		assert_last_event::<T, I>(Event::OwnerChanged(Default::default(), target).into());
+	}
+
+	set_team {
+		let (caller, _) = create_default_asset::<T, I>(true);
+		let target0 = T::Lookup::unlookup(account("target", 0, SEED));
+		let target1 = T::Lookup::unlookup(account("target", 1, SEED));
+		let target2 = T::Lookup::unlookup(account("target", 2, SEED));
+	}: _(SystemOrigin::Signed(caller), Default::default(), target0, target1, target2)
+	verify {
+		assert_last_event::<T, I>(Event::TeamChanged(
+			Default::default(),
+			account("target", 0, SEED),
+			account("target", 1, SEED),
+			account("target", 2, SEED),
+		).into());
+	}
+
+	set_metadata {
+		let n in 0 .. T::StringLimit::get();
+		let s in 0 .. T::StringLimit::get();
+
+		let name = vec![0u8; n as usize];
+		let symbol = vec![0u8; s as usize];
+		let decimals = 12;
+
+		let (caller, _) = create_default_asset::<T, I>(true);
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+	}: _(SystemOrigin::Signed(caller), Default::default(), name.clone(), symbol.clone(), decimals)
+	verify {
+		let id = Default::default();
+		assert_last_event::<T, I>(Event::MetadataSet(id, name, symbol, decimals, false).into());
+	}
+
+	clear_metadata {
+		let (caller, _) = create_default_asset::<T, I>(true);
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+		let dummy = vec![0u8; T::StringLimit::get() as usize];
+		let origin = SystemOrigin::Signed(caller.clone()).into();
+		Assets::<T, I>::set_metadata(origin, Default::default(), dummy.clone(), dummy, 12)?;
+	}: _(SystemOrigin::Signed(caller), Default::default())
+	verify {
+		assert_last_event::<T, I>(Event::MetadataCleared(Default::default()).into());
+	}
+
+	force_set_metadata {
+		let n in 0 .. T::StringLimit::get();
+		let s in 0 .. T::StringLimit::get();
+
+		let name = vec![0u8; n as usize];
+		let symbol = vec![0u8; s as usize];
+		let decimals = 12;
+
+		create_default_asset::<T, I>(true);
+
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T, I>::force_set_metadata(
+			Default::default(),
+			name.clone(),
+			symbol.clone(),
+			decimals,
+			false,
+		);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		let id = Default::default();
+		assert_last_event::<T, I>(Event::MetadataSet(id, name, symbol, decimals, false).into());
+	}
+
+	force_clear_metadata {
+		let (caller, _) = create_default_asset::<T, I>(true);
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+		let dummy = vec![0u8; T::StringLimit::get() as usize];
+		let origin = SystemOrigin::Signed(caller).into();
+		Assets::<T, I>::set_metadata(origin, Default::default(), dummy.clone(), dummy, 12)?;
+
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T, I>::force_clear_metadata(Default::default());
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_last_event::<T, I>(Event::MetadataCleared(Default::default()).into());
+	}
+
+	set_extended_metadata {
+		let n in 0 .. T::DescriptionLimit::get() + 2 * T::UrlLimit::get();
+
+		let (description, uri, website) = extended_metadata::<T, I>(n);
+		let logo_hash = Some([0u8; 32]);
+
+		let (caller, _) = create_default_asset::<T, I>(true);
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+	}: _(
+		SystemOrigin::Signed(caller),
+		Default::default(),
+		description.clone(),
+		uri.clone(),
+		logo_hash,
+		website.clone()
+	)
+	verify {
+		assert_last_event::<T, I>(Event::ExtendedMetadataSet(
+			Default::default(),
+			description,
+			uri,
+			logo_hash,
+			website,
+			false,
+		).into());
+	}
+
+	clear_extended_metadata {
+		let (caller, _) = create_default_asset::<T, I>(true);
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+		let (description, uri, website) = extended_metadata::<T, I>(
+			T::DescriptionLimit::get() + 2 * T::UrlLimit::get(),
+		);
+		let origin = SystemOrigin::Signed(caller.clone()).into();
+		Assets::<T, I>::set_extended_metadata(
+			origin,
+			Default::default(),
+			description,
+			uri,
+			Some([0u8; 32]),
+			website,
+		)?;
+	}: _(SystemOrigin::Signed(caller), Default::default())
+	verify {
+		assert_last_event::<T, I>(Event::ExtendedMetadataCleared(Default::default()).into());
+	}
+
+	force_set_extended_metadata {
+		let n in 0 .. T::DescriptionLimit::get() + 2 * T::UrlLimit::get();
+
+		let (description, uri, website) = extended_metadata::<T, I>(n);
+		let logo_hash = Some([0u8; 32]);
+
+		create_default_asset::<T, I>(true);
+
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T, I>::force_set_extended_metadata(
+			Default::default(),
+			description.clone(),
+			uri.clone(),
+			logo_hash,
+			website.clone(),
+			false,
+		);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_last_event::<T, I>(Event::ExtendedMetadataSet(
+			Default::default(),
+			description,
+			uri,
+			logo_hash,
+			website,
+			false,
+		).into());
+	}
+
+	force_clear_extended_metadata {
+		let (caller, _) = create_default_asset::<T, I>(true);
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+		let (description, uri, website) = extended_metadata::<T, I>(
+			T::DescriptionLimit::get() + 2 * T::UrlLimit::get(),
+		);
+		let origin = SystemOrigin::Signed(caller).into();
+		Assets::<T, I>::set_extended_metadata(
+			origin,
+			Default::default(),
+			description,
+			uri,
+			Some([0u8; 32]),
+			website,
+		)?;
+
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T, I>::force_clear_extended_metadata(Default::default());
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_last_event::<T, I>(Event::ExtendedMetadataCleared(Default::default()).into());
+	}
+
+	force_asset_status {
+		let (_, caller_lookup) = create_default_asset::<T, I>(true);
+
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T, I>::force_asset_status(
+			Default::default(),
+			caller_lookup.clone(),
+			caller_lookup.clone(),
+			caller_lookup.clone(),
+			caller_lookup,
+			100u32.into(),
+			true,
+			false,
+		);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_last_event::<T, I>(Event::AssetStatusChanged(Default::default()).into());
+	}
+
+	approve_transfer {
+		let (caller, _) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+
+		let id = Default::default();
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let amount = 100u32.into();
+	}: _(SystemOrigin::Signed(caller.clone()), id, delegate_lookup, amount)
+	verify {
+		assert_last_event::<T, I>(
+			Event::ApprovedTransfer(id, caller, delegate, amount, amount).into()
+		);
+	}
+
+	approve_unlimited {
+		let (caller, _) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+
+		let id = Default::default();
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+	}: _(SystemOrigin::Signed(caller.clone()), id, delegate_lookup)
+	verify {
+		assert_last_event::<T, I>(Event::ApprovedUnlimited(id, caller, delegate).into());
+	}
+
+	transfer_approved {
+		let (owner, owner_lookup) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		T::Currency::make_free_balance_be(&owner, DepositBalanceOf::<T, I>::max_value());
+
+		let id = Default::default();
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		whitelist_account!(delegate);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let amount = 100u32.into();
+		let origin = SystemOrigin::Signed(owner.clone()).into();
+		Assets::<T, I>::approve_transfer(origin, id, delegate_lookup, amount)?;
+
+		let dest: T::AccountId = account("dest", 0, SEED);
+		let dest_lookup = T::Lookup::unlookup(dest.clone());
+	}: _(SystemOrigin::Signed(delegate.clone()), id, owner_lookup, dest_lookup, amount)
+	verify {
+		assert!(T::Currency::reserved_balance(&owner).is_zero());
+		assert_event::<T, I>(Event::Transferred(id, owner.clone(), dest.clone(), amount).into());
+		assert_last_event::<T, I>(
+			Event::ApprovalUsed(id, owner, delegate, dest, amount, Zero::zero()).into()
+		);
+	}
+
+	transfer_approved_unlimited {
+		let (owner, owner_lookup) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		T::Currency::make_free_balance_be(&owner, DepositBalanceOf::<T, I>::max_value());
+
+		let id = Default::default();
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		whitelist_account!(delegate);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let amount = 100u32.into();
+		let origin = SystemOrigin::Signed(owner.clone()).into();
+		Assets::<T, I>::approve_unlimited(origin, id, delegate_lookup)?;
+
+		let dest: T::AccountId = account("dest", 0, SEED);
+		let dest_lookup = T::Lookup::unlookup(dest.clone());
+		let origin = SystemOrigin::Signed(delegate.clone());
+	}: transfer_approved(origin, id, owner_lookup, dest_lookup, amount)
+	verify {
+		assert!(Approvals::<T, I>::get((id, &owner, &delegate)).unwrap().unlimited);
+		assert_last_event::<T, I>(
+			Event::ApprovalUsed(id, owner, delegate, dest, amount, T::Balance::max_value()).into()
+		);
+	}
+
+	cancel_approval {
+		let (caller, _) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+
+		let id = Default::default();
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let amount = 100u32.into();
+		let origin = SystemOrigin::Signed(caller.clone()).into();
+		Assets::<T, I>::approve_transfer(origin, id, delegate_lookup.clone(), amount)?;
+	}: _(SystemOrigin::Signed(caller.clone()), id, delegate_lookup)
+	verify {
+		assert_last_event::<T, I>(Event::ApprovalCancelled(id, caller, delegate).into());
+	}
+
+	force_cancel_approval {
+		let (caller, caller_lookup) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+
+		let id = Default::default();
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let amount = 100u32.into();
+		let origin = SystemOrigin::Signed(caller.clone()).into();
+		Assets::<T, I>::approve_transfer(origin, id, delegate_lookup.clone(), amount)?;
+	}: _(SystemOrigin::Signed(caller.clone()), id, caller_lookup, delegate_lookup)
+	verify {
+		assert_last_event::<T, I>(Event::ApprovalCancelled(id, caller, delegate).into());
+	}
+
+	touch {
+		create_default_asset::<T, I>(false);
+		let who: T::AccountId = account("touched", 0, SEED);
+		whitelist_account!(who);
+		fund_account::<T, I>(&who);
+	}: _(SystemOrigin::Signed(who.clone()), Default::default())
+	verify {
+		assert_last_event::<T, I>(
+			Event::Touched(Default::default(), who, T::AssetAccountDeposit::get()).into()
+		);
+	}
+
+	refund {
+		let (caller, _) = create_default_asset::<T, I>(false);
+		let who: T::AccountId = account("touched", 0, SEED);
+		whitelist_account!(who);
+		fund_account::<T, I>(&who);
+		Assets::<T, I>::touch(SystemOrigin::Signed(who.clone()).into(), Default::default())?;
+		// Leave a balance behind, so that the refund also has to burn it.
+		Assets::<T, I>::mint(
+			SystemOrigin::Signed(caller).into(),
+			Default::default(),
+			T::Lookup::unlookup(who.clone()),
+			100u32.into(),
+		)?;
+	}: _(SystemOrigin::Signed(who.clone()), Default::default(), true)
+	verify {
+		assert_last_event::<T, I>(
+			Event::Refunded(Default::default(), who, T::AssetAccountDeposit::get()).into()
+		);
+	}
+
+	create_pool {
+		let (caller, caller_lookup) = create_default_asset::<T, I>(true);
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+		let asset_a = T::AssetId::default();
+		let asset_b = asset_a + One::one();
+		let lp_asset = T::ReservedAssetIds::get();
+		Assets::<T, I>::force_create(SystemOrigin::Root.into(), asset_b, caller_lookup, true, 1u32.into())?;
+	}: _(SystemOrigin::Signed(caller.clone()), asset_a, asset_b, lp_asset)
+	verify {
+		let pool = Assets::<T, I>::pool_account(asset_a, asset_b);
+		assert_last_event::<T, I>(Event::PoolCreated(asset_a, asset_b, caller, pool, lp_asset).into());
+	}
+
+	add_liquidity {
+		let (caller, asset_a, asset_b, lp_asset) = create_default_pool::<T, I>();
+		let amount = T::Balance::from(1_000u32);
+		let lp_before = Assets::<T, I>::balance(lp_asset, &caller);
+	}: _(SystemOrigin::Signed(caller.clone()), asset_a, asset_b, amount, amount, amount, amount)
+	verify {
+		// The pool holds equal reserves, so one LP token is minted per unit of each asset.
+		assert_eq!(Assets::<T, I>::balance(lp_asset, &caller), lp_before + amount);
+		assert_last_event::<T, I>(
+			Event::LiquidityAdded(caller, asset_a, asset_b, amount, amount, amount).into()
+		);
+	}
+
+	remove_liquidity {
+		let (caller, asset_a, asset_b, lp_asset) = create_default_pool::<T, I>();
+		let amount = T::Balance::from(1_000u32);
+	}: _(SystemOrigin::Signed(caller.clone()), asset_a, asset_b, amount, amount, amount)
+	verify {
+		assert_last_event::<T, I>(
+			Event::LiquidityRemoved(caller, asset_a, asset_b, amount, amount, amount).into()
+		);
+	}
+
+	swap_exact_in {
+		let (caller, asset_a, asset_b, _) = create_default_pool::<T, I>();
+		let amount_in = T::Balance::from(1_000u32);
+		let amount_out = Assets::<T, I>::quote_exact_in(asset_a, asset_b, amount_in).unwrap();
+	}: _(SystemOrigin::Signed(caller.clone()), asset_a, asset_b, amount_in, amount_out)
+	verify {
+		assert_last_event::<T, I>(
+			Event::Swapped(caller, asset_a, asset_b, amount_in, amount_out).into()
+		);
+	}
+
+	swap_exact_out {
+		let (caller, asset_a, asset_b, _) = create_default_pool::<T, I>();
+		let amount_out = T::Balance::from(1_000u32);
+		let amount_in = Assets::<T, I>::quote_exact_out(asset_a, asset_b, amount_out).unwrap();
+	}: _(SystemOrigin::Signed(caller.clone()), asset_a, asset_b, amount_out, amount_in)
+	verify {
+		assert_last_event::<T, I>(
+			Event::Swapped(caller, asset_a, asset_b, amount_in, amount_out).into()
+		);
+	}
+
+	create_airdrop {
+		let (caller, _) = create_default_minted_asset::<T, I>(true, 1_000u32.into());
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+		let total = T::Balance::from(1_000u32);
+		let root = [0u8; 32];
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), root, 1, total, 10u32.into(), false)
+	verify {
+		assert_last_event::<T, I>(
+			Event::AirdropCreated(0, Default::default(), caller, root, total).into()
+		);
+	}
+
+	claim_airdrop {
+		let p in 0 .. MAX_PROOF_LENGTH as u32;
+		let recipient: T::AccountId = account("recipient", 0, SEED);
+		let amount = T::Balance::from(100u32);
+		let root = merkle_root_from_proof(airdrop_leaf(0, &recipient, amount), &airdrop_proof(p));
+		create_default_airdrop::<T, I>(root, u32::max_value());
+	}: _(SystemOrigin::None, 0, 0, recipient.clone(), amount, airdrop_proof(p))
+	verify {
+		assert_last_event::<T, I>(
+			Event::AirdropClaimed(0, Default::default(), recipient, amount).into()
+		);
+	}
+
+	reclaim_airdrop {
+		let w in 1 .. MAX_DESTROY_ITEMS;
+		let caller = create_default_airdrop::<T, I>([0u8; 32], w * 128);
+		for word in 0..w {
+			AirdropClaims::<T, I>::insert(0, word, 1u128);
+		}
+		frame_system::Pallet::<T>::set_block_number(10u32.into());
+	}: _(SystemOrigin::Signed(caller.clone()), 0, w)
+	verify {
+		assert_last_event::<T, I>(
+			Event::AirdropReclaimed(0, Default::default(), caller, 1_000u32.into()).into()
+		);
+	}
+}
+
+impl_benchmark_test_suite!(Assets, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,190 @@
+//! Paged enumeration of the holders and approvals of an asset and of the approvals an account
+//! gave or was given, and the snapshots of the largest holders of an asset that the offchain
+//! worker keeps in offchain storage.
+
+use super::*;
+use codec::{Decode, Encode};
+use frame_support::{
+	storage::StoragePrefixedMap, traits::PalletInfoAccess, Blake2_128Concat, StorageHasher,
+};
+use sp_runtime::{offchain::storage::StorageValueRef, RuntimeDebug};
+use sp_std::{cmp::Reverse, collections::binary_heap::BinaryHeap};
+
+/// The offchain storage key prefix of the top holders snapshots.
+pub const TOP_HOLDERS_PREFIX: &[u8] = b"erc20::top_holders::";
+
+/// The largest holders of an asset at some block.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct TopHoldersSnapshot<BlockNumber, AccountId, Balance> {
+	/// The block whose state the snapshot was taken of.
+	pub block_number: BlockNumber,
+	/// The largest holders and their balances, largest first.
+	pub holders: Vec<(AccountId, Balance)>,
+}
+
+/// The offchain storage key of the top holders snapshot of asset `id` of the pallet instance
+/// named `pallet` in the runtime.
+pub fn top_holders_key<AssetId: Encode>(pallet: &str, id: &AssetId) -> Vec<u8> {
+	let mut key = TOP_HOLDERS_PREFIX.to_vec();
+	key.extend_from_slice(pallet.as_bytes());
+	key.extend_from_slice(b"::");
+	id.encode_to(&mut key);
+	key
+}
+
+/// The `Blake2_128Concat` hashed storage key part of `part`.
+fn hashed(part: &impl Encode) -> Vec<u8> {
+	Blake2_128Concat::hash(&part.encode())
+}
+
+/// Decode the `Blake2_128Concat` hashed storage key part at the start of `key`, and advance past
+/// it.
+fn unhashed<K: Decode>(key: &mut &[u8]) -> Option<K> {
+	*key = key.get(16..)?;
+	K::decode(key).ok()
+}
+
+/// Up to `limit` entries stored under `prefix`, in storage order, starting after the entry whose
+/// key below `prefix` is `start_after`. `decode_key` decodes the key below `prefix` of an entry.
+fn page<K, V: Decode>(
+	prefix: Vec<u8>,
+	start_after: Option<Vec<u8>>,
+	limit: u32,
+	decode_key: impl Fn(&mut &[u8]) -> Option<K>,
+) -> Vec<(K, V)> {
+	let mut key = match start_after {
+		Some(k) => [&prefix[..], &k].concat(),
+		None => prefix.clone(),
+	};
+	let mut page = Vec::new();
+	while page.len() < limit as usize {
+		key = match sp_io::storage::next_key(&key) {
+			Some(next) if next.starts_with(&prefix) => next,
+			_ => break,
+		};
+		let item = key
+			.get(prefix.len()..)
+			.and_then(|mut k| decode_key(&mut k))
+			.zip(frame_support::storage::unhashed::get::<V>(&key));
+		page.extend(item);
+	}
+	page
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Up to `limit` holders of asset `id` and their balances, in storage order, starting after
+	/// `start_after`.
+	pub fn holders(
+		id: T::AssetId,
+		start_after: Option<T::AccountId>,
+		limit: u32,
+	) -> Vec<(T::AccountId, T::Balance)> {
+		let prefix = [&Account::<T, I>::final_prefix()[..], &hashed(&id)].concat();
+		page::<_, AssetBalance<T::Balance, DepositBalanceOf<T, I>, T::Extra, T::AccountId>>(
+			prefix,
+			start_after.map(|who| hashed(&who)),
+			limit,
+			unhashed,
+		)
+		.into_iter()
+		.map(|(who, account)| (who, account.balance))
+		.collect()
+	}
+
+	/// Up to `limit` delegates approved by `owner` to spend asset `id` and their allowances, in
+	/// storage order, starting after `start_after`.
+	pub fn approvals_of(
+		id: T::AssetId,
+		owner: T::AccountId,
+		start_after: Option<T::AccountId>,
+		limit: u32,
+	) -> Vec<(T::AccountId, T::Balance)> {
+		let prefix =
+			[&Approvals::<T, I>::final_prefix()[..], &hashed(&id), &hashed(&owner)].concat();
+		page::<_, Approval<T::Balance, DepositBalanceOf<T, I>>>(
+			prefix,
+			start_after.map(|delegate| hashed(&delegate)),
+			limit,
+			unhashed,
+		)
+		.into_iter()
+		.map(|(delegate, approval)| (delegate, approval.allowance()))
+		.collect()
+	}
+
+	/// Up to `limit` approvals `owner` gave, across assets, as the asset, the delegate and the
+	/// approved amount, in storage order, starting after the approval `start_after`.
+	pub fn approvals_by_owner(
+		owner: T::AccountId,
+		start_after: Option<(T::AssetId, T::AccountId)>,
+		limit: u32,
+	) -> Vec<(T::AssetId, T::AccountId, T::Balance)> {
+		let prefix = [&OwnerApprovals::<T, I>::final_prefix()[..], &hashed(&owner)].concat();
+		Self::approvals_page(prefix, start_after, limit)
+			.into_iter()
+			.map(|(id, delegate)| {
+				let amount = Self::allowance(id, &owner, &delegate);
+				(id, delegate, amount)
+			})
+			.collect()
+	}
+
+	/// Up to `limit` approvals `delegate` was given, across assets, as the asset, the owner and
+	/// the approved amount, in storage order, starting after the approval `start_after`.
+	pub fn approvals_by_delegate(
+		delegate: T::AccountId,
+		start_after: Option<(T::AssetId, T::AccountId)>,
+		limit: u32,
+	) -> Vec<(T::AssetId, T::AccountId, T::Balance)> {
+		let prefix = [&DelegateApprovals::<T, I>::final_prefix()[..], &hashed(&delegate)].concat();
+		Self::approvals_page(prefix, start_after, limit)
+			.into_iter()
+			.map(|(id, owner)| {
+				let amount = Self::allowance(id, &owner, &delegate);
+				(id, owner, amount)
+			})
+			.collect()
+	}
+
+	/// Up to `limit` keys of `OwnerApprovals` or `DelegateApprovals` below the account `prefix`,
+	/// as the asset and the other account, starting after `start_after`.
+	fn approvals_page(
+		prefix: Vec<u8>,
+		start_after: Option<(T::AssetId, T::AccountId)>,
+		limit: u32,
+	) -> Vec<(T::AssetId, T::AccountId)> {
+		let start_after = start_after.map(|(id, who)| [hashed(&id), hashed(&who)].concat());
+		page::<_, ()>(prefix, start_after, limit, |key| Some((unhashed(key)?, unhashed(key)?)))
+			.into_iter()
+			.map(|(key, ())| key)
+			.collect()
+	}
+
+	/// The `n` largest holders of asset `id` and their balances, largest first. Iterates all
+	/// accounts of the asset.
+	pub(super) fn top_holders(id: T::AssetId, n: u32) -> Vec<(T::AccountId, T::Balance)> {
+		let mut top = BinaryHeap::with_capacity(n as usize + 1);
+		for (who, account) in Account::<T, I>::iter_prefix(id) {
+			top.push(Reverse((account.balance, who)));
+			if top.len() > n as usize {
+				top.pop();
+			}
+		}
+		top.into_sorted_vec()
+			.into_iter()
+			.map(|Reverse((balance, who))| (who, balance))
+			.collect()
+	}
+
+	/// Store a snapshot of the `TopHolders` largest holders of every asset in offchain storage.
+	pub(super) fn snapshot_top_holders(block_number: T::BlockNumber) {
+		let pallet = <Self as PalletInfoAccess>::name();
+		for id in Asset::<T, I>::iter_keys() {
+			let snapshot = TopHoldersSnapshot {
+				block_number,
+				holders: Self::top_holders(id, T::TopHolders::get()),
+			};
+			StorageValueRef::persistent(&top_holders_key(pallet, &id)).set(&snapshot);
+		}
+	}
+}
@@ -0,0 +1,198 @@
+//! Consistency checks over the whole asset ledger.
+//!
+//! The checks compare the counters and deposits recorded by the pallet with the storage they
+//! describe. They iterate every asset, account, approval, metadata and airdrop entry, so they are
+//! meant for try-runtime and development nodes, never for block execution.
+
+use super::*;
+use codec::{Decode, Encode};
+use sp_runtime::RuntimeDebug;
+use sp_std::collections::btree_map::BTreeMap;
+
+/// An inconsistency found in the ledger.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub enum IntegrityIssue<AssetId, AccountId, Balance, DepositBalance> {
+	/// The recorded supply is not the sum of all balances. \[asset_id, recorded, actual\]
+	Supply(AssetId, Balance, Balance),
+	/// The recorded number of accounts is wrong. \[asset_id, recorded, actual\]
+	Accounts(AssetId, u32, u32),
+	/// The recorded number of self-sufficient accounts is wrong. \[asset_id, recorded, actual\]
+	Sufficients(AssetId, u32, u32),
+	/// The recorded number of approvals is wrong. \[asset_id, recorded, actual\]
+	Approvals(AssetId, u32, u32),
+	/// An account of an asset that does not exist. \[asset_id, who\]
+	OrphanedAccount(AssetId, AccountId),
+	/// An approval of an asset that does not exist. \[asset_id, owner, delegate\]
+	OrphanedApproval(AssetId, AccountId, AccountId),
+	/// Metadata or extended metadata of an asset that does not exist. \[asset_id\]
+	OrphanedMetadata(AssetId),
+	/// An account has less reserved than the deposits it holds with this pallet.
+	/// \[who, deposits, reserved\]
+	Reserved(AccountId, DepositBalance, DepositBalance),
+	/// Amounts frozen on an account that does not exist. \[asset_id, who\]
+	OrphanedFreeze(AssetId, AccountId),
+	/// An approval missing from the indexes by owner and delegate, or an index entry of an
+	/// approval that does not exist. \[asset_id, owner, delegate\]
+	ApprovalIndex(AssetId, AccountId, AccountId),
+}
+
+/// The outcome of `Pallet::integrity_report`.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct IntegrityReport<AssetId, AccountId, Balance, DepositBalance> {
+	/// The number of assets checked.
+	pub assets: u32,
+	/// The number of asset accounts checked.
+	pub accounts: u32,
+	/// The number of approvals checked.
+	pub approvals: u32,
+	/// Everything found to be inconsistent. Empty for a healthy ledger.
+	pub issues: Vec<IntegrityIssue<AssetId, AccountId, Balance, DepositBalance>>,
+}
+
+impl<AssetId, AccountId, Balance, DepositBalance>
+	IntegrityReport<AssetId, AccountId, Balance, DepositBalance>
+{
+	/// Whether the ledger was found to be consistent.
+	pub fn is_ok(&self) -> bool {
+		self.issues.is_empty()
+	}
+}
+
+/// The `IntegrityReport` of an instance of this pallet.
+pub type IntegrityReportOf<T, I = ()> = IntegrityReport<
+	<T as Config<I>>::AssetId,
+	<T as SystemConfig>::AccountId,
+	<T as Config<I>>::Balance,
+	<<T as Config<I>>::Currency as Currency<<T as SystemConfig>::AccountId>>::Balance,
+>;
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Check the whole ledger for inconsistencies: the supply, account, self-sufficient and
+	/// approval counts of every asset, the indexes of the approvals, entries left behind by
+	/// destroyed assets or dead accounts, and whether every account still has reserved the
+	/// deposits it holds with this pallet.
+	///
+	/// Other pallets may reserve from the same accounts, so reserves are only required to cover
+	/// the deposits, not to match them.
+	pub fn integrity_report() -> IntegrityReportOf<T, I> {
+		let mut issues = Vec::new();
+		let mut deposits = BTreeMap::<T::AccountId, DepositBalanceOf<T, I>>::new();
+		let mut note_deposit = |who: T::AccountId, deposit: DepositBalanceOf<T, I>| {
+			let total = deposits.entry(who).or_insert_with(Zero::zero);
+			*total = total.saturating_add(deposit);
+		};
+
+		// The deposits of LP assets are held by the creators of their pools.
+		let pool_creators = Pools::<T, I>::iter_values()
+			.map(|pool| (pool.lp_asset, pool.creator))
+			.collect::<BTreeMap<_, _>>();
+
+		let mut assets = 0u32;
+		for (id, details) in Asset::<T, I>::iter() {
+			assets.saturating_inc();
+
+			let mut supply = T::Balance::zero();
+			let mut accounts = 0u32;
+			let mut sufficients = 0u32;
+			for (who, account) in Account::<T, I>::iter_prefix(id) {
+				supply = supply.saturating_add(account.balance);
+				accounts.saturating_inc();
+				match account.reason {
+					ExistenceReason::Consumer => {},
+					ExistenceReason::Sufficient => sufficients.saturating_inc(),
+					ExistenceReason::DepositHeld(deposit) => note_deposit(who, deposit),
+					ExistenceReason::DepositFrom(depositor, deposit) =>
+						note_deposit(depositor, deposit),
+				}
+			}
+			if supply != details.supply {
+				issues.push(IntegrityIssue::Supply(id, details.supply, supply));
+			}
+			if accounts != details.accounts {
+				issues.push(IntegrityIssue::Accounts(id, details.accounts, accounts));
+			}
+			if sufficients != details.sufficients {
+				issues.push(IntegrityIssue::Sufficients(id, details.sufficients, sufficients));
+			}
+
+			let mut approvals = 0u32;
+			for ((owner, _), approval) in Approvals::<T, I>::iter_prefix((id,)) {
+				approvals.saturating_inc();
+				note_deposit(owner, approval.deposit);
+			}
+			if approvals != details.approvals {
+				issues.push(IntegrityIssue::Approvals(id, details.approvals, approvals));
+			}
+
+			let metadata_deposit = Metadata::<T, I>::get(id).deposit;
+			let extended_deposit =
+				ExtendedMetadata::<T, I>::get(id).map_or(Zero::zero(), |m| m.deposit);
+			let depositor = pool_creators.get(&id).unwrap_or(&details.owner).clone();
+			note_deposit(depositor, details.deposit);
+			note_deposit(details.owner, metadata_deposit.saturating_add(extended_deposit));
+		}
+
+		let mut accounts = 0u32;
+		for (id, who) in Account::<T, I>::iter_keys() {
+			accounts.saturating_inc();
+			if !Asset::<T, I>::contains_key(id) {
+				issues.push(IntegrityIssue::OrphanedAccount(id, who));
+			}
+		}
+		let mut approvals = 0u32;
+		for (id, owner, delegate) in Approvals::<T, I>::iter_keys() {
+			approvals.saturating_inc();
+			if !Asset::<T, I>::contains_key(id) {
+				issues.push(IntegrityIssue::OrphanedApproval(id, owner.clone(), delegate.clone()));
+			}
+			if !OwnerApprovals::<T, I>::contains_key((&owner, id, &delegate)) ||
+				!DelegateApprovals::<T, I>::contains_key((&delegate, id, &owner))
+			{
+				issues.push(IntegrityIssue::ApprovalIndex(id, owner, delegate));
+			}
+		}
+		let by_owner =
+			OwnerApprovals::<T, I>::iter_keys().map(|(owner, id, delegate)| (id, owner, delegate));
+		let by_delegate = DelegateApprovals::<T, I>::iter_keys()
+			.map(|(delegate, id, owner)| (id, owner, delegate));
+		for (id, owner, delegate) in by_owner.chain(by_delegate) {
+			if !Approvals::<T, I>::contains_key((id, &owner, &delegate)) {
+				issues.push(IntegrityIssue::ApprovalIndex(id, owner, delegate));
+			}
+		}
+		for id in Metadata::<T, I>::iter_keys().chain(ExtendedMetadata::<T, I>::iter_keys()) {
+			if !Asset::<T, I>::contains_key(id) {
+				issues.push(IntegrityIssue::OrphanedMetadata(id));
+			}
+		}
+		for (id, who) in Freezes::<T, I>::iter_keys() {
+			if !Account::<T, I>::contains_key(id, &who) {
+				issues.push(IntegrityIssue::OrphanedFreeze(id, who));
+			}
+		}
+
+		for airdrop in Airdrops::<T, I>::iter_values() {
+			note_deposit(airdrop.creator, airdrop.deposit);
+		}
+
+		for (who, deposit) in deposits {
+			let reserved = T::Currency::reserved_balance(&who);
+			if reserved < deposit {
+				issues.push(IntegrityIssue::Reserved(who, deposit, reserved));
+			}
+		}
+
+		IntegrityReport { assets, accounts, approvals, issues }
+	}
+
+	/// Fail if `integrity_report` finds any issue, logging each of them.
+	#[cfg(feature = "try-runtime")]
+	pub fn try_state() -> Result<(), &'static str> {
+		let report = Self::integrity_report();
+		for issue in report.issues.iter() {
+			frame_support::log::error!(target: "runtime::erc20", "ledger integrity: {:?}", issue);
+		}
+		ensure!(report.is_ok(), "the asset ledger is inconsistent");
+		Ok(())
+	}
+}
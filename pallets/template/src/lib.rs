@@ -1,9 +1,30 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod abi;
+mod airdrop;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 // mod extra_mutator;
+mod holders;
+mod integrity;
+pub mod migration;
+#[cfg(test)]
+mod mock;
+mod pool;
+#[cfg(test)]
+mod proptests;
+#[cfg(test)]
+mod tests;
+mod u256;
 pub mod weights;
+pub use abi::Erc20Call;
+pub use airdrop::{airdrop_leaf, merkle_root_from_proof, MerkleHash, MerkleTree, MAX_PROOF_LENGTH};
 pub use extra_mutator::*;
+pub use holders::{top_holders_key, TopHoldersSnapshot, TOP_HOLDERS_PREFIX};
+pub use integrity::{IntegrityIssue, IntegrityReport, IntegrityReportOf};
+pub use pool::MINIMUM_LIQUIDITY;
 pub use types::*;
+pub use u256::U256Balance;
 
 use codec::HasCompact;
 use frame_support::{
@@ -14,15 +35,22 @@ use frame_support::{
 		BalanceStatus::Reserved,
 		Currency, ReservableCurrency, StoredMap,
 	},
+	BoundedVec, PalletId,
 };
 use frame_system::Config as SystemConfig;
 use sp_runtime::{
 	traits::{
-		AtLeast32BitUnsigned, Bounded, CheckedAdd, CheckedSub, Saturating, StaticLookup, Zero,
+		AtLeast32BitUnsigned, Bounded, CheckedAdd, CheckedSub, Convert, MaybeSerializeDeserialize,
+		One, Saturating, StaticLookup, Zero,
 	},
-	ArithmeticError, TokenError,
+	ArithmeticError, Permill, TokenError,
+};
+use sp_core::{H160, U256};
+use sp_std::{
+	borrow::Borrow,
+	convert::{TryFrom, TryInto},
+	prelude::*,
 };
-use sp_std::{borrow::Borrow, convert::TryInto, prelude::*};
 
 pub use pallet::*;
 pub use weights::WeightInfo;
@@ -30,19 +58,38 @@ pub use weights::WeightInfo;
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
+	use frame_support::{dispatch::DispatchResult, pallet_prelude::*, traits::StorageVersion};
 	use frame_system::pallet_prelude::*;
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	#[pallet::generate_storage_info]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T, I = ()>(_);
 
 	#[pallet::config]
 	pub trait Config<I: 'static = ()>: frame_system::Config {
 		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
-		type Balance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy + MaxEncodedLen;
-		type AssetId: Member + Parameter + Default + Copy + HasCompact + MaxEncodedLen;
+		type Balance: Member
+			+ Parameter
+			+ AtLeast32BitUnsigned
+			+ Default
+			+ Copy
+			+ MaybeSerializeDeserialize
+			+ MaxEncodedLen
+			+ Into<U256>
+			+ TryFrom<U256>;
+		type AssetId: Member
+			+ Parameter
+			+ Default
+			+ Copy
+			+ HasCompact
+			+ MaybeSerializeDeserialize
+			+ MaxEncodedLen
+			+ AtLeast32BitUnsigned;
 		type Currency: ReservableCurrency<Self::AccountId>;
 		type ForceOrigin: EnsureOrigin<Self::Origin>;
 
@@ -58,13 +105,71 @@ pub mod pallet {
 		#[pallet::constant]
 		type ApprovalDeposit: Get<DepositBalanceOf<Self, I>>;
 
+		/// The deposit reserved for holding an account of a non-sufficient asset. `touch` reserves
+		/// it from the account itself. An account that first receives the asset from a transfer or
+		/// mint has it reserved from the signer of that call rather than from its own balance, and
+		/// returned once the account is removed. Accounts credited by this pallet's own accounts,
+		/// such as pools and airdrops, are paid for by a consumer reference, as are all accounts
+		/// when the deposit is zero.
+		#[pallet::constant]
+		type AssetAccountDeposit: Get<DepositBalanceOf<Self, I>>;
+
 		#[pallet::constant]
 		type StringLimit: Get<u32>;
 
+		/// The maximum length of the description in an asset's extended metadata.
+		#[pallet::constant]
+		type DescriptionLimit: Get<u32>;
+
+		/// The maximum length of the URI and website in an asset's extended metadata.
+		#[pallet::constant]
+		type UrlLimit: Get<u32>;
+
+		/// Asset ids below this are reserved for `force_create`. `create_auto` allocates ids from
+		/// here upwards.
+		#[pallet::constant]
+		type ReservedAssetIds: Get<Self::AssetId>;
+
 		type Freezer: FrozenBalance<Self::AssetId, Self::AccountId, Self::Balance>;
 
+		/// Told about every asset created and destroyed.
+		type CallbackHandle: AssetsCallback<Self::AssetId, Self::AccountId>;
+
 		type Extra: Member + Parameter + Default + MaxEncodedLen;
 
+		/// The pallet id, used for deriving the accounts that hold liquidity pool reserves.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The fee taken from the input of every pool swap. It stays in the pool, accruing to the
+		/// liquidity providers.
+		#[pallet::constant]
+		type PoolFee: Get<Permill>;
+
+		/// How many of the largest holders of each asset the offchain worker keeps a snapshot of.
+		#[pallet::constant]
+		type TopHolders: Get<u32>;
+
+		/// How many blocks apart the offchain worker takes the top holders snapshots. Zero turns
+		/// the snapshots off.
+		#[pallet::constant]
+		type TopHoldersInterval: Get<Self::BlockNumber>;
+
+		/// The deposit reserved from the creator of an airdrop until it is reclaimed.
+		#[pallet::constant]
+		type AirdropDeposit: Get<DepositBalanceOf<Self, I>>;
+
+		/// The priority of unsigned airdrop claims.
+		#[pallet::constant]
+		type AirdropUnsignedPriority: Get<TransactionPriority>;
+
+		/// The maximum number of reasons an account may have amounts of an asset frozen for.
+		#[pallet::constant]
+		type MaxFreezes: Get<u32>;
+
+		/// Maps the 20 byte addresses of ABI encoded calls to accounts.
+		type AddressMapping: Convert<H160, Self::AccountId>;
+
 		type WeightInfo: WeightInfo;
 	}
 
@@ -85,7 +190,7 @@ pub mod pallet {
 		T::AssetId,
 		Blake2_128Concat,
 		T::AccountId,
-		AssetBalance<T::Balance, T::Extra>,
+		AssetBalance<T::Balance, DepositBalanceOf<T, I>, T::Extra, T::AccountId>,
 		ValueQuery,
 		GetDefault,
 		ConstU32<300_000>,
@@ -106,6 +211,48 @@ pub mod pallet {
 		ConstU32<300_000>,
 	>;
 
+	// `Approvals` indexed by owner, across assets.
+	#[pallet::storage]
+	pub(super) type OwnerApprovals<T: Config<I>, I: 'static = ()> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::AccountId>, // owner
+			NMapKey<Blake2_128Concat, T::AssetId>,
+			NMapKey<Blake2_128Concat, T::AccountId>, // delegate
+		),
+		(),
+		OptionQuery,
+		GetDefault,
+		ConstU32<300_000>,
+	>;
+
+	// `Approvals` indexed by delegate, across assets.
+	#[pallet::storage]
+	pub(super) type DelegateApprovals<T: Config<I>, I: 'static = ()> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::AccountId>, // delegate
+			NMapKey<Blake2_128Concat, T::AssetId>,
+			NMapKey<Blake2_128Concat, T::AccountId>, // owner
+		),
+		(),
+		OptionQuery,
+		GetDefault,
+		ConstU32<300_000>,
+	>;
+
+	// The amounts of an account frozen by `freeze_amount`, by reason.
+	#[pallet::storage]
+	pub(super) type Freezes<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<(FreezeReason, T::Balance), T::MaxFreezes>,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	pub(super) type Metadata<T: Config<I>, I: 'static = ()> = StorageMap<
 		_,
@@ -117,6 +264,124 @@ pub mod pallet {
 		ConstU32<300_000>,
 	>;
 
+	#[pallet::storage]
+	pub(super) type ExtendedMetadata<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		AssetExtendedMetadata<
+			DepositBalanceOf<T, I>,
+			BoundedVec<u8, T::DescriptionLimit>,
+			BoundedVec<u8, T::UrlLimit>,
+		>,
+	>;
+
+	// The id `create_auto` allocates next. Always above every id in use outside the reserved range.
+	#[pallet::storage]
+	pub(super) type NextAssetId<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AssetId>;
+
+	// Liquidity pools, keyed by their pair of assets in ascending order.
+	#[pallet::storage]
+	pub(super) type Pools<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		Blake2_128Concat,
+		T::AssetId,
+		PoolInfo<T::AssetId, T::AccountId>,
+	>;
+
+	// Airdrops that were not yet reclaimed.
+	#[pallet::storage]
+	pub(super) type Airdrops<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, AirdropId, AirdropInfoOf<T, I>>;
+
+	// The id of the next airdrop.
+	#[pallet::storage]
+	pub(super) type NextAirdropId<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, AirdropId, ValueQuery>;
+
+	// The claimed leaves of every airdrop, by word of the bitmap.
+	#[pallet::storage]
+	pub(super) type AirdropClaims<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Blake2_128Concat, AirdropId, Blake2_128Concat, u32, u128, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+		/// Assets to create: `(id, owner, is_sufficient, min_balance)`.
+		pub assets: Vec<(T::AssetId, T::AccountId, bool, T::Balance)>,
+		/// Metadata of genesis assets: `(id, name, symbol, decimals)`.
+		pub metadata: Vec<(T::AssetId, Vec<u8>, Vec<u8>, u8)>,
+		/// Balances of genesis assets: `(id, who, balance)`.
+		pub accounts: Vec<(T::AssetId, T::AccountId, T::Balance)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
+		fn default() -> Self {
+			Self {
+				assets: Default::default(),
+				metadata: Default::default(),
+				accounts: Default::default(),
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
+		fn build(&self) {
+			for (id, owner, is_sufficient, min_balance) in &self.assets {
+				assert!(!Asset::<T, I>::contains_key(id), "Asset id already in use");
+				assert!(!min_balance.is_zero(), "Min balance should not be zero");
+				Pallet::<T, I>::note_asset_id(*id);
+				Asset::<T, I>::insert(
+					id,
+					AssetDetails {
+						owner: owner.clone(),
+						issuer: owner.clone(),
+						admin: owner.clone(),
+						freezer: owner.clone(),
+						supply: Zero::zero(),
+						deposit: Zero::zero(),
+						min_balance: *min_balance,
+						is_sufficient: *is_sufficient,
+						accounts: 0,
+						sufficients: 0,
+						approvals: 0,
+						status: AssetStatus::Live,
+					},
+				);
+				T::CallbackHandle::created(id, owner);
+			}
+
+			for (id, name, symbol, decimals) in &self.metadata {
+				assert!(Asset::<T, I>::contains_key(id), "Metadata of an unknown asset");
+				let bounded_name: BoundedVec<u8, T::StringLimit> =
+					name.clone().try_into().expect("Asset name is too long");
+				let bounded_symbol: BoundedVec<u8, T::StringLimit> =
+					symbol.clone().try_into().expect("Asset symbol is too long");
+				Metadata::<T, I>::insert(
+					id,
+					AssetMetadata {
+						deposit: Zero::zero(),
+						name: bounded_name,
+						symbol: bounded_symbol,
+						decimals: *decimals,
+						is_frozen: false,
+					},
+				);
+			}
+
+			for (id, who, amount) in &self.accounts {
+				let result = Pallet::<T, I>::increase_balance(*id, who, *amount, None, |details| {
+					details.supply = details.supply.saturating_add(*amount);
+					Ok(())
+				});
+				assert!(result.is_ok(), "Genesis balance could not be credited: {:?}", result);
+			}
+		}
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	#[pallet::metadata(T::AccountId = "AccountId", T::Balance = "Balance", T::AssetId = "AssetId")]
@@ -141,7 +406,7 @@ pub mod pallet {
 		AssetFrozen(T::AssetId),
 		/// Some asset `asset_id` was thawed. \[asset_id\]
 		AssetThawed(T::AssetId),
-		/// An asset class was destroyed.
+		/// An asset class was destroyed and its deposits released. \[asset_id\]
 		Destroyed(T::AssetId),
 		/// Some asset class was force-created. \[asset_id, owner\]
 		ForceCreated(T::AssetId, T::AccountId),
@@ -149,19 +414,67 @@ pub mod pallet {
 		MetadataSet(T::AssetId, Vec<u8>, Vec<u8>, u8, bool),
 		/// Metadata has been cleared for an asset. \[asset_id\]
 		MetadataCleared(T::AssetId),
+		/// New extended metadata has been set for an asset.
+		/// \[asset_id, description, uri, logo_hash, website, is_frozen\]
+		ExtendedMetadataSet(T::AssetId, Vec<u8>, Vec<u8>, Option<[u8; 32]>, Vec<u8>, bool),
+		/// Extended metadata has been cleared for an asset. \[asset_id\]
+		ExtendedMetadataCleared(T::AssetId),
 		/// (Additional) funds have been approved for transfer to a destination account.
-		/// \[asset_id, source, delegate, amount\]
-		ApprovedTransfer(T::AssetId, T::AccountId, T::AccountId, T::Balance),
+		/// \[asset_id, source, delegate, amount, allowance\]
+		ApprovedTransfer(T::AssetId, T::AccountId, T::AccountId, T::Balance, T::Balance),
 		/// An approval for account `delegate` was cancelled by `owner`.
 		/// \[id, owner, delegate\]
 		ApprovalCancelled(T::AssetId, T::AccountId, T::AccountId),
-		/// An `amount` was transferred in its entirety from `owner` to `destination` by
-		/// the approved `delegate`.
-		/// \[id, owner, delegate, destination\]
+		/// An `amount` was transferred from `owner` to `destination` by the approved `delegate`.
+		/// `ApprovalUsed` follows with what is left of the approval.
+		/// \[id, owner, delegate, destination, amount\]
 		TransferredApproved(T::AssetId, T::AccountId, T::AccountId, T::AccountId, T::Balance),
 		/// An asset has had its attributes changed by the `Force` origin.
 		/// \[id\]
 		AssetStatusChanged(T::AssetId),
+		/// A liquidity pool was created. \[asset_a, asset_b, creator, pool_account, lp_asset\]
+		PoolCreated(T::AssetId, T::AssetId, T::AccountId, T::AccountId, T::AssetId),
+		/// Liquidity was added to a pool.
+		/// \[who, asset_a, asset_b, amount_a, amount_b, lp_minted\]
+		LiquidityAdded(T::AccountId, T::AssetId, T::AssetId, T::Balance, T::Balance, T::Balance),
+		/// Liquidity was removed from a pool.
+		/// \[who, asset_a, asset_b, amount_a, amount_b, lp_burned\]
+		LiquidityRemoved(T::AccountId, T::AssetId, T::AssetId, T::Balance, T::Balance, T::Balance),
+		/// Assets were swapped through a pool.
+		/// \[who, asset_in, asset_out, amount_in, amount_out\]
+		Swapped(T::AccountId, T::AssetId, T::AssetId, T::Balance, T::Balance),
+		/// The destruction of an asset class was started. \[asset_id\]
+		DestructionStarted(T::AssetId),
+		/// Accounts of an asset being destroyed were removed.
+		/// \[asset_id, removed, remaining\]
+		AccountsDestroyed(T::AssetId, u32, u32),
+		/// Approvals of an asset being destroyed were removed.
+		/// \[asset_id, removed, remaining\]
+		ApprovalsDestroyed(T::AssetId, u32, u32),
+		/// An account was created with a deposit. \[asset_id, who, deposit\]
+		Touched(T::AssetId, T::AccountId, DepositBalanceOf<T, I>),
+		/// An account was removed and its deposit returned. \[asset_id, who, deposit\]
+		Refunded(T::AssetId, T::AccountId, DepositBalanceOf<T, I>),
+		/// An airdrop was created and its budget set aside.
+		/// \[airdrop_id, asset_id, creator, merkle_root, total\]
+		AirdropCreated(AirdropId, T::AssetId, T::AccountId, MerkleHash, T::Balance),
+		/// A recipient claimed from an airdrop. \[airdrop_id, asset_id, who, amount\]
+		AirdropClaimed(AirdropId, T::AssetId, T::AccountId, T::Balance),
+		/// An expired airdrop was removed and what was left of its budget returned.
+		/// \[airdrop_id, asset_id, creator, amount\]
+		AirdropReclaimed(AirdropId, T::AssetId, T::AccountId, T::Balance),
+		/// An amount of account `who` was frozen for a reason. \[asset_id, who, reason, amount\]
+		AmountFrozen(T::AssetId, T::AccountId, FreezeReason, T::Balance),
+		/// The amount frozen on account `who` for a reason was thawed.
+		/// \[asset_id, who, reason, amount\]
+		AmountThawed(T::AssetId, T::AccountId, FreezeReason, T::Balance),
+		/// The approved `delegate` transferred `amount` from `owner` to `destination`, leaving
+		/// `remaining` of the approval.
+		/// \[asset_id, owner, delegate, destination, amount, remaining\]
+		ApprovalUsed(T::AssetId, T::AccountId, T::AccountId, T::AccountId, T::Balance, T::Balance),
+		/// Account `delegate` was approved to transfer any amount of `owner`'s balance.
+		/// \[asset_id, owner, delegate\]
+		ApprovedUnlimited(T::AssetId, T::AccountId, T::AccountId),
 	}
 
 	#[pallet::error]
@@ -176,10 +489,8 @@ pub mod pallet {
 		Unknown,
 		/// The origin account is frozen.
 		Frozen,
-		/// The asset ID is already taken.
+		/// The asset ID is already taken, or the asset still has accounts or approvals.
 		InUse,
-		/// Invalid witness data given.
-		BadWitness,
 		/// Minimum balance should be non-zero.
 		MinBalanceZero,
 		/// No provider reference exists to allow a non-zero balance of a non-self-sufficient
@@ -191,6 +502,85 @@ pub mod pallet {
 		Unapproved,
 		/// The source account would not survive the transfer and it needs to stay alive.
 		WouldDie,
+		/// A pool cannot be made of an asset paired with itself.
+		SameAsset,
+		/// A pool for the given pair of assets already exists.
+		PoolExists,
+		/// No pool exists for the given pair of assets.
+		UnknownPool,
+		/// The pool reserves cannot cover the requested amount.
+		InsufficientLiquidity,
+		/// The trade would execute outside of the given limits.
+		SlippageExceeded,
+		/// The asset is being destroyed and can no longer be used.
+		AssetNotLive,
+		/// The asset is not in the status required by the operation.
+		IncorrectStatus,
+		/// The account already exists.
+		AlreadyExists,
+		/// The asset ID is reserved for the `Force` origin.
+		ReservedId,
+		/// The account does not exist, or it is an account of a sufficient asset, which holds no
+		/// deposit.
+		NoDeposit,
+		/// The account still holds a balance which would be burned.
+		WouldBurn,
+		/// The given airdrop ID is unknown.
+		UnknownAirdrop,
+		/// The airdrop has expired and can no longer be claimed from.
+		AirdropExpired,
+		/// The airdrop has not expired yet and cannot be reclaimed.
+		AirdropNotExpired,
+		/// The leaf was already claimed.
+		AlreadyClaimed,
+		/// The proof does not prove the claim to be part of the airdrop.
+		InvalidProof,
+		/// The witness data given does not cover the state it is checked against.
+		BadWitness,
+		/// The ABI encoded input does not start with the selector of a supported ERC20
+		/// function.
+		UnsupportedSelector,
+		/// The arguments of the ABI encoded call are missing or invalid.
+		InvalidAbiInput,
+		/// The ERC20 function only reads state: query it with `call_abi`.
+		ReadOnlyFunction,
+		/// The ERC20 function changes state: dispatch it with `dispatch_abi`.
+		StateChangingFunction,
+		/// The account has amounts frozen for the maximum number of reasons.
+		TooManyFreezes,
+		/// No amount is frozen on the account for the given reason.
+		NotFrozen,
+		/// Only self-sufficient assets can be airdropped, as recipients may have no native
+		/// balance to hold any other.
+		AirdropNotSufficient,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn offchain_worker(n: BlockNumberFor<T>) {
+			let interval = T::TopHoldersInterval::get();
+			if !interval.is_zero() && (n % interval).is_zero() {
+				Self::snapshot_top_holders(n);
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::try_state()
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config<I>, I: 'static> ValidateUnsigned for Pallet<T, I> {
+		type Call = Call<T, I>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::claim_airdrop(airdrop, index, who, amount, proof) =>
+					Self::validate_claim(*airdrop, *index, who, *amount, proof),
+				_ => InvalidTransaction::Call.into(),
+			}
+		}
 	}
 
 	#[pallet::call]
@@ -205,31 +595,20 @@ pub mod pallet {
 			let owner = ensure_signed(origin)?;
 			let admin = T::Lookup::lookup(admin)?;
 
-			ensure!(!Asset::<T, I>::contains_key(id), Error::<T, I>::InUse);
-			ensure!(!min_balance.is_zero(), Error::<T, I>::MinBalanceZero);
+			ensure!(id >= T::ReservedAssetIds::get(), Error::<T, I>::ReservedId);
+			Self::do_create(id, owner, admin, min_balance)
+		}
 
-			let deposit = T::AssetDeposit::get();
-			T::Currency::reserve(&owner, deposit)?;
+		#[pallet::weight(T::WeightInfo::create_auto())]
+		pub fn create_auto(
+			origin: OriginFor<T>,
+			admin: <T::Lookup as StaticLookup>::Source,
+			min_balance: T::Balance,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let admin = T::Lookup::lookup(admin)?;
 
-			Asset::<T, I>::insert(
-				id,
-				AssetDetails {
-					owner: owner.clone(),
-					issuer: admin.clone(),
-					admin: admin.clone(),
-					freezer: admin.clone(),
-					supply: Zero::zero(),
-					deposit,
-					min_balance,
-					is_sufficient: false,
-					accounts: 0,
-					sufficients: 0,
-					approvals: 0,
-					is_frozen: false,
-				},
-			);
-			Self::deposit_event(Event::Created(id, owner, admin));
-			Ok(())
+			Self::do_create(Self::next_asset_id(), owner, admin, min_balance)
 		}
 
 		#[pallet::weight(T::WeightInfo::force_create())]
@@ -238,13 +617,14 @@ pub mod pallet {
 			#[pallet::compact] id: T::AssetId,
 			owner: <T::Lookup as StaticLookup>::Source,
 			is_sufficient: bool,
-			#[pallet::compact] min_balance: T::Balance,
+			min_balance: T::Balance,
 		) -> DispatchResult {
 			T::ForceOrigin::ensure_origin(origin)?;
 			let owner = T::Lookup::lookup(owner)?;
 
 			ensure!(!Asset::<T, I>::contains_key(id), Error::<T, I>::InUse);
 			ensure!(!min_balance.is_zero(), Error::<T, I>::MinBalanceZero);
+			Self::note_asset_id(id);
 
 			Asset::<T, I>::insert(
 				id,
@@ -260,59 +640,90 @@ pub mod pallet {
 					accounts: 0,
 					sufficients: 0,
 					approvals: 0,
-					is_frozen: false,
+					status: AssetStatus::Live,
 				},
 			);
+			T::CallbackHandle::created(&id, &owner);
 			Self::deposit_event(Event::ForceCreated(id, owner));
 			Ok(())
 		}
 
-		#[pallet::weight(T::WeightInfo::destroy(
-			witness.accounts.saturating_sub(witness.sufficients),
- 			witness.sufficients,
- 			witness.approvals,
- 		))]
-		pub fn destroy(
+		#[pallet::weight(T::WeightInfo::start_destroy())]
+		pub fn start_destroy(
 			origin: OriginFor<T>,
 			#[pallet::compact] id: T::AssetId,
-			witness: DestroyWitness,
-		) -> DispatchResultWithPostInfo {
+		) -> DispatchResult {
 			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
 				Ok(_) => None,
 				Err(origin) => Some(ensure_signed(origin)?),
 			};
-			Asset::<T, I>::try_mutate_exists(id, |maybe_details| {
-				let mut details = maybe_details.take().ok_or(Error::<T, I>::Unknown)?;
+			Asset::<T, I>::try_mutate(id, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T, I>::Unknown)?;
 				if let Some(check_owner) = maybe_check_owner {
 					ensure!(details.owner == check_owner, Error::<T, I>::NoPermission);
 				}
-				ensure!(details.accounts <= witness.accounts, Error::<T, I>::BadWitness);
-				ensure!(details.sufficients <= witness.sufficients, Error::<T, I>::BadWitness);
-				ensure!(details.approvals <= witness.approvals, Error::<T, I>::BadWitness);
+				ensure!(details.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 
-				for (who, v) in Account::<T, I>::drain_prefix(id) {
-					Self::dead_account(id, &who, &mut details, v.sufficient);
-				}
-				debug_assert_eq!(details.accounts, 0);
+				details.status = AssetStatus::Destroying;
+
+				Self::deposit_event(Event::DestructionStarted(id));
+				Ok(())
+			})
+		}
+
+		#[pallet::weight(T::WeightInfo::destroy_accounts(*max))]
+		pub fn destroy_accounts(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			#[pallet::compact] max: u32,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			let removed = Self::do_destroy_accounts(id, max)?;
+			Ok(Some(T::WeightInfo::destroy_accounts(removed)).into())
+		}
+
+		#[pallet::weight(T::WeightInfo::destroy_approvals(*max))]
+		pub fn destroy_approvals(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			#[pallet::compact] max: u32,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			let removed = Self::do_destroy_approvals(id, max)?;
+			Ok(Some(T::WeightInfo::destroy_approvals(removed)).into())
+		}
+
+		#[pallet::weight(T::WeightInfo::finish_destroy())]
+		pub fn finish_destroy(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			Asset::<T, I>::try_mutate_exists(id, |maybe_details| {
+				let details = maybe_details.take().ok_or(Error::<T, I>::Unknown)?;
+				ensure!(details.status == AssetStatus::Destroying, Error::<T, I>::IncorrectStatus);
+				ensure!(details.accounts == 0, Error::<T, I>::InUse);
+				ensure!(details.approvals == 0, Error::<T, I>::InUse);
 				debug_assert_eq!(details.sufficients, 0);
+				debug_assert!(details.supply.is_zero());
 
 				let metadata = Metadata::<T, I>::take(&id);
+				let extended_deposit =
+					ExtendedMetadata::<T, I>::take(&id).map_or(Zero::zero(), |m| m.deposit);
 				T::Currency::unreserve(
 					&details.owner,
-					details.deposit.saturating_add(metadata.deposit),
+					details
+						.deposit
+						.saturating_add(metadata.deposit)
+						.saturating_add(extended_deposit),
 				);
 
-				for ((owner, _), approval) in Approvals::<T, I>::drain_prefix((&id,)) {
-					T::Currency::unreserve(&owner, approval.deposit);
-				}
+				T::CallbackHandle::destroyed(&id);
 				Self::deposit_event(Event::Destroyed(id));
-
-				Ok(Some(T::WeightInfo::destroy(
-					details.accounts.saturating_sub(details.sufficients),
-					details.sufficients,
-					details.approvals,
-				))
-				.into())
+				Ok(())
 			})
 		}
 
@@ -321,7 +732,7 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			#[pallet::compact] id: T::AssetId,
 			beneficiary: <T::Lookup as StaticLookup>::Source,
-			#[pallet::compact] amount: T::Balance,
+			amount: T::Balance,
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 			let beneficiary = T::Lookup::lookup(beneficiary)?;
@@ -334,7 +745,7 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			#[pallet::compact] id: T::AssetId,
 			who: <T::Lookup as StaticLookup>::Source,
-			#[pallet::compact] amount: T::Balance,
+			amount: T::Balance,
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 			let who = T::Lookup::lookup(who)?;
@@ -349,13 +760,13 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			#[pallet::compact] id: T::AssetId,
 			target: <T::Lookup as StaticLookup>::Source,
-			#[pallet::compact] amount: T::Balance,
+			amount: T::Balance,
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 			let dest = T::Lookup::lookup(target)?;
 
 			let f = TransferFlags { keep_alive: false, best_effort: false, burn_dust: false };
-			Self::do_transfer(id, &origin, &dest, amount, None, f).map(|_| ())
+			Self::do_transfer(id, &origin, &dest, amount, None, Some(&origin), f).map(|_| ())
 		}
 
 		#[pallet::weight(T::WeightInfo::transfer_keep_alive())]
@@ -363,13 +774,13 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			#[pallet::compact] id: T::AssetId,
 			target: <T::Lookup as StaticLookup>::Source,
-			#[pallet::compact] amount: T::Balance,
+			amount: T::Balance,
 		) -> DispatchResult {
 			let source = ensure_signed(origin)?;
 			let dest = T::Lookup::lookup(target)?;
 
 			let f = TransferFlags { keep_alive: true, best_effort: false, burn_dust: false };
-			Self::do_transfer(id, &source, &dest, amount, None, f).map(|_| ())
+			Self::do_transfer(id, &source, &dest, amount, None, Some(&source), f).map(|_| ())
 		}
 
 		#[pallet::weight(T::WeightInfo::force_transfer())]
@@ -378,14 +789,15 @@ pub mod pallet {
 			#[pallet::compact] id: T::AssetId,
 			source: <T::Lookup as StaticLookup>::Source,
 			dest: <T::Lookup as StaticLookup>::Source,
-			#[pallet::compact] amount: T::Balance,
+			amount: T::Balance,
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 			let source = T::Lookup::lookup(source)?;
 			let dest = T::Lookup::lookup(dest)?;
 
 			let f = TransferFlags { keep_alive: false, best_effort: false, burn_dust: false };
-			Self::do_transfer(id, &source, &dest, amount, Some(origin), f).map(|_| ())
+			Self::do_transfer(id, &source, &dest, amount, Some(origin.clone()), Some(&origin), f)
+				.map(|_| ())
 		}
 
 		#[pallet::weight(T::WeightInfo::freeze())]
@@ -397,6 +809,7 @@ pub mod pallet {
 			let origin = ensure_signed(origin)?;
 
 			let d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 			ensure!(&origin == &d.freezer, Error::<T, I>::NoPermission);
 			let who = T::Lookup::lookup(who)?;
 			ensure!(Account::<T, I>::contains_key(id, &who), Error::<T, I>::BalanceZero);
@@ -416,6 +829,7 @@ pub mod pallet {
 			let origin = ensure_signed(origin)?;
 
 			let details = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(details.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 			ensure!(&origin == &details.admin, Error::<T, I>::NoPermission);
 			let who = T::Lookup::lookup(who)?;
 			ensure!(Account::<T, I>::contains_key(id, &who), Error::<T, I>::BalanceZero);
@@ -426,6 +840,79 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Freeze `amount` of asset `id` on account `who` for `reason`, replacing any amount
+		/// frozen for the same reason before. The amounts frozen for all reasons add up, must not
+		/// exceed the balance of `who`, and must stay on the account until thawed with
+		/// `thaw_amount`.
+		///
+		/// Origin must be Signed and the sender should be the Freezer of the asset `id`.
+		#[pallet::weight(T::WeightInfo::freeze_amount())]
+		pub fn freeze_amount(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			who: <T::Lookup as StaticLookup>::Source,
+			amount: T::Balance,
+			reason: FreezeReason,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T, I>::BalanceZero);
+
+			let d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
+			ensure!(&origin == &d.freezer, Error::<T, I>::NoPermission);
+			let who = T::Lookup::lookup(who)?;
+			ensure!(Account::<T, I>::contains_key(id, &who), Error::<T, I>::BalanceZero);
+			let balance = Account::<T, I>::get(id, &who).balance;
+
+			Freezes::<T, I>::try_mutate(id, &who, |freezes| -> DispatchResult {
+				match freezes.iter_mut().find(|(r, _)| *r == reason) {
+					Some((_, frozen)) => *frozen = amount,
+					None => freezes
+						.try_push((reason, amount))
+						.map_err(|_| Error::<T, I>::TooManyFreezes)?,
+				}
+				let frozen = freezes
+					.iter()
+					.fold(T::Balance::zero(), |total, (_, amount)| total.saturating_add(*amount));
+				ensure!(frozen <= balance, Error::<T, I>::BalanceLow);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T, I>::AmountFrozen(id, who, reason, amount));
+			Ok(())
+		}
+
+		/// Thaw the amount of asset `id` frozen on account `who` for `reason`.
+		///
+		/// Origin must be Signed and the sender should be the Freezer of the asset `id`.
+		#[pallet::weight(T::WeightInfo::thaw_amount())]
+		pub fn thaw_amount(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			who: <T::Lookup as StaticLookup>::Source,
+			reason: FreezeReason,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			let details = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(details.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
+			ensure!(&origin == &details.freezer, Error::<T, I>::NoPermission);
+			let who = T::Lookup::lookup(who)?;
+
+			let mut freezes = Freezes::<T, I>::get(id, &who);
+			let index =
+				freezes.iter().position(|(r, _)| *r == reason).ok_or(Error::<T, I>::NotFrozen)?;
+			let (_, amount) = freezes.remove(index);
+			if freezes.is_empty() {
+				Freezes::<T, I>::remove(id, &who);
+			} else {
+				Freezes::<T, I>::insert(id, &who, freezes);
+			}
+
+			Self::deposit_event(Event::<T, I>::AmountThawed(id, who, reason, amount));
+			Ok(())
+		}
+
 		#[pallet::weight(T::WeightInfo::freeze_asset())]
 		pub fn freeze_asset(
 			origin: OriginFor<T>,
@@ -435,9 +922,10 @@ pub mod pallet {
 
 			Asset::<T, I>::try_mutate(id, |maybe_details| {
 				let d = maybe_details.as_mut().ok_or(Error::<T, I>::Unknown)?;
+				ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 				ensure!(&origin == &d.freezer, Error::<T, I>::NoPermission);
 
-				d.is_frozen = true;
+				d.status = AssetStatus::Frozen;
 
 				Self::deposit_event(Event::<T, I>::AssetFrozen(id));
 				Ok(())
@@ -453,9 +941,10 @@ pub mod pallet {
 
 			Asset::<T, I>::try_mutate(id, |maybe_details| {
 				let d = maybe_details.as_mut().ok_or(Error::<T, I>::Unknown)?;
+				ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 				ensure!(&origin == &d.admin, Error::<T, I>::NoPermission);
 
-				d.is_frozen = false;
+				d.status = AssetStatus::Live;
 
 				Self::deposit_event(Event::<T, I>::AssetThawed(id));
 				Ok(())
@@ -473,13 +962,16 @@ pub mod pallet {
 
 			Asset::<T, I>::try_mutate(id, |maybe_details| {
 				let details = maybe_details.as_mut().ok_or(Error::<T, I>::Unknown)?;
+				ensure!(details.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 				ensure!(&origin == &details.owner, Error::<T, I>::NoPermission);
 				if details.owner == owner {
 					return Ok(());
 				}
 
 				let metadata_deposit = Metadata::<T, I>::get(id).deposit;
-				let deposit = details.deposit + metadata_deposit;
+				let extended_deposit =
+					ExtendedMetadata::<T, I>::get(id).map_or(Zero::zero(), |m| m.deposit);
+				let deposit = details.deposit + metadata_deposit + extended_deposit;
 
 				// Move the deposit to the new owner.
 				T::Currency::repatriate_reserved(&details.owner, &owner, deposit, Reserved)?;
@@ -506,6 +998,7 @@ pub mod pallet {
 
 			Asset::<T, I>::try_mutate(id, |maybe_details| {
 				let details = maybe_details.as_mut().ok_or(Error::<T, I>::Unknown)?;
+				ensure!(details.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 				ensure!(&origin == &details.owner, Error::<T, I>::NoPermission);
 
 				details.issuer = issuer.clone();
@@ -533,6 +1026,7 @@ pub mod pallet {
 				symbol.clone().try_into().map_err(|_| Error::<T, I>::BadMetadata)?;
 
 			let d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 			ensure!(&origin == &d.owner, Error::<T, I>::NoPermission);
 
 			Metadata::<T, I>::try_mutate_exists(id, |metadata| {
@@ -573,6 +1067,7 @@ pub mod pallet {
 			let origin = ensure_signed(origin)?;
 
 			let d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 			ensure!(&origin == &d.owner, Error::<T, I>::NoPermission);
 
 			Metadata::<T, I>::try_mutate_exists(id, |metadata| {
@@ -600,7 +1095,8 @@ pub mod pallet {
 			let bounded_symbol: BoundedVec<u8, T::StringLimit> =
 				symbol.clone().try_into().map_err(|_| Error::<T, I>::BadMetadata)?;
 
-			ensure!(Asset::<T, I>::contains_key(id), Error::<T, I>::Unknown);
+			let d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 			Metadata::<T, I>::try_mutate_exists(id, |metadata| {
 				let deposit = metadata.take().map_or(Zero::zero(), |m| m.deposit);
 				*metadata = Some(AssetMetadata {
@@ -624,6 +1120,7 @@ pub mod pallet {
 			T::ForceOrigin::ensure_origin(origin)?;
 
 			let d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 			Metadata::<T, I>::try_mutate_exists(id, |metadata| {
 				let deposit = metadata.take().ok_or(Error::<T, I>::Unknown)?.deposit;
 				T::Currency::unreserve(&d.owner, deposit);
@@ -632,6 +1129,71 @@ pub mod pallet {
 			})
 		}
 
+		#[pallet::weight(T::WeightInfo::set_extended_metadata(
+			(description.len() + uri.len() + website.len()) as u32
+		))]
+		pub fn set_extended_metadata(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			description: Vec<u8>,
+			uri: Vec<u8>,
+			logo_hash: Option<[u8; 32]>,
+			website: Vec<u8>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			Self::do_set_extended_metadata(
+				id,
+				Some(origin),
+				description,
+				uri,
+				logo_hash,
+				website,
+				false,
+			)
+		}
+
+		#[pallet::weight(T::WeightInfo::clear_extended_metadata())]
+		pub fn clear_extended_metadata(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			Self::do_clear_extended_metadata(id, Some(origin))
+		}
+
+		#[pallet::weight(T::WeightInfo::force_set_extended_metadata(
+			(description.len() + uri.len() + website.len()) as u32
+		))]
+		pub fn force_set_extended_metadata(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			description: Vec<u8>,
+			uri: Vec<u8>,
+			logo_hash: Option<[u8; 32]>,
+			website: Vec<u8>,
+			is_frozen: bool,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			Self::do_set_extended_metadata(
+				id,
+				None,
+				description,
+				uri,
+				logo_hash,
+				website,
+				is_frozen,
+			)
+		}
+
+		#[pallet::weight(T::WeightInfo::force_clear_extended_metadata())]
+		pub fn force_clear_extended_metadata(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			Self::do_clear_extended_metadata(id, None)
+		}
+
 		#[pallet::weight(T::WeightInfo::force_asset_status())]
 		pub fn force_asset_status(
 			origin: OriginFor<T>,
@@ -640,7 +1202,7 @@ pub mod pallet {
 			issuer: <T::Lookup as StaticLookup>::Source,
 			admin: <T::Lookup as StaticLookup>::Source,
 			freezer: <T::Lookup as StaticLookup>::Source,
-			#[pallet::compact] min_balance: T::Balance,
+			min_balance: T::Balance,
 			is_sufficient: bool,
 			is_frozen: bool,
 		) -> DispatchResult {
@@ -648,13 +1210,14 @@ pub mod pallet {
 
 			Asset::<T, I>::try_mutate(id, |maybe_asset| {
 				let mut asset = maybe_asset.take().ok_or(Error::<T, I>::Unknown)?;
+				ensure!(asset.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 				asset.owner = T::Lookup::lookup(owner)?;
 				asset.issuer = T::Lookup::lookup(issuer)?;
 				asset.admin = T::Lookup::lookup(admin)?;
 				asset.freezer = T::Lookup::lookup(freezer)?;
 				asset.min_balance = min_balance;
 				asset.is_sufficient = is_sufficient;
-				asset.is_frozen = is_frozen;
+				asset.status = if is_frozen { AssetStatus::Frozen } else { AssetStatus::Live };
 				*maybe_asset = Some(asset);
 
 				Self::deposit_event(Event::AssetStatusChanged(id));
@@ -667,37 +1230,38 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			#[pallet::compact] id: T::AssetId,
 			delegate: <T::Lookup as StaticLookup>::Source,
-			#[pallet::compact] amount: T::Balance,
+			amount: T::Balance,
 		) -> DispatchResult {
 			let owner = ensure_signed(origin)?;
 			let delegate = T::Lookup::lookup(delegate)?;
 
-			let mut d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
-			ensure!(!d.is_frozen, Error::<T, I>::Frozen);
-			Approvals::<T, I>::try_mutate(
-				(id, &owner, &delegate),
-				|maybe_approved| -> DispatchResult {
-					let mut approved = match maybe_approved.take() {
-						// an approval already exists and is being updated
-						Some(a) => a,
-						// a new approval is created
-						None => {
-							d.approvals.saturating_inc();
-							Default::default()
-						}
-					};
-					let deposit_required = T::ApprovalDeposit::get();
-					if approved.deposit < deposit_required {
-						T::Currency::reserve(&owner, deposit_required - approved.deposit)?;
-						approved.deposit = deposit_required;
-					}
-					approved.amount = approved.amount.saturating_add(amount);
-					*maybe_approved = Some(approved);
-					Ok(())
-				},
-			)?;
-			Asset::<T, I>::insert(id, d);
-			Self::deposit_event(Event::ApprovedTransfer(id, owner, delegate, amount));
+			let allowance = Self::do_approve_transfer(id, &owner, &delegate, amount, false)?;
+			Self::deposit_event(Event::ApprovedTransfer(id, owner, delegate, amount, allowance));
+
+			Ok(())
+		}
+
+		/// Approve `delegate` to transfer any amount of asset `id` from the sender's account, as
+		/// with an ERC20 allowance of the maximum `uint256`.
+		///
+		/// Transfers by the delegate leave the approval as it is, until it is cancelled with
+		/// `cancel_approval`. An existing approval becomes unlimited, and reserves no further
+		/// deposit.
+		///
+		/// Origin must be Signed.
+		///
+		/// Emits `ApprovedUnlimited` on success.
+		#[pallet::weight(T::WeightInfo::approve_unlimited())]
+		pub fn approve_unlimited(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+
+			Self::do_approve_transfer(id, &owner, &delegate, Zero::zero(), true)?;
+			Self::deposit_event(Event::ApprovedUnlimited(id, owner, delegate));
 
 			Ok(())
 		}
@@ -711,8 +1275,10 @@ pub mod pallet {
 			let owner = ensure_signed(origin)?;
 			let delegate = T::Lookup::lookup(delegate)?;
 			let mut d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 			let approval =
 				Approvals::<T, I>::take((id, &owner, &delegate)).ok_or(Error::<T, I>::Unknown)?;
+			Self::unindex_approval(id, &owner, &delegate);
 			T::Currency::unreserve(&owner, approval.deposit);
 
 			d.approvals.saturating_dec();
@@ -730,6 +1296,7 @@ pub mod pallet {
 			delegate: <T::Lookup as StaticLookup>::Source,
 		) -> DispatchResult {
 			let mut d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
 			T::ForceOrigin::try_origin(origin)
 				.map(|_| ())
 				.or_else(|origin| -> DispatchResult {
@@ -743,6 +1310,7 @@ pub mod pallet {
 
 			let approval =
 				Approvals::<T, I>::take((id, &owner, &delegate)).ok_or(Error::<T, I>::Unknown)?;
+			Self::unindex_approval(id, &owner, &delegate);
 			T::Currency::unreserve(&owner, approval.deposit);
 			d.approvals.saturating_dec();
 			Asset::<T, I>::insert(id, d);
@@ -757,38 +1325,214 @@ pub mod pallet {
 			#[pallet::compact] id: T::AssetId,
 			owner: <T::Lookup as StaticLookup>::Source,
 			destination: <T::Lookup as StaticLookup>::Source,
-			#[pallet::compact] amount: T::Balance,
-		) -> DispatchResult {
+			amount: T::Balance,
+		) -> DispatchResultWithPostInfo {
 			let delegate = ensure_signed(origin)?;
 			let owner = T::Lookup::lookup(owner)?;
 			let destination = T::Lookup::lookup(destination)?;
 
-			Approvals::<T, I>::try_mutate_exists(
-				(id, &owner, delegate),
-				|maybe_approved| -> DispatchResult {
-					let mut approved = maybe_approved.take().ok_or(Error::<T, I>::Unapproved)?;
-					let remaining =
-						approved.amount.checked_sub(&amount).ok_or(Error::<T, I>::Unapproved)?;
-
-					let f =
-						TransferFlags { keep_alive: false, best_effort: false, burn_dust: false };
-					Self::do_transfer(id, &owner, &destination, amount, None, f)?;
-
-					if remaining.is_zero() {
-						T::Currency::unreserve(&owner, approved.deposit);
-						Asset::<T, I>::mutate(id, |maybe_details| {
-							if let Some(details) = maybe_details {
-								details.approvals.saturating_dec();
-							}
-						});
-					} else {
-						approved.amount = remaining;
-						*maybe_approved = Some(approved);
+			let key = (id, &owner, &delegate);
+			let mut approved = Approvals::<T, I>::get(key).ok_or(Error::<T, I>::Unapproved)?;
+			let remaining = if approved.unlimited {
+				approved.allowance()
+			} else {
+				approved.amount.checked_sub(&amount).ok_or(Error::<T, I>::Unapproved)?
+			};
+
+			let f = TransferFlags { keep_alive: false, best_effort: false, burn_dust: false };
+			Self::do_transfer(id, &owner, &destination, amount, None, Some(&delegate), f)?;
+
+			// An unlimited approval is left as it is, sparing the write.
+			let actual_weight = if approved.unlimited {
+				Some(T::WeightInfo::transfer_approved_unlimited())
+			} else if remaining.is_zero() {
+				Approvals::<T, I>::remove(key);
+				Self::unindex_approval(id, &owner, &delegate);
+				T::Currency::unreserve(&owner, approved.deposit);
+				Asset::<T, I>::mutate(id, |maybe_details| {
+					if let Some(details) = maybe_details {
+						details.approvals.saturating_dec();
 					}
-					Ok(())
-				},
-			)?;
-			Ok(())
+				});
+				None
+			} else {
+				approved.amount = remaining;
+				Approvals::<T, I>::insert(key, approved);
+				None
+			};
+
+			Self::deposit_event(Event::TransferredApproved(
+				id,
+				owner.clone(),
+				delegate.clone(),
+				destination.clone(),
+				amount,
+			));
+			Self::deposit_event(Event::ApprovalUsed(
+				id,
+				owner,
+				delegate,
+				destination,
+				amount,
+				remaining,
+			));
+			Ok(actual_weight.into())
+		}
+
+		#[pallet::weight(T::WeightInfo::touch())]
+		pub fn touch(origin: OriginFor<T>, #[pallet::compact] id: T::AssetId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_touch(id, &who, T::AssetAccountDeposit::get())
+		}
+
+		#[pallet::weight(T::WeightInfo::refund())]
+		pub fn refund(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			allow_burn: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_refund(id, who, allow_burn)
+		}
+
+		#[pallet::weight(T::WeightInfo::create_pool())]
+		pub fn create_pool(
+			origin: OriginFor<T>,
+			#[pallet::compact] asset_a: T::AssetId,
+			#[pallet::compact] asset_b: T::AssetId,
+			#[pallet::compact] lp_asset: T::AssetId,
+		) -> DispatchResult {
+			let creator = ensure_signed(origin)?;
+			Self::do_create_pool(&creator, asset_a, asset_b, lp_asset)
+		}
+
+		#[pallet::weight(T::WeightInfo::add_liquidity())]
+		pub fn add_liquidity(
+			origin: OriginFor<T>,
+			#[pallet::compact] asset_a: T::AssetId,
+			#[pallet::compact] asset_b: T::AssetId,
+			amount_a_desired: T::Balance,
+			amount_b_desired: T::Balance,
+			amount_a_min: T::Balance,
+			amount_b_min: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_add_liquidity(
+				&who,
+				asset_a,
+				asset_b,
+				amount_a_desired,
+				amount_b_desired,
+				amount_a_min,
+				amount_b_min,
+			)
+		}
+
+		#[pallet::weight(T::WeightInfo::remove_liquidity())]
+		pub fn remove_liquidity(
+			origin: OriginFor<T>,
+			#[pallet::compact] asset_a: T::AssetId,
+			#[pallet::compact] asset_b: T::AssetId,
+			lp_amount: T::Balance,
+			amount_a_min: T::Balance,
+			amount_b_min: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_remove_liquidity(&who, asset_a, asset_b, lp_amount, amount_a_min, amount_b_min)
+		}
+
+		#[pallet::weight(T::WeightInfo::swap_exact_in())]
+		pub fn swap_exact_in(
+			origin: OriginFor<T>,
+			#[pallet::compact] asset_in: T::AssetId,
+			#[pallet::compact] asset_out: T::AssetId,
+			amount_in: T::Balance,
+			amount_out_min: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_swap_exact_in(&who, asset_in, asset_out, amount_in, amount_out_min)
+		}
+
+		#[pallet::weight(T::WeightInfo::swap_exact_out())]
+		pub fn swap_exact_out(
+			origin: OriginFor<T>,
+			#[pallet::compact] asset_in: T::AssetId,
+			#[pallet::compact] asset_out: T::AssetId,
+			amount_out: T::Balance,
+			amount_in_max: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_swap_exact_out(&who, asset_in, asset_out, amount_out, amount_in_max)
+		}
+
+		/// Create an airdrop of asset `id` to the `recipients` leaves of the Merkle tree with root
+		/// `merkle_root`, which can be claimed until block `expires_at`.
+		///
+		/// The budget of `total` is minted if `mint` is set, which the issuer of the asset must do,
+		/// and otherwise transferred from the origin. The asset must be self-sufficient.
+		#[pallet::weight(T::WeightInfo::create_airdrop())]
+		#[transactional]
+		pub fn create_airdrop(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			merkle_root: MerkleHash,
+			#[pallet::compact] recipients: u32,
+			total: T::Balance,
+			expires_at: T::BlockNumber,
+			mint: bool,
+		) -> DispatchResult {
+			let creator = ensure_signed(origin)?;
+			Self::do_create_airdrop(creator, id, merkle_root, recipients, total, expires_at, mint)
+		}
+
+		/// Claim `amount` of airdrop `airdrop` for `who`, the leaf at `index` of its tree.
+		///
+		/// This is an unsigned transaction, so that recipients need no funds to claim. Anybody may
+		/// submit it on their behalf.
+		#[pallet::weight(T::WeightInfo::claim_airdrop(proof.len() as u32))]
+		pub fn claim_airdrop(
+			origin: OriginFor<T>,
+			#[pallet::compact] airdrop: AirdropId,
+			#[pallet::compact] index: u32,
+			who: T::AccountId,
+			amount: T::Balance,
+			proof: Vec<MerkleHash>,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			Self::do_claim_airdrop(airdrop, index, who, amount, &proof)
+		}
+
+		/// Remove the expired airdrop `airdrop` and return what is left of its budget and its
+		/// deposit to its creator. The budget of an asset being destroyed is left to be destroyed
+		/// with it.
+		///
+		/// `claim_words` must be at least the number of words of its claimed bitmap, one for
+		/// every 128 recipients.
+		#[pallet::weight(T::WeightInfo::reclaim_airdrop(*claim_words))]
+		#[transactional]
+		pub fn reclaim_airdrop(
+			origin: OriginFor<T>,
+			#[pallet::compact] airdrop: AirdropId,
+			#[pallet::compact] claim_words: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_reclaim_airdrop(who, airdrop, claim_words)
+		}
+
+		/// Dispatch the ABI encoded ERC20 call `input` on asset `id`: `transfer`, `approve` or
+		/// `transferFrom`, with the accounts `Config::AddressMapping` maps the addresses to.
+		///
+		/// `approve` replaces an existing approval, as in ERC20. The view functions are queried
+		/// with the `call_abi` runtime API instead.
+		#[pallet::weight(Pallet::<T, I>::abi_weight(input))]
+		pub fn dispatch_abi(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			input: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let call = Erc20Call::decode(&input).map_err(Error::<T, I>::from)?;
+			Self::dispatch_erc20(who, id, call).map(|_| ())
 		}
 	}
 }
@@ -821,38 +1565,230 @@ mod functions {
 			Asset::<T, I>::get(id).map(|x| x.supply).unwrap_or_else(Zero::zero)
 		}
 
+		/// Get the metadata of an asset `id`, empty if it has none.
+		pub fn asset_metadata(
+			id: T::AssetId,
+		) -> AssetMetadata<DepositBalanceOf<T, I>, BoundedVec<u8, T::StringLimit>> {
+			Metadata::<T, I>::get(id)
+		}
+
+		/// Get the amount of asset `id` frozen on `who` by `freeze_amount`, over all reasons.
+		pub fn frozen_amount(id: T::AssetId, who: &T::AccountId) -> T::Balance {
+			Freezes::<T, I>::get(id, who)
+				.iter()
+				.fold(Zero::zero(), |total: T::Balance, (_, amount)| total.saturating_add(*amount))
+		}
+
+		/// The balance of `who` that must stay on the account: what `T::Freezer` and
+		/// `freeze_amount` froze, or `None` if neither froze any.
+		pub(super) fn frozen_balance(id: T::AssetId, who: &T::AccountId) -> Option<T::Balance> {
+			let frozen = Self::frozen_amount(id, who);
+			match T::Freezer::frozen_balance(id, who) {
+				Some(other) => Some(other.saturating_add(frozen)),
+				None if frozen.is_zero() => None,
+				None => Some(frozen),
+			}
+		}
+
+		/// Get the amount of asset `id` that `delegate` may still transfer from `owner`, the
+		/// maximum balance if the approval is unlimited.
+		pub fn allowance(
+			id: T::AssetId,
+			owner: &T::AccountId,
+			delegate: &T::AccountId,
+		) -> T::Balance {
+			Approvals::<T, I>::get((id, owner, delegate))
+				.map(|a| a.allowance())
+				.unwrap_or_else(Zero::zero)
+		}
+
+		/// Whether `delegate` may transfer any amount of asset `id` from `owner`.
+		pub fn is_unlimited_approval(
+			id: T::AssetId,
+			owner: &T::AccountId,
+			delegate: &T::AccountId,
+		) -> bool {
+			Approvals::<T, I>::get((id, owner, delegate)).map_or(false, |a| a.unlimited)
+		}
+
+		/// Record the approval of `delegate` by `owner` for asset `id` in the indexes by account.
+		pub(super) fn index_approval(
+			id: T::AssetId,
+			owner: &T::AccountId,
+			delegate: &T::AccountId,
+		) {
+			OwnerApprovals::<T, I>::insert((owner, id, delegate), ());
+			DelegateApprovals::<T, I>::insert((delegate, id, owner), ());
+		}
+
+		/// Remove the approval of `delegate` by `owner` for asset `id` from the indexes by
+		/// account.
+		pub(super) fn unindex_approval(
+			id: T::AssetId,
+			owner: &T::AccountId,
+			delegate: &T::AccountId,
+		) {
+			OwnerApprovals::<T, I>::remove((owner, id, delegate));
+			DelegateApprovals::<T, I>::remove((delegate, id, owner));
+		}
+
+		/// Approve `delegate` to transfer a further `amount` of asset `id` from `owner`, or any
+		/// amount if `unlimited`, and return the allowance.
+		pub(super) fn do_approve_transfer(
+			id: T::AssetId,
+			owner: &T::AccountId,
+			delegate: &T::AccountId,
+			amount: T::Balance,
+			unlimited: bool,
+		) -> Result<T::Balance, DispatchError> {
+			let mut d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
+			ensure!(d.status != AssetStatus::Frozen, Error::<T, I>::Frozen);
+			let allowance = Approvals::<T, I>::try_mutate(
+				(id, owner, delegate),
+				|maybe_approved| -> Result<T::Balance, DispatchError> {
+					let mut approved = match maybe_approved.take() {
+						// an approval already exists and is being updated
+						Some(a) => a,
+						// a new approval is created
+						None => {
+							d.approvals.saturating_inc();
+							Self::index_approval(id, owner, delegate);
+							Default::default()
+						}
+					};
+					let deposit_required = T::ApprovalDeposit::get();
+					if approved.deposit < deposit_required {
+						T::Currency::reserve(owner, deposit_required - approved.deposit)?;
+						approved.deposit = deposit_required;
+					}
+					approved.amount = approved.amount.saturating_add(amount);
+					approved.unlimited |= unlimited;
+					let allowance = approved.allowance();
+					*maybe_approved = Some(approved);
+					Ok(allowance)
+				},
+			)?;
+			Asset::<T, I>::insert(id, d);
+			Ok(allowance)
+		}
+
+		/// The id the next `create_auto` will use.
+		pub fn next_asset_id() -> T::AssetId {
+			NextAssetId::<T, I>::get()
+				.unwrap_or_else(Zero::zero)
+				.max(T::ReservedAssetIds::get())
+		}
+
+		/// Move `NextAssetId` past `id`, which is about to be taken, so that `create_auto` never
+		/// collides with an explicitly chosen id. Saturates at the largest id, which `create_auto`
+		/// then reports as in use.
+		pub(super) fn note_asset_id(id: T::AssetId) {
+			if id >= Self::next_asset_id() {
+				NextAssetId::<T, I>::put(id.saturating_add(One::one()));
+			}
+		}
+
+		pub(super) fn do_create(
+			id: T::AssetId,
+			owner: T::AccountId,
+			admin: T::AccountId,
+			min_balance: T::Balance,
+		) -> DispatchResult {
+			ensure!(!Asset::<T, I>::contains_key(id), Error::<T, I>::InUse);
+			ensure!(!min_balance.is_zero(), Error::<T, I>::MinBalanceZero);
+
+			let deposit = T::AssetDeposit::get();
+			T::Currency::reserve(&owner, deposit)?;
+			Self::note_asset_id(id);
+
+			Asset::<T, I>::insert(
+				id,
+				AssetDetails {
+					owner: owner.clone(),
+					issuer: admin.clone(),
+					admin: admin.clone(),
+					freezer: admin.clone(),
+					supply: Zero::zero(),
+					deposit,
+					min_balance,
+					is_sufficient: false,
+					accounts: 0,
+					sufficients: 0,
+					approvals: 0,
+					status: AssetStatus::Live,
+				},
+			);
+			T::CallbackHandle::created(&id, &owner);
+			Self::deposit_event(Event::Created(id, owner, admin));
+			Ok(())
+		}
+
+		/// Pay for a new account of `who`, with `AssetAccountDeposit` reserved from
+		/// `maybe_depositor` if the asset is not sufficient.
 		pub(super) fn new_account(
 			who: &T::AccountId,
 			d: &mut AssetDetails<T::Balance, T::AccountId, DepositBalanceOf<T, I>>,
-		) -> Result<bool, DispatchError> {
+			maybe_depositor: Option<&T::AccountId>,
+		) -> Result<ExistenceReasonOf<T, I>, DispatchError> {
 			let accounts = d.accounts.checked_add(1).ok_or(ArithmeticError::Overflow)?;
-			let is_sufficient = if d.is_sufficient {
+			let deposit = T::AssetAccountDeposit::get();
+			let reason = if d.is_sufficient {
 				frame_system::Pallet::<T>::inc_sufficients(who);
 				d.sufficients += 1;
-				true
+				ExistenceReason::Sufficient
+			} else if let Some(depositor) = maybe_depositor.filter(|_| !deposit.is_zero()) {
+				T::Currency::reserve(depositor, deposit)?;
+				ExistenceReason::DepositFrom(depositor.clone(), deposit)
 			} else {
 				frame_system::Pallet::<T>::inc_consumers(who)
 					.map_err(|_| Error::<T, I>::NoProvider)?;
-				false
+				ExistenceReason::Consumer
 			};
 			d.accounts = accounts;
-			Ok(is_sufficient)
+			Ok(reason)
 		}
 
+		/// Drop the references an account of asset `what` placed on `who`.
+		///
+		/// An account holding its own deposit is kept, with a zero balance, until it is refunded.
+		/// Pass `force` to remove it regardless; the caller is then responsible for the deposit. A
+		/// deposit paid by another account is returned to it.
 		pub(super) fn dead_account(
 			what: T::AssetId,
 			who: &T::AccountId,
 			d: &mut AssetDetails<T::Balance, T::AccountId, DepositBalanceOf<T, I>>,
-			sufficient: bool,
-		) {
-			if sufficient {
-				d.sufficients = d.sufficients.saturating_sub(1);
-				frame_system::Pallet::<T>::dec_sufficients(who);
-			} else {
-				frame_system::Pallet::<T>::dec_consumers(who);
+			reason: &ExistenceReasonOf<T, I>,
+			force: bool,
+		) -> DeadConsequence {
+			match *reason {
+				ExistenceReason::Consumer => frame_system::Pallet::<T>::dec_consumers(who),
+				ExistenceReason::Sufficient => {
+					d.sufficients = d.sufficients.saturating_sub(1);
+					frame_system::Pallet::<T>::dec_sufficients(who);
+				},
+				ExistenceReason::DepositHeld(_) if !force => return DeadConsequence::Keep,
+				ExistenceReason::DepositHeld(_) => {},
+				ExistenceReason::DepositFrom(ref depositor, deposit) => {
+					T::Currency::unreserve(depositor, deposit);
+				},
 			}
 			d.accounts = d.accounts.saturating_sub(1);
-			T::Freezer::died(what, who)
+			Freezes::<T, I>::remove(what, who);
+			T::Freezer::died(what, who);
+			DeadConsequence::Remove
+		}
+
+		/// Whether the self-sufficient reference an asset account gives `who` may not be dropped.
+		///
+		/// That is the case when it is the only reference keeping the system account alive (no
+		/// providers and no other sufficients) while something still depends on the account
+		/// through a consumer reference. Non-sufficient asset accounts only hold a consumer
+		/// reference themselves, so they never keep an account alive.
+		pub(super) fn sufficient_is_required(who: &T::AccountId) -> bool {
+			frame_system::Pallet::<T>::providers(who) == 0 &&
+				frame_system::Pallet::<T>::sufficients(who) == 1 &&
+				frame_system::Pallet::<T>::consumers(who) > 0
 		}
 
 		pub(super) fn can_increase(
@@ -864,6 +1800,9 @@ mod functions {
 				Some(details) => details,
 				None => return DepositConsequence::UnknownAsset,
 			};
+			if details.status == AssetStatus::Destroying {
+				return DepositConsequence::UnknownAsset;
+			}
 			if details.supply.checked_add(&amount).is_none() {
 				return DepositConsequence::Overflow;
 			}
@@ -875,6 +1814,8 @@ mod functions {
 				if amount < details.min_balance {
 					return DepositConsequence::BelowMinimum;
 				}
+			}
+			if !Account::<T, I>::contains_key(id, who) {
 				if !details.is_sufficient && frame_system::Pallet::<T>::providers(who) == 0 {
 					return DepositConsequence::CannotCreate;
 				}
@@ -901,15 +1842,17 @@ mod functions {
 			if details.supply.checked_sub(&amount).is_none() {
 				return Underflow;
 			}
-			if details.is_frozen {
-				return Frozen;
+			match details.status {
+				AssetStatus::Live => {},
+				AssetStatus::Frozen => return Frozen,
+				AssetStatus::Destroying => return UnknownAsset,
 			}
 			let account = Account::<T, I>::get(id, who);
 			if account.is_frozen {
 				return Frozen;
 			}
 			if let Some(rest) = account.balance.checked_sub(&amount) {
-				if let Some(frozen) = T::Freezer::frozen_balance(id, who) {
+				if let Some(frozen) = Self::frozen_balance(id, who) {
 					match frozen.checked_add(&details.min_balance) {
 						Some(required) if rest < required => return Frozen,
 						None => return Overflow,
@@ -917,8 +1860,8 @@ mod functions {
 					}
 				}
 
-				let is_provider = false;
-				let is_required = is_provider && !frame_system::Pallet::<T>::can_dec_provider(who);
+				let is_required =
+					account.reason.is_sufficient() && Self::sufficient_is_required(who);
 				let must_keep_alive = keep_alive || is_required;
 
 				if rest < details.min_balance {
@@ -943,19 +1886,20 @@ mod functions {
 			keep_alive: bool,
 		) -> Result<T::Balance, DispatchError> {
 			let details = Asset::<T, I>::get(id).ok_or_else(|| Error::<T, I>::Unknown)?;
-			ensure!(!details.is_frozen, Error::<T, I>::Frozen);
+			ensure!(details.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
+			ensure!(details.status != AssetStatus::Frozen, Error::<T, I>::Frozen);
 
 			let account = Account::<T, I>::get(id, who);
 			ensure!(!account.is_frozen, Error::<T, I>::Frozen);
 
-			let amount = if let Some(frozen) = T::Freezer::frozen_balance(id, who) {
+			let amount = if let Some(frozen) = Self::frozen_balance(id, who) {
 				// Frozen balance: account CANNOT be deleted
 				let required =
 					frozen.checked_add(&details.min_balance).ok_or(ArithmeticError::Overflow)?;
 				account.balance.saturating_sub(required)
 			} else {
-				let is_provider = false;
-				let is_required = is_provider && !frame_system::Pallet::<T>::can_dec_provider(who);
+				let is_required =
+					account.reason.is_sufficient() && Self::sufficient_is_required(who);
 				if keep_alive || is_required {
 					// We want to keep the account around.
 					account.balance.saturating_sub(details.min_balance)
@@ -1009,9 +1953,11 @@ mod functions {
 			amount: T::Balance,
 			maybe_check_issuer: Option<T::AccountId>,
 		) -> DispatchResult {
-			Self::increase_balance(id, beneficiary, amount, |details| -> DispatchResult {
-				if let Some(check_issuer) = maybe_check_issuer {
-					ensure!(&check_issuer == &details.issuer, Error::<T, I>::NoPermission);
+			// The issuer pays the deposit of an account it creates.
+			let maybe_depositor = maybe_check_issuer.as_ref();
+			Self::increase_balance(id, beneficiary, amount, maybe_depositor, |details| {
+				if let Some(check_issuer) = maybe_depositor {
+					ensure!(check_issuer == &details.issuer, Error::<T, I>::NoPermission);
 				}
 				debug_assert!(
 					T::Balance::max_value() - details.supply >= amount,
@@ -1024,10 +1970,13 @@ mod functions {
 			Ok(())
 		}
 
+		/// Credit `amount` to `beneficiary`, creating its account with a deposit from
+		/// `maybe_depositor` if there is none.
 		pub(super) fn increase_balance(
 			id: T::AssetId,
 			beneficiary: &T::AccountId,
 			amount: T::Balance,
+			maybe_depositor: Option<&T::AccountId>,
 			check: impl FnOnce(
 				&mut AssetDetails<T::Balance, T::AccountId, DepositBalanceOf<T, I>>,
 			) -> DispatchResult,
@@ -1042,15 +1991,21 @@ mod functions {
 
 				check(details)?;
 
-				Account::<T, I>::try_mutate(id, beneficiary, |t| -> DispatchResult {
-					let new_balance = t.balance.saturating_add(amount);
-					ensure!(new_balance >= details.min_balance, TokenError::BelowMinimum);
-					if t.balance.is_zero() {
-						t.sufficient = Self::new_account(beneficiary, details)?;
-					}
-					t.balance = new_balance;
-					Ok(())
-				})?;
+				Account::<T, I>::try_mutate_exists(
+					id,
+					beneficiary,
+					|maybe_account| -> DispatchResult {
+						let is_new = maybe_account.is_none();
+						let t = maybe_account.get_or_insert_with(Default::default);
+						let new_balance = t.balance.saturating_add(amount);
+						ensure!(new_balance >= details.min_balance, TokenError::BelowMinimum);
+						if is_new {
+							t.reason = Self::new_account(beneficiary, details, maybe_depositor)?;
+						}
+						t.balance = new_balance;
+						Ok(())
+					},
+				)?;
 				Ok(())
 			})?;
 			Ok(())
@@ -1103,15 +2058,25 @@ mod functions {
 					id,
 					target,
 					|maybe_account| -> DispatchResult {
-						let mut account = maybe_account.take().unwrap_or_default();
+						let mut account = match maybe_account.take() {
+							Some(account) => account,
+							// Only a best-effort debit of nothing gets here; there is no
+							// account to reap.
+							None => {
+								debug_assert!(actual.is_zero(), "checked in prep; qed");
+								return Ok(());
+							},
+						};
 						debug_assert!(account.balance >= actual, "checked in prep; qed");
 
 						// Make the debit.
 						account.balance = account.balance.saturating_sub(actual);
 						*maybe_account = if account.balance < details.min_balance {
 							debug_assert!(account.balance.is_zero(), "checked in prep; qed");
-							Self::dead_account(id, target, details, account.sufficient);
-							None
+							match Self::dead_account(id, target, details, &account.reason, false) {
+								DeadConsequence::Remove => None,
+								DeadConsequence::Keep => Some(account),
+							}
 						} else {
 							Some(account)
 						};
@@ -1125,12 +2090,229 @@ mod functions {
 			Ok(actual)
 		}
 
+		/// Remove up to `max` accounts of the asset `id`, which must be `Destroying`. Returns the
+		/// number of accounts removed.
+		pub(super) fn do_destroy_accounts(id: T::AssetId, max: u32) -> Result<u32, DispatchError> {
+			Asset::<T, I>::try_mutate(id, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T, I>::Unknown)?;
+				ensure!(details.status == AssetStatus::Destroying, Error::<T, I>::IncorrectStatus);
+
+				let dead = Account::<T, I>::iter_prefix(id).take(max as usize).collect::<Vec<_>>();
+				for (who, account) in dead.iter() {
+					Account::<T, I>::remove(id, who);
+					details.supply = details.supply.saturating_sub(account.balance);
+					if let ExistenceReason::DepositHeld(deposit) = account.reason {
+						T::Currency::unreserve(who, deposit);
+					}
+					Self::dead_account(id, who, details, &account.reason, true);
+				}
+				let removed = dead.len() as u32;
+
+				Self::deposit_event(Event::AccountsDestroyed(id, removed, details.accounts));
+				Ok(removed)
+			})
+		}
+
+		/// Remove up to `max` approvals of the asset `id`, which must be `Destroying`, releasing
+		/// their deposits. Returns the number of approvals removed.
+		pub(super) fn do_destroy_approvals(id: T::AssetId, max: u32) -> Result<u32, DispatchError> {
+			Asset::<T, I>::try_mutate(id, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T, I>::Unknown)?;
+				ensure!(details.status == AssetStatus::Destroying, Error::<T, I>::IncorrectStatus);
+
+				let approvals =
+					Approvals::<T, I>::iter_prefix((id,)).take(max as usize).collect::<Vec<_>>();
+				for ((owner, delegate), approval) in approvals.iter() {
+					Approvals::<T, I>::remove((id, owner, delegate));
+					Self::unindex_approval(id, owner, delegate);
+					T::Currency::unreserve(owner, approval.deposit);
+					details.approvals.saturating_dec();
+				}
+				let removed = approvals.len() as u32;
+
+				Self::deposit_event(Event::ApprovalsDestroyed(id, removed, details.approvals));
+				Ok(removed)
+			})
+		}
+
+		/// Set the extended metadata of asset `id`. With `maybe_check_owner` the caller must own the
+		/// asset, the metadata must not be frozen and the deposit is adjusted to its new size;
+		/// otherwise the existing deposit is kept.
+		pub(super) fn do_set_extended_metadata(
+			id: T::AssetId,
+			maybe_check_owner: Option<T::AccountId>,
+			description: Vec<u8>,
+			uri: Vec<u8>,
+			logo_hash: Option<[u8; 32]>,
+			website: Vec<u8>,
+			is_frozen: bool,
+		) -> DispatchResult {
+			let bounded_description: BoundedVec<u8, T::DescriptionLimit> =
+				description.clone().try_into().map_err(|_| Error::<T, I>::BadMetadata)?;
+			let bounded_uri: BoundedVec<u8, T::UrlLimit> =
+				uri.clone().try_into().map_err(|_| Error::<T, I>::BadMetadata)?;
+			let bounded_website: BoundedVec<u8, T::UrlLimit> =
+				website.clone().try_into().map_err(|_| Error::<T, I>::BadMetadata)?;
+
+			let d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
+
+			ExtendedMetadata::<T, I>::try_mutate_exists(id, |metadata| {
+				let old_deposit = metadata.as_ref().map_or(Zero::zero(), |m| m.deposit);
+				let deposit = match maybe_check_owner {
+					Some(origin) => {
+						ensure!(origin == d.owner, Error::<T, I>::NoPermission);
+						ensure!(
+							metadata.as_ref().map_or(true, |m| !m.is_frozen),
+							Error::<T, I>::NoPermission
+						);
+
+						let hash_len = if logo_hash.is_some() { 32 } else { 0 };
+						let len = description.len() + uri.len() + website.len() + hash_len;
+						let new_deposit = T::MetadataDepositPerByte::get()
+							.saturating_mul((len as u32).into())
+							.saturating_add(T::MetadataDepositBase::get());
+
+						if new_deposit > old_deposit {
+							T::Currency::reserve(&origin, new_deposit - old_deposit)?;
+						} else {
+							T::Currency::unreserve(&origin, old_deposit - new_deposit);
+						}
+						new_deposit
+					},
+					None => old_deposit,
+				};
+
+				*metadata = Some(AssetExtendedMetadata {
+					deposit,
+					description: bounded_description,
+					uri: bounded_uri,
+					logo_hash,
+					website: bounded_website,
+					is_frozen,
+				});
+
+				Self::deposit_event(Event::ExtendedMetadataSet(
+					id,
+					description,
+					uri,
+					logo_hash,
+					website,
+					is_frozen,
+				));
+				Ok(())
+			})
+		}
+
+		/// Clear the extended metadata of asset `id`, returning its deposit to the owner. With
+		/// `maybe_check_owner` the caller must own the asset.
+		pub(super) fn do_clear_extended_metadata(
+			id: T::AssetId,
+			maybe_check_owner: Option<T::AccountId>,
+		) -> DispatchResult {
+			let d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
+			if let Some(check_owner) = maybe_check_owner {
+				ensure!(check_owner == d.owner, Error::<T, I>::NoPermission);
+			}
+
+			let deposit = ExtendedMetadata::<T, I>::take(id).ok_or(Error::<T, I>::Unknown)?.deposit;
+			T::Currency::unreserve(&d.owner, deposit);
+			Self::deposit_event(Event::ExtendedMetadataCleared(id));
+			Ok(())
+		}
+
+		/// Create a zero-balance account of asset `id` for `who`, reserving `deposit` from it, or
+		/// placing a consumer reference on it if `deposit` is zero.
+		pub(super) fn do_touch(
+			id: T::AssetId,
+			who: &T::AccountId,
+			deposit: DepositBalanceOf<T, I>,
+		) -> DispatchResult {
+			ensure!(!Account::<T, I>::contains_key(id, who), Error::<T, I>::AlreadyExists);
+			ensure!(frame_system::Pallet::<T>::providers(who) > 0, Error::<T, I>::NoProvider);
+			Asset::<T, I>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T, I>::Unknown)?;
+				ensure!(details.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
+				details.accounts =
+					details.accounts.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+
+				let reason = if deposit.is_zero() {
+					frame_system::Pallet::<T>::inc_consumers(who)
+						.map_err(|_| Error::<T, I>::NoProvider)?;
+					ExistenceReason::Consumer
+				} else {
+					T::Currency::reserve(who, deposit)?;
+					ExistenceReason::DepositHeld(deposit)
+				};
+				Account::<T, I>::insert(
+					id,
+					who,
+					AssetBalance {
+						balance: Zero::zero(),
+						is_frozen: false,
+						reason,
+						extra: T::Extra::default(),
+					},
+				);
+				Ok(())
+			})?;
+			Self::deposit_event(Event::Touched(id, who.clone(), deposit));
+			Ok(())
+		}
+
+		/// Remove the account of asset `id` held by `who` and return its deposit. Any balance left
+		/// in the account is burned, which must be allowed with `allow_burn`.
+		pub(super) fn do_refund(
+			id: T::AssetId,
+			who: T::AccountId,
+			allow_burn: bool,
+		) -> DispatchResult {
+			let account =
+				Account::<T, I>::try_get(id, &who).map_err(|_| Error::<T, I>::NoDeposit)?;
+			let deposit = match account.reason {
+				ExistenceReason::DepositHeld(deposit) => deposit,
+				// Returned to the account that paid it by `dead_account`.
+				ExistenceReason::DepositFrom(_, deposit) => deposit,
+				// Touched while `AssetAccountDeposit` was zero, or credited without a deposit.
+				ExistenceReason::Consumer => Zero::zero(),
+				ExistenceReason::Sufficient => return Err(Error::<T, I>::NoDeposit.into()),
+			};
+			ensure!(account.balance.is_zero() || allow_burn, Error::<T, I>::WouldBurn);
+			ensure!(!account.is_frozen, Error::<T, I>::Frozen);
+			ensure!(!Freezes::<T, I>::contains_key(id, &who), Error::<T, I>::Frozen);
+
+			Asset::<T, I>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T, I>::Unknown)?;
+				match details.status {
+					AssetStatus::Live => {},
+					AssetStatus::Frozen => return Err(Error::<T, I>::Frozen.into()),
+					AssetStatus::Destroying => return Err(Error::<T, I>::AssetNotLive.into()),
+				}
+
+				details.supply = details.supply.saturating_sub(account.balance);
+				if let ExistenceReason::DepositHeld(deposit) = account.reason {
+					T::Currency::unreserve(&who, deposit);
+				}
+				Self::dead_account(id, &who, details, &account.reason, true);
+				Account::<T, I>::remove(id, &who);
+				Ok(())
+			})?;
+
+			if !account.balance.is_zero() {
+				Self::deposit_event(Event::Burned(id, who.clone(), account.balance));
+			}
+			Self::deposit_event(Event::Refunded(id, who, deposit));
+			Ok(())
+		}
+
 		pub(super) fn do_transfer(
 			id: T::AssetId,
 			source: &T::AccountId,
 			dest: &T::AccountId,
 			amount: T::Balance,
 			maybe_need_admin: Option<T::AccountId>,
+			maybe_depositor: Option<&T::AccountId>,
 			f: TransferFlags,
 		) -> Result<T::Balance, DispatchError> {
 			// Early exist if no-op.
@@ -1170,14 +2352,17 @@ mod functions {
 				debug_assert!(source_account.balance >= debit, "checked in prep; qed");
 				source_account.balance = source_account.balance.saturating_sub(debit);
 
-				Account::<T, I>::try_mutate(id, &dest, |a| -> DispatchResult {
+				Account::<T, I>::try_mutate_exists(id, &dest, |maybe_account| -> DispatchResult {
+					let is_new = maybe_account.is_none();
+					let a = maybe_account.get_or_insert_with(Default::default);
+
 					// Calculate new balance; this will not saturate since it's already checked in prep.
 					debug_assert!(a.balance.checked_add(&credit).is_some(), "checked in prep; qed");
 					let new_balance = a.balance.saturating_add(credit);
 
 					// Create a new account if there wasn't one already.
-					if a.balance.is_zero() {
-						a.sufficient = Self::new_account(&dest, details)?;
+					if is_new {
+						a.reason = Self::new_account(&dest, details, maybe_depositor)?;
 					}
 
 					a.balance = new_balance;
@@ -1187,8 +2372,12 @@ mod functions {
 				// Remove source account if it's now dead.
 				if source_account.balance < details.min_balance {
 					debug_assert!(source_account.balance.is_zero(), "checked in prep; qed");
-					Self::dead_account(id, &source, details, source_account.sufficient);
-					Account::<T, I>::remove(id, &source);
+					match Self::dead_account(id, &source, details, &source_account.reason, false) {
+						DeadConsequence::Remove => Account::<T, I>::remove(id, &source),
+						DeadConsequence::Keep => {
+							Account::<T, I>::insert(id, &source, &source_account)
+						},
+					}
 				} else {
 					Account::<T, I>::insert(id, &source, &source_account)
 				}
@@ -1207,48 +2396,41 @@ mod types {
 	use frame_support::pallet_prelude::*;
 
 	use frame_support::traits::{fungible, tokens::BalanceConversion};
-	use sp_runtime::{traits::Convert, FixedPointNumber, FixedPointOperand, FixedU128};
+	use sp_runtime::traits::Convert;
 
 	pub(super) type DepositBalanceOf<T, I = ()> =
 		<<T as Config<I>>::Currency as Currency<<T as SystemConfig>::AccountId>>::Balance;
 
+	pub(super) type ExistenceReasonOf<T, I = ()> =
+		ExistenceReason<DepositBalanceOf<T, I>, <T as SystemConfig>::AccountId>;
+
 	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen)]
 	pub struct AssetDetails<Balance, AccountId, DepositBalance> {
 		/// Can change `owner`, `issuer`, `freezer` and `admin` accounts.
-		pub(super) owner: AccountId,
+		pub owner: AccountId,
 		/// Can mint tokens.
-		pub(super) issuer: AccountId,
+		pub issuer: AccountId,
 		/// Can thaw tokens, force transfers and burn tokens from any account.
-		pub(super) admin: AccountId,
+		pub admin: AccountId,
 		/// Can freeze tokens.
-		pub(super) freezer: AccountId,
+		pub freezer: AccountId,
 		/// The total supply across all accounts.
-		pub(super) supply: Balance,
+		pub supply: Balance,
 		/// The balance deposited for this asset. This pays for the data stored here.
-		pub(super) deposit: DepositBalance,
+		pub deposit: DepositBalance,
 		/// The ED for virtual accounts.
-		pub(super) min_balance: Balance,
+		pub min_balance: Balance,
 		/// If `true`, then any account with this asset is given a provider reference. Otherwise, it
 		/// requires a consumer reference.
-		pub(super) is_sufficient: bool,
+		pub is_sufficient: bool,
 		/// The total number of accounts.
-		pub(super) accounts: u32,
+		pub accounts: u32,
 		/// The total number of accounts for which we have placed a self-sufficient reference.
-		pub(super) sufficients: u32,
+		pub sufficients: u32,
 		/// The total number of approvals.
-		pub(super) approvals: u32,
-		/// Whether the asset is frozen for non-admin transfers.
-		pub(super) is_frozen: bool,
-	}
-
-	impl<Balance, AccountId, DepositBalance> AssetDetails<Balance, AccountId, DepositBalance> {
-		pub fn destroy_witness(&self) -> DestroyWitness {
-			DestroyWitness {
-				accounts: self.accounts,
-				sufficients: self.sufficients,
-				approvals: self.approvals,
-			}
-		}
+		pub approvals: u32,
+		/// The lifecycle status of the asset.
+		pub status: AssetStatus,
 	}
 
 	/// Data concerning an approval.
@@ -1256,21 +2438,72 @@ mod types {
 	pub struct Approval<Balance, DepositBalance> {
 		/// The amount of funds approved for the balance transfer from the owner to some delegated
 		/// target.
-		pub(super) amount: Balance,
+		pub amount: Balance,
 		/// The amount reserved on the owner's account to hold this item in storage.
-		pub(super) deposit: DepositBalance,
+		pub deposit: DepositBalance,
+		/// Whether the delegate may transfer any amount, in which case transfers leave `amount`
+		/// untouched.
+		pub unlimited: bool,
+	}
+
+	impl<Balance: Bounded + Copy, DepositBalance> Approval<Balance, DepositBalance> {
+		/// The amount the delegate may still transfer: the maximum balance if unlimited.
+		pub fn allowance(&self) -> Balance {
+			if self.unlimited {
+				Balance::max_value()
+			} else {
+				self.amount
+			}
+		}
 	}
 
 	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, Default, MaxEncodedLen)]
-	pub struct AssetBalance<Balance, Extra> {
+	pub struct AssetBalance<Balance, DepositBalance, Extra, AccountId> {
 		/// The balance.
-		pub(super) balance: Balance,
+		pub balance: Balance,
 		/// Whether the account is frozen.
-		pub(super) is_frozen: bool,
-		/// `true` if this balance gave the account a self-sufficient reference.
-		pub(super) sufficient: bool,
+		pub is_frozen: bool,
+		/// What pays for this account to exist.
+		pub reason: ExistenceReason<DepositBalance, AccountId>,
 		/// Additional "sidecar" data, in case some other pallet wants to use this storage item.
-		pub(super) extra: Extra,
+		pub extra: Extra,
+	}
+
+	/// The reason an asset account exists.
+	///
+	/// `Consumer` and `Sufficient` encode the same as the `sufficient: bool` this replaces, so
+	/// accounts stored before deposits existed decode unchanged.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen)]
+	pub enum ExistenceReason<Balance, AccountId> {
+		/// A consumer reference was placed on the account.
+		Consumer,
+		/// The asset is sufficient and gave the account a self-sufficient reference.
+		Sufficient,
+		/// A deposit was reserved from the account. It is kept, even with a zero balance, until
+		/// refunded.
+		DepositHeld(Balance),
+		/// A deposit was reserved from the account that first sent the asset to this one. It is
+		/// returned to that account once this one is removed.
+		DepositFrom(AccountId, Balance),
+	}
+
+	impl<Balance, AccountId> Default for ExistenceReason<Balance, AccountId> {
+		fn default() -> Self {
+			ExistenceReason::Consumer
+		}
+	}
+
+	impl<Balance, AccountId> ExistenceReason<Balance, AccountId> {
+		pub(super) fn is_sufficient(&self) -> bool {
+			matches!(self, ExistenceReason::Sufficient)
+		}
+	}
+
+	/// Whether an account left without a balance is removed from storage.
+	#[derive(Copy, Clone, PartialEq, Eq)]
+	pub(super) enum DeadConsequence {
+		Remove,
+		Keep,
 	}
 
 	#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug, MaxEncodedLen)]
@@ -1278,31 +2511,95 @@ mod types {
 		/// The balance deposited for this metadata.
 		///
 		/// This pays for the data stored in this struct.
-		pub(super) deposit: DepositBalance,
+		pub deposit: DepositBalance,
 		/// The user friendly name of this asset. Limited in length by `StringLimit`.
-		pub(super) name: BoundedString,
+		pub name: BoundedString,
 		/// The ticker symbol for this asset. Limited in length by `StringLimit`.
-		pub(super) symbol: BoundedString,
+		pub symbol: BoundedString,
 		/// The number of decimals this asset uses to represent one unit.
-		pub(super) decimals: u8,
+		pub decimals: u8,
 		/// Whether the asset metadata may be changed by a non Force origin.
+		pub is_frozen: bool,
+	}
+
+	/// Metadata for wallets and token lists, kept apart from `AssetMetadata` and paid for by its
+	/// own deposit.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen)]
+	pub struct AssetExtendedMetadata<DepositBalance, BoundedDescription, BoundedUrl> {
+		/// The balance deposited for this metadata.
+		pub(super) deposit: DepositBalance,
+		/// A human readable description of the asset. Limited in length by `DescriptionLimit`.
+		pub(super) description: BoundedDescription,
+		/// A URI pointing to further, off-chain metadata. Limited in length by `UrlLimit`.
+		pub(super) uri: BoundedUrl,
+		/// The hash of the asset's logo, so that wallets can verify whatever they fetch.
+		pub(super) logo_hash: Option<[u8; 32]>,
+		/// The project website. Limited in length by `UrlLimit`.
+		pub(super) website: BoundedUrl,
+		/// Whether the extended metadata may be changed by a non Force origin.
 		pub(super) is_frozen: bool,
 	}
 
-	/// Witness data for the destroy transactions.
+	/// The lifecycle status of an asset.
+	///
+	/// Encodes the same as the `is_frozen: bool` it replaces, so `Live` and `Frozen` assets stored
+	/// before `Destroying` existed decode unchanged.
 	#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen)]
-	pub struct DestroyWitness {
-		/// The number of accounts holding the asset.
-		#[codec(compact)]
-		pub(super) accounts: u32,
-		/// The number of accounts holding the asset with a self-sufficient reference.
-		#[codec(compact)]
-		pub(super) sufficients: u32,
-		/// The number of transfer-approvals of the asset.
-		#[codec(compact)]
-		pub(super) approvals: u32,
+	pub enum AssetStatus {
+		/// The asset is active and may be used normally.
+		Live,
+		/// The asset is frozen for non-admin transfers.
+		Frozen,
+		/// The asset is being destroyed. No further mutation is possible; only the `destroy_*`
+		/// calls may make progress.
+		Destroying,
+	}
+
+	/// A constant-product liquidity pool.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen)]
+	pub struct PoolInfo<AssetId, AccountId> {
+		/// The asset minted to liquidity providers as a claim on the reserves.
+		pub(super) lp_asset: AssetId,
+		/// The account that created the pool, which holds the deposit of `lp_asset`.
+		pub(super) creator: AccountId,
+	}
+
+	/// The code of the reason an amount is frozen for, such as the order it enforces. The
+	/// freezer chooses the codes.
+	pub type FreezeReason = u32;
+
+	/// The index of an airdrop.
+	pub type AirdropId = u32;
+
+	/// An airdrop of an asset to the leaves of a Merkle tree.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen)]
+	pub struct AirdropInfo<AccountId, AssetId, Balance, DepositBalance, BlockNumber> {
+		/// Funded the airdrop and may reclaim it once it expires.
+		pub creator: AccountId,
+		/// The asset airdropped.
+		pub asset: AssetId,
+		/// The root of the tree of claims.
+		pub merkle_root: [u8; 32],
+		/// The number of leaves of the tree.
+		pub recipients: u32,
+		/// The budget set aside for the airdrop.
+		pub total: Balance,
+		/// The amount claimed so far.
+		pub claimed: Balance,
+		/// The deposit reserved from the creator.
+		pub deposit: DepositBalance,
+		/// The block from which the airdrop can no longer be claimed.
+		pub expires_at: BlockNumber,
 	}
 
+	pub(super) type AirdropInfoOf<T, I = ()> = AirdropInfo<
+		<T as SystemConfig>::AccountId,
+		<T as Config<I>>::AssetId,
+		<T as Config<I>>::Balance,
+		DepositBalanceOf<T, I>,
+		<T as SystemConfig>::BlockNumber,
+	>;
+
 	pub trait FrozenBalance<AssetId, AccountId, Balance> {
 		fn frozen_balance(asset: AssetId, who: &AccountId) -> Option<Balance>;
 
@@ -1317,6 +2614,19 @@ mod types {
 		fn died(_: AssetId, _: &AccountId) {}
 	}
 
+	pub trait AssetsCallback<AssetId, AccountId> {
+		/// Called when asset `id` has been created, with `owner` as its owner.
+		fn created(id: &AssetId, owner: &AccountId);
+
+		/// Called when asset `id` has been destroyed.
+		fn destroyed(id: &AssetId);
+	}
+
+	impl<AssetId, AccountId> AssetsCallback<AssetId, AccountId> for () {
+		fn created(_: &AssetId, _: &AccountId) {}
+		fn destroyed(_: &AssetId) {}
+	}
+
 	#[derive(Copy, Clone, PartialEq, Eq)]
 	pub(super) struct TransferFlags {
 		pub(super) keep_alive: bool,
@@ -1364,8 +2674,6 @@ mod types {
 		T: Config<I>,
 		I: 'static,
 		CON: Convert<BalanceOf<F, T>, AssetBalanceOf<T, I>>,
-		BalanceOf<F, T>: FixedPointOperand + Zero,
-		AssetBalanceOf<T, I>: FixedPointOperand + Zero,
 	{
 		type Error = ConversionError;
 
@@ -1380,9 +2688,12 @@ mod types {
 			// make sure we don't divide by zero
 			ensure!(!min_balance.is_zero(), ConversionError::MinBalanceZero);
 			let balance = CON::convert(balance);
-			// balance * asset.min_balance / min_balance
-			Ok(FixedU128::saturating_from_rational(asset.min_balance, min_balance)
-				.saturating_mul_int(balance))
+			// balance * asset.min_balance / min_balance, split on the quotient and remainder of
+			// balance / min_balance so that it works in the asset balance type of any width.
+			let (whole, part) = (balance / min_balance, balance % min_balance);
+			Ok(whole
+				.saturating_mul(asset.min_balance)
+				.saturating_add(part.saturating_mul(asset.min_balance) / min_balance))
 		}
 	}
 }
@@ -1469,7 +2780,7 @@ mod impl_fungibles {
 			keep_alive: bool,
 		) -> Result<T::Balance, DispatchError> {
 			let f = TransferFlags { keep_alive, best_effort: false, burn_dust: false };
-			Self::do_transfer(asset, source, dest, amount, None, f)
+			Self::do_transfer(asset, source, dest, amount, None, None, f)
 		}
 	}
 
@@ -1505,7 +2816,7 @@ mod impl_fungibles {
 			who: &T::AccountId,
 			amount: Self::Balance,
 		) -> Result<Self::Balance, DispatchError> {
-			Self::increase_balance(asset, who, amount, |_| Ok(()))?;
+			Self::increase_balance(asset, who, amount, None, |_| Ok(()))?;
 			Ok(amount)
 		}
 		fn increase_balance_at_most(
@@ -1513,7 +2824,7 @@ mod impl_fungibles {
 			who: &T::AccountId,
 			amount: Self::Balance,
 		) -> Self::Balance {
-			match Self::increase_balance(asset, who, amount, |_| Ok(())) {
+			match Self::increase_balance(asset, who, amount, None, |_| Ok(())) {
 				Ok(()) => amount,
 				Err(_) => Zero::zero(),
 			}
@@ -0,0 +1,437 @@
+//! Storage migrations for the assets pallet.
+//!
+//! Each `vN` module migrates the pallet storage from version `N - 1` to `N` and is a no-op on
+//! chains already at `N` or later. Add them to the runtime's `Executive` in ascending order.
+
+use super::*;
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::{OnRuntimeUpgradeHelpersExt, PalletInfoAccess};
+use frame_support::{
+	pallet_prelude::*,
+	storage::unhashed,
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use sp_std::marker::PhantomData;
+
+/// Keep the count `item` of instance `I` from `pre_upgrade` to `post_upgrade`. The checks of all
+/// migrations run before and after all of them, so each instance needs keys of its own.
+#[cfg(feature = "try-runtime")]
+fn set_count<T: Config<I>, I: 'static>(item: &str, count: usize) {
+	let key = [Pallet::<T, I>::name(), item].concat();
+	<() as OnRuntimeUpgradeHelpersExt>::set_temp_storage(count as u32, &key);
+}
+
+/// The count `item` of instance `I` kept by `set_count`.
+#[cfg(feature = "try-runtime")]
+fn get_count<T: Config<I>, I: 'static>(item: &str) -> Option<u32> {
+	let key = [Pallet::<T, I>::name(), item].concat();
+	<() as OnRuntimeUpgradeHelpersExt>::get_temp_storage(&key)
+}
+
+pub mod v1 {
+	use super::*;
+
+	/// `AssetDetails` as stored at version 0, with an `is_frozen` flag instead of a status.
+	#[derive(Decode)]
+	pub struct OldAssetDetails<Balance, AccountId, DepositBalance> {
+		owner: AccountId,
+		issuer: AccountId,
+		admin: AccountId,
+		freezer: AccountId,
+		supply: Balance,
+		deposit: DepositBalance,
+		min_balance: Balance,
+		is_sufficient: bool,
+		accounts: u32,
+		sufficients: u32,
+		approvals: u32,
+		is_frozen: bool,
+	}
+
+	impl<Balance, AccountId, DepositBalance> OldAssetDetails<Balance, AccountId, DepositBalance> {
+		pub(super) fn migrate_to_v1(self) -> AssetDetails<Balance, AccountId, DepositBalance> {
+			let status = if self.is_frozen { AssetStatus::Frozen } else { AssetStatus::Live };
+			AssetDetails {
+				owner: self.owner,
+				issuer: self.issuer,
+				admin: self.admin,
+				freezer: self.freezer,
+				supply: self.supply,
+				deposit: self.deposit,
+				min_balance: self.min_balance,
+				is_sufficient: self.is_sufficient,
+				accounts: self.accounts,
+				sufficients: self.sufficients,
+				approvals: self.approvals,
+				status,
+			}
+		}
+	}
+
+	/// Replace the `is_frozen` flag of every `Asset` with an `AssetStatus`.
+	///
+	/// This decodes balances as the current `T::Balance`. Chains at version 0 that changed their
+	/// balance type use `v2::MigrateToV2` instead, which migrates from version 0 as well.
+	pub struct MigrateToV1<T, I = ()>(PhantomData<(T, I)>);
+	impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV1<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T, I>::on_chain_storage_version() >= 1 {
+				return T::DbWeight::get().reads(1);
+			}
+
+			let mut translated = 0u64;
+			Asset::<T, I>::translate::<
+				OldAssetDetails<T::Balance, T::AccountId, DepositBalanceOf<T, I>>,
+				_,
+			>(|_, old| {
+				translated.saturating_inc();
+				Some(old.migrate_to_v1())
+			});
+			StorageVersion::new(1).put::<Pallet<T, I>>();
+
+			T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			ensure!(Pallet::<T, I>::on_chain_storage_version() == 0, "expected storage version 0");
+			set_count::<T, I>("asset_count", Asset::<T, I>::iter_keys().count());
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T, I>::on_chain_storage_version() >= 1,
+				"expected storage version 1 or later"
+			);
+			let count: u32 = get_count::<T, I>("asset_count")
+				.ok_or("asset count not set in pre_upgrade")?;
+			let mut migrated = 0u32;
+			for (_, details) in Asset::<T, I>::iter() {
+				// Version 0 had no way of expressing an asset being destroyed.
+				ensure!(details.status != AssetStatus::Destroying, "asset migrated as destroying");
+				migrated += 1;
+			}
+			ensure!(migrated == count, "assets were lost in the migration");
+			Ok(())
+		}
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// `Approval` as stored up to version 3, with a balance of `Balance`. Version 2 writes it
+	/// widened but still without the `unlimited` flag, which `v4::MigrateToV4` adds.
+	#[derive(Encode, Decode)]
+	pub struct OldApproval<Balance, DepositBalance> {
+		pub amount: Balance,
+		pub deposit: DepositBalance,
+	}
+
+	fn widen_asset<Old: Into<New>, New, AccountId, DepositBalance>(
+		old: AssetDetails<Old, AccountId, DepositBalance>,
+	) -> AssetDetails<New, AccountId, DepositBalance> {
+		AssetDetails {
+			owner: old.owner,
+			issuer: old.issuer,
+			admin: old.admin,
+			freezer: old.freezer,
+			supply: old.supply.into(),
+			deposit: old.deposit,
+			min_balance: old.min_balance.into(),
+			is_sufficient: old.is_sufficient,
+			accounts: old.accounts,
+			sufficients: old.sufficients,
+			approvals: old.approvals,
+			status: old.status,
+		}
+	}
+
+	/// Widen every balance stored by an instance, such as from `u64` to `U256Balance`, from
+	/// `OldBalance` to `T::Balance`.
+	///
+	/// Storage versions are kept per instance, so every instance of the pallet needs this
+	/// migration. Instances whose balance type does not change pass it as `OldBalance`, and have
+	/// their entries rewritten unchanged. Chains at version 0 are migrated as `v1::MigrateToV1`
+	/// would have on the way, so this takes its place in the runtime.
+	///
+	/// The Merkle roots of open airdrops commit to amounts encoded as `OldBalance`, so their
+	/// claims no longer prove once the balance is widened. Let them expire before upgrading.
+	pub struct MigrateToV2<T, I, OldBalance>(PhantomData<(T, I, OldBalance)>);
+	impl<T: Config<I>, I: 'static, OldBalance> OnRuntimeUpgrade for MigrateToV2<T, I, OldBalance>
+	where
+		OldBalance: Decode + Into<T::Balance>,
+	{
+		fn on_runtime_upgrade() -> Weight {
+			let version = Pallet::<T, I>::on_chain_storage_version();
+			if version >= 2 {
+				return T::DbWeight::get().reads(1);
+			}
+
+			let mut translated = 0u64;
+			if version == 0 {
+				Asset::<T, I>::translate::<
+					v1::OldAssetDetails<OldBalance, T::AccountId, DepositBalanceOf<T, I>>,
+					_,
+				>(|_, old| {
+					translated.saturating_inc();
+					Some(widen_asset(old.migrate_to_v1()))
+				});
+			} else {
+				Asset::<T, I>::translate::<
+					AssetDetails<OldBalance, T::AccountId, DepositBalanceOf<T, I>>,
+					_,
+				>(|_, old| {
+					translated.saturating_inc();
+					Some(widen_asset(old))
+				});
+			}
+			Account::<T, I>::translate::<
+				AssetBalance<OldBalance, DepositBalanceOf<T, I>, T::Extra, T::AccountId>,
+				_,
+			>(|_, _, old| {
+				translated.saturating_inc();
+				Some(AssetBalance {
+					balance: old.balance.into(),
+					is_frozen: old.is_frozen,
+					reason: old.reason,
+					extra: old.extra,
+				})
+			});
+			// Approvals keep their version 2 layout, so they are rewritten raw rather than through
+			// `Approvals`, whose values have the layout of the current version.
+			let approvals = Approvals::<T, I>::iter_keys().collect::<Vec<_>>();
+			for key in approvals {
+				let key = Approvals::<T, I>::hashed_key_for(key);
+				let old = unhashed::get::<OldApproval<OldBalance, DepositBalanceOf<T, I>>>(&key);
+				if let Some(old) = old {
+					let new = OldApproval::<T::Balance, _> {
+						amount: old.amount.into(),
+						deposit: old.deposit,
+					};
+					unhashed::put(&key, &new);
+				} else {
+					unhashed::kill(&key);
+				}
+				translated.saturating_inc();
+			}
+			Airdrops::<T, I>::translate::<
+				AirdropInfo<
+					T::AccountId,
+					T::AssetId,
+					OldBalance,
+					DepositBalanceOf<T, I>,
+					T::BlockNumber,
+				>,
+				_,
+			>(|_, old| {
+				translated.saturating_inc();
+				Some(AirdropInfo {
+					creator: old.creator,
+					asset: old.asset,
+					merkle_root: old.merkle_root,
+					recipients: old.recipients,
+					total: old.total.into(),
+					claimed: old.claimed.into(),
+					deposit: old.deposit,
+					expires_at: old.expires_at,
+				})
+			});
+			StorageVersion::new(2).put::<Pallet<T, I>>();
+
+			T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T, I>::on_chain_storage_version() < 2,
+				"expected storage version 0 or 1"
+			);
+			let count = Asset::<T, I>::iter_keys().count() + Account::<T, I>::iter_keys().count();
+			set_count::<T, I>("entry_count", count);
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T, I>::on_chain_storage_version() >= 2,
+				"expected storage version 2 or later"
+			);
+			let count: u32 = get_count::<T, I>("entry_count")
+				.ok_or("entry count not set in pre_upgrade")?;
+			let migrated = Asset::<T, I>::iter().count() + Account::<T, I>::iter().count();
+			ensure!(migrated as u32 == count, "entries were lost in the migration");
+			Pallet::<T, I>::try_state()
+		}
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	/// Index every approval by owner and by delegate, for `approvals_by_owner` and
+	/// `approvals_by_delegate`.
+	pub struct MigrateToV3<T, I = ()>(PhantomData<(T, I)>);
+	impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV3<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T, I>::on_chain_storage_version() >= 3 {
+				return T::DbWeight::get().reads(1);
+			}
+
+			let mut indexed = 0u64;
+			for (id, owner, delegate) in Approvals::<T, I>::iter_keys() {
+				Pallet::<T, I>::index_approval(id, &owner, &delegate);
+				indexed.saturating_inc();
+			}
+			StorageVersion::new(3).put::<Pallet<T, I>>();
+
+			T::DbWeight::get().reads_writes(indexed + 1, 2 * indexed + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T, I>::on_chain_storage_version() <= 2,
+				"expected storage version 2 or earlier"
+			);
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T, I>::on_chain_storage_version() >= 3,
+				"expected storage version 3 or later"
+			);
+			let approvals = Approvals::<T, I>::iter_keys().count();
+			ensure!(
+				OwnerApprovals::<T, I>::iter_keys().count() == approvals &&
+					DelegateApprovals::<T, I>::iter_keys().count() == approvals,
+				"approvals were not all indexed"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v4 {
+	use super::*;
+
+	/// `Approval` as stored up to version 3, without the `unlimited` flag.
+	#[derive(Encode, Decode)]
+	pub struct OldApproval<Balance, DepositBalance> {
+		pub amount: Balance,
+		pub deposit: DepositBalance,
+	}
+
+	/// Add the `unlimited` flag to every approval, unset.
+	pub struct MigrateToV4<T, I = ()>(PhantomData<(T, I)>);
+	impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV4<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T, I>::on_chain_storage_version() >= 4 {
+				return T::DbWeight::get().reads(1);
+			}
+
+			let mut translated = 0u64;
+			Approvals::<T, I>::translate::<OldApproval<T::Balance, DepositBalanceOf<T, I>>, _>(
+				|_, old| {
+					translated.saturating_inc();
+					Some(Approval { amount: old.amount, deposit: old.deposit, unlimited: false })
+				},
+			);
+			StorageVersion::new(4).put::<Pallet<T, I>>();
+
+			T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T, I>::on_chain_storage_version() <= 3,
+				"expected storage version 3 or earlier"
+			);
+			set_count::<T, I>("approval_count", Approvals::<T, I>::iter_keys().count());
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T, I>::on_chain_storage_version() >= 4,
+				"expected storage version 4 or later"
+			);
+			let count: u32 = get_count::<T, I>("approval_count")
+				.ok_or("approval count not set in pre_upgrade")?;
+			let mut migrated = 0u32;
+			for (_, approval) in Approvals::<T, I>::iter() {
+				ensure!(!approval.unlimited, "approval migrated as unlimited");
+				migrated += 1;
+			}
+			ensure!(migrated == count, "approvals were lost in the migration");
+			Ok(())
+		}
+	}
+}
+
+pub mod v5 {
+	use super::*;
+
+	/// `PoolInfo` as stored up to version 4, without the creator of the pool.
+	#[derive(Encode, Decode)]
+	pub struct OldPoolInfo<AssetId> {
+		pub lp_asset: AssetId,
+	}
+
+	/// Tell `Config::CallbackHandle` about every asset created before there was one, as if it had
+	/// just been created, and record the pool account as the creator of every pool. Pools created
+	/// before took no deposit, so there is none to attribute.
+	pub struct MigrateToV5<T, I = ()>(PhantomData<(T, I)>);
+	impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV5<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T, I>::on_chain_storage_version() >= 5 {
+				return T::DbWeight::get().reads(1);
+			}
+
+			let mut migrated = 0u64;
+			for (id, details) in Asset::<T, I>::iter() {
+				T::CallbackHandle::created(&id, &details.owner);
+				migrated.saturating_inc();
+			}
+			Pools::<T, I>::translate::<OldPoolInfo<T::AssetId>, _>(|a, b, old| {
+				migrated.saturating_inc();
+				let creator = Pallet::<T, I>::pool_account(a, b);
+				Some(PoolInfo { lp_asset: old.lp_asset, creator })
+			});
+			StorageVersion::new(5).put::<Pallet<T, I>>();
+
+			T::DbWeight::get().reads_writes(migrated + 1, migrated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T, I>::on_chain_storage_version() <= 4,
+				"expected storage version 4 or earlier"
+			);
+			set_count::<T, I>("pool_count", Pools::<T, I>::iter_keys().count());
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T, I>::on_chain_storage_version() >= 5,
+				"expected storage version 5 or later"
+			);
+			let count: u32 =
+				get_count::<T, I>("pool_count").ok_or("pool count not set in pre_upgrade")?;
+			let migrated = Pools::<T, I>::iter().count() as u32;
+			ensure!(migrated == count, "pools were lost in the migration");
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,239 @@
+//! Test environment for the ERC20 assets pallet.
+
+use super::*;
+use crate as pallet_template;
+
+use frame_support::{construct_runtime, parameter_types, traits::Get};
+use sp_core::{H160, H256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	transaction_validity::TransactionPriority,
+};
+use std::{
+	cell::RefCell,
+	collections::{BTreeMap, BTreeSet},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Assets: pallet_template::{Pallet, Call, Storage, Config<T>, Event<T>, ValidateUnsigned},
+		WideAssets: pallet_template::<Instance2>::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::AllowAll;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Config for Test {
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+}
+
+parameter_types! {
+	pub const AssetDeposit: u64 = 1;
+	pub const ApprovalDeposit: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const DescriptionLimit: u32 = 100;
+	pub const UrlLimit: u32 = 50;
+	pub const ReservedAssetIds: u32 = 100;
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+	pub const AssetsPalletId: PalletId = PalletId(*b"py/erc20");
+	pub const PoolFee: Permill = Permill::from_perthousand(3);
+	pub const TopHolders: u32 = 3;
+	pub const TopHoldersInterval: u64 = 10;
+	pub const AirdropDeposit: u64 = 1;
+	pub const AirdropUnsignedPriority: TransactionPriority = 100;
+	pub const MaxFreezes: u32 = 2;
+}
+impl Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type AssetAccountDeposit = AssetAccountDeposit;
+	type StringLimit = StringLimit;
+	type DescriptionLimit = DescriptionLimit;
+	type UrlLimit = UrlLimit;
+	type ReservedAssetIds = ReservedAssetIds;
+	type Freezer = TestFreezer;
+	type CallbackHandle = TestCallbackHandle;
+	type Extra = u64;
+	type PalletId = AssetsPalletId;
+	type PoolFee = PoolFee;
+	type TopHolders = TopHolders;
+	type TopHoldersInterval = TopHoldersInterval;
+	type AirdropDeposit = AirdropDeposit;
+	type AirdropUnsignedPriority = AirdropUnsignedPriority;
+	type MaxFreezes = MaxFreezes;
+	type AddressMapping = AddressToAccount;
+	type WeightInfo = ();
+}
+
+// A second instance, with 256-bit balances.
+parameter_types! {
+	pub const WideStringLimit: u32 = 10;
+	pub const WidePalletId: PalletId = PalletId(*b"py/wide_");
+}
+impl Config<Instance2> for Test {
+	type Event = Event;
+	type Balance = U256Balance;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type AssetAccountDeposit = AssetAccountDeposit;
+	type StringLimit = WideStringLimit;
+	type DescriptionLimit = DescriptionLimit;
+	type UrlLimit = UrlLimit;
+	type ReservedAssetIds = ReservedAssetIds;
+	type Freezer = ();
+	type CallbackHandle = ();
+	type Extra = ();
+	type PalletId = WidePalletId;
+	type PoolFee = PoolFee;
+	type TopHolders = TopHolders;
+	type TopHoldersInterval = TopHoldersInterval;
+	type AirdropDeposit = AirdropDeposit;
+	type AirdropUnsignedPriority = AirdropUnsignedPriority;
+	type MaxFreezes = MaxFreezes;
+	type AddressMapping = AddressToAccount;
+	type WeightInfo = ();
+}
+
+/// Maps an address to the account of its low 8 bytes.
+pub struct AddressToAccount;
+impl Convert<H160, u64> for AddressToAccount {
+	fn convert(address: H160) -> u64 {
+		address.to_low_u64_be()
+	}
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum Hook {
+	Died(u32, u64),
+}
+
+thread_local! {
+	static ACCOUNT_DEPOSIT: RefCell<u64> = RefCell::new(10);
+	static FROZEN: RefCell<BTreeMap<(u32, u64), u64>> = RefCell::new(BTreeMap::new());
+	static HOOKS: RefCell<Vec<Hook>> = RefCell::new(Vec::new());
+	static CREATED: RefCell<BTreeSet<u32>> = RefCell::new(BTreeSet::new());
+}
+
+pub struct AssetAccountDeposit;
+impl Get<u64> for AssetAccountDeposit {
+	fn get() -> u64 {
+		ACCOUNT_DEPOSIT.with(|d| *d.borrow())
+	}
+}
+
+pub(crate) fn set_account_deposit(deposit: u64) {
+	ACCOUNT_DEPOSIT.with(|d| *d.borrow_mut() = deposit);
+}
+
+pub struct TestFreezer;
+impl FrozenBalance<u32, u64, u64> for TestFreezer {
+	fn frozen_balance(asset: u32, who: &u64) -> Option<u64> {
+		FROZEN.with(|f| f.borrow().get(&(asset, *who)).cloned())
+	}
+
+	fn died(asset: u32, who: &u64) {
+		HOOKS.with(|h| h.borrow_mut().push(Hook::Died(asset, *who)));
+	}
+}
+
+/// Keeps the ids of the assets created and not yet destroyed.
+pub struct TestCallbackHandle;
+impl AssetsCallback<u32, u64> for TestCallbackHandle {
+	fn created(id: &u32, _: &u64) {
+		CREATED.with(|c| c.borrow_mut().insert(*id));
+	}
+
+	fn destroyed(id: &u32) {
+		CREATED.with(|c| c.borrow_mut().remove(id));
+	}
+}
+
+pub(crate) fn created_assets() -> Vec<u32> {
+	CREATED.with(|c| c.borrow().iter().cloned().collect())
+}
+
+pub(crate) fn set_frozen_balance(asset: u32, who: u64, amount: u64) {
+	FROZEN.with(|f| f.borrow_mut().insert((asset, who), amount));
+}
+
+pub(crate) fn clear_frozen_balance(asset: u32, who: u64) {
+	FROZEN.with(|f| f.borrow_mut().remove(&(asset, who)));
+}
+
+pub(crate) fn hooks() -> Vec<Hook> {
+	HOOKS.with(|h| h.borrow().clone())
+}
+
+pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	set_account_deposit(10);
+	FROZEN.with(|f| f.borrow_mut().clear());
+	HOOKS.with(|h| h.borrow_mut().clear());
+	CREATED.with(|c| c.borrow_mut().clear());
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,348 @@
+//! Constant-product (`x * y = k`) liquidity pools over the assets of this pallet.
+//!
+//! Every pool holds its reserves in a keyless account derived from `T::PalletId` and the pair
+//! of assets. Liquidity providers receive an LP token, which is itself an asset of this pallet
+//! owned by the pool account, and whose `T::AssetDeposit` the creator of the pool holds. All
+//! balance movements go through the `fungibles` traits. Prices and shares are computed as `U256`,
+//! with products taken in full, so that they hold for balances of up to 256 bits.
+//!
+//! The first deposit into a pool locks [`MINIMUM_LIQUIDITY`] of its LP token in the pool account,
+//! so that the LP supply never returns to zero and the price of a share cannot be inflated by a
+//! first provider who mints a dust amount and then donates to the reserves. Anything sent to the
+//! pool account before the first deposit counts towards its reserves, and so its shares.
+
+use super::*;
+use frame_support::traits::tokens::fungibles::{Mutate, Transfer};
+use sp_core::U512;
+use sp_runtime::{traits::AccountIdConversion, PerThing};
+
+/// The amount of LP token locked in the pool account by the first deposit into a pool.
+pub const MINIMUM_LIQUIDITY: u128 = 1_000;
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Return the pair `(a, b)` in the order it is keyed in `Pools`.
+	pub fn pool_key(a: T::AssetId, b: T::AssetId) -> (T::AssetId, T::AssetId) {
+		if a <= b {
+			(a, b)
+		} else {
+			(b, a)
+		}
+	}
+
+	/// The account holding the reserves of the pool of `a` and `b`.
+	pub fn pool_account(a: T::AssetId, b: T::AssetId) -> T::AccountId {
+		T::PalletId::get().into_sub_account(Self::pool_key(a, b))
+	}
+
+	/// The reserves of `asset_in` and `asset_out` held by their pool, in that order.
+	pub fn pool_reserves(
+		asset_in: T::AssetId,
+		asset_out: T::AssetId,
+	) -> Result<(T::Balance, T::Balance), DispatchError> {
+		let (a, b) = Self::pool_key(asset_in, asset_out);
+		ensure!(Pools::<T, I>::contains_key(a, b), Error::<T, I>::UnknownPool);
+		let pool = Self::pool_account(a, b);
+		Ok((Self::balance(asset_in, &pool), Self::balance(asset_out, &pool)))
+	}
+
+	/// Price a swap of exactly `amount_in` of `asset_in`, returning the amount of `asset_out`
+	/// that would be received. `None` if there is no such pool or it cannot cover the trade.
+	pub fn quote_exact_in(
+		asset_in: T::AssetId,
+		asset_out: T::AssetId,
+		amount_in: T::Balance,
+	) -> Option<T::Balance> {
+		let (reserve_in, reserve_out) = Self::pool_reserves(asset_in, asset_out).ok()?;
+		Self::get_amount_out(amount_in, reserve_in, reserve_out).ok()
+	}
+
+	/// Price a swap for exactly `amount_out` of `asset_out`, returning the amount of `asset_in`
+	/// that would have to be paid. `None` if there is no such pool or it cannot cover the trade.
+	pub fn quote_exact_out(
+		asset_in: T::AssetId,
+		asset_out: T::AssetId,
+		amount_out: T::Balance,
+	) -> Option<T::Balance> {
+		let (reserve_in, reserve_out) = Self::pool_reserves(asset_in, asset_out).ok()?;
+		Self::get_amount_in(amount_out, reserve_in, reserve_out).ok()
+	}
+
+	/// The output of selling `amount_in` into a pool with the given reserves, net of the fee.
+	pub(super) fn get_amount_out(
+		amount_in: T::Balance,
+		reserve_in: T::Balance,
+		reserve_out: T::Balance,
+	) -> Result<T::Balance, DispatchError> {
+		ensure!(!amount_in.is_zero(), Error::<T, I>::BalanceZero);
+		ensure!(
+			!reserve_in.is_zero() && !reserve_out.is_zero(),
+			Error::<T, I>::InsufficientLiquidity
+		);
+		let (fee_num, fee_denom) = Self::fee_ratio();
+		let (amount_in, reserve_in, reserve_out): (U256, U256, U256) =
+			(amount_in.into(), reserve_in.into(), reserve_out.into());
+
+		// amount_out = amount_in * f * reserve_out / (reserve_in + amount_in * f)
+		let in_with_fee = Self::mul_div(amount_in, fee_num, fee_denom)?;
+		let denominator = reserve_in.checked_add(in_with_fee).ok_or(ArithmeticError::Overflow)?;
+		let amount_out = Self::mul_div(in_with_fee, reserve_out, denominator)?;
+
+		ensure!(amount_out < reserve_out, Error::<T, I>::InsufficientLiquidity);
+		Self::from_u256(amount_out)
+	}
+
+	/// The input needed to buy `amount_out` from a pool with the given reserves, fee included.
+	pub(super) fn get_amount_in(
+		amount_out: T::Balance,
+		reserve_in: T::Balance,
+		reserve_out: T::Balance,
+	) -> Result<T::Balance, DispatchError> {
+		ensure!(!amount_out.is_zero(), Error::<T, I>::BalanceZero);
+		ensure!(
+			!reserve_in.is_zero() && amount_out < reserve_out,
+			Error::<T, I>::InsufficientLiquidity
+		);
+		let (fee_num, fee_denom) = Self::fee_ratio();
+		let (amount_out, reserve_in, reserve_out): (U256, U256, U256) =
+			(amount_out.into(), reserve_in.into(), reserve_out.into());
+
+		// amount_in = reserve_in * amount_out / ((reserve_out - amount_out) * f) + 1
+		let remaining = Self::mul_div(reserve_out - amount_out, fee_num, fee_denom)?;
+		ensure!(!remaining.is_zero(), Error::<T, I>::InsufficientLiquidity);
+		let amount_in = Self::mul_div(reserve_in, amount_out, remaining)?
+			.checked_add(U256::one())
+			.ok_or(ArithmeticError::Overflow)?;
+		Self::from_u256(amount_in)
+	}
+
+	pub(super) fn do_create_pool(
+		creator: &T::AccountId,
+		asset_a: T::AssetId,
+		asset_b: T::AssetId,
+		lp_asset: T::AssetId,
+	) -> DispatchResult {
+		ensure!(asset_a != asset_b, Error::<T, I>::SameAsset);
+		for id in &[asset_a, asset_b] {
+			let d = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
+			ensure!(d.status != AssetStatus::Destroying, Error::<T, I>::AssetNotLive);
+		}
+		ensure!(lp_asset >= T::ReservedAssetIds::get(), Error::<T, I>::ReservedId);
+		ensure!(!Asset::<T, I>::contains_key(lp_asset), Error::<T, I>::InUse);
+
+		let (a, b) = Self::pool_key(asset_a, asset_b);
+		ensure!(!Pools::<T, I>::contains_key(a, b), Error::<T, I>::PoolExists);
+
+		// The creator pays the deposit of the LP asset, which the pool account cannot: it has no
+		// native balance. Pools are never removed, so the deposit stays reserved.
+		let deposit = T::AssetDeposit::get();
+		T::Currency::reserve(creator, deposit)?;
+
+		let pool = Self::pool_account(a, b);
+		Self::note_asset_id(lp_asset);
+		// Give the pool account a provider reference so that it can hold non-sufficient assets.
+		// Like the deposit, the reference is never released.
+		frame_system::Pallet::<T>::inc_providers(&pool);
+
+		Asset::<T, I>::insert(
+			lp_asset,
+			AssetDetails {
+				owner: pool.clone(),
+				issuer: pool.clone(),
+				admin: pool.clone(),
+				freezer: pool.clone(),
+				supply: Zero::zero(),
+				deposit,
+				min_balance: One::one(),
+				is_sufficient: false,
+				accounts: 0,
+				sufficients: 0,
+				approvals: 0,
+				status: AssetStatus::Live,
+			},
+		);
+		T::CallbackHandle::created(&lp_asset, &pool);
+		Pools::<T, I>::insert(a, b, PoolInfo { lp_asset, creator: creator.clone() });
+
+		Self::deposit_event(Event::PoolCreated(a, b, creator.clone(), pool, lp_asset));
+		Ok(())
+	}
+
+	pub(super) fn do_add_liquidity(
+		who: &T::AccountId,
+		asset_a: T::AssetId,
+		asset_b: T::AssetId,
+		amount_a_desired: T::Balance,
+		amount_b_desired: T::Balance,
+		amount_a_min: T::Balance,
+		amount_b_min: T::Balance,
+	) -> DispatchResult {
+		let (a, b) = Self::pool_key(asset_a, asset_b);
+		let info = Pools::<T, I>::get(a, b).ok_or(Error::<T, I>::UnknownPool)?;
+		let pool = Self::pool_account(a, b);
+		let (reserve_a, reserve_b) = Self::pool_reserves(asset_a, asset_b)?;
+		let lp_supply = Self::total_supply(info.lp_asset);
+
+		// The first deposit sets the price, together with whatever was sent to the pool account
+		// before it.
+		let (amount_a, amount_b) = if lp_supply.is_zero() {
+			(amount_a_desired, amount_b_desired)
+		} else {
+			let amount_b_optimal = Self::quote(amount_a_desired, reserve_a, reserve_b)?;
+			if amount_b_optimal <= amount_b_desired {
+				ensure!(amount_b_optimal >= amount_b_min, Error::<T, I>::SlippageExceeded);
+				(amount_a_desired, amount_b_optimal)
+			} else {
+				let amount_a_optimal = Self::quote(amount_b_desired, reserve_b, reserve_a)?;
+				debug_assert!(amount_a_optimal <= amount_a_desired);
+				ensure!(amount_a_optimal >= amount_a_min, Error::<T, I>::SlippageExceeded);
+				(amount_a_optimal, amount_b_desired)
+			}
+		};
+		ensure!(!amount_a.is_zero() && !amount_b.is_zero(), Error::<T, I>::BalanceZero);
+
+		let (minted, locked) = if lp_supply.is_zero() {
+			let total_a = reserve_a.checked_add(&amount_a).ok_or(ArithmeticError::Overflow)?;
+			let total_b = reserve_b.checked_add(&amount_b).ok_or(ArithmeticError::Overflow)?;
+			let product = Into::<U256>::into(total_a).full_mul(total_b.into());
+			// The square root of a product of two `U256` always fits a `U256`.
+			let liquidity =
+				U256::try_from(product.integer_sqrt()).map_err(|_| ArithmeticError::Overflow)?;
+			let minimum = U256::from(MINIMUM_LIQUIDITY);
+			ensure!(liquidity > minimum, Error::<T, I>::InsufficientLiquidity);
+			(Self::from_u256(liquidity - minimum)?, Self::from_u256(minimum)?)
+		} else {
+			let minted = Self::quote(amount_a, reserve_a, lp_supply)?
+				.min(Self::quote(amount_b, reserve_b, lp_supply)?);
+			(minted, Zero::zero())
+		};
+		ensure!(!minted.is_zero(), Error::<T, I>::InsufficientLiquidity);
+
+		<Self as Transfer<T::AccountId>>::transfer(asset_a, who, &pool, amount_a, false)?;
+		<Self as Transfer<T::AccountId>>::transfer(asset_b, who, &pool, amount_b, false)?;
+		if !locked.is_zero() {
+			<Self as Mutate<T::AccountId>>::mint_into(info.lp_asset, &pool, locked)?;
+		}
+		<Self as Mutate<T::AccountId>>::mint_into(info.lp_asset, who, minted)?;
+
+		Self::deposit_event(Event::LiquidityAdded(
+			who.clone(),
+			asset_a,
+			asset_b,
+			amount_a,
+			amount_b,
+			minted,
+		));
+		Ok(())
+	}
+
+	pub(super) fn do_remove_liquidity(
+		who: &T::AccountId,
+		asset_a: T::AssetId,
+		asset_b: T::AssetId,
+		lp_amount: T::Balance,
+		amount_a_min: T::Balance,
+		amount_b_min: T::Balance,
+	) -> DispatchResult {
+		ensure!(!lp_amount.is_zero(), Error::<T, I>::BalanceZero);
+		let (a, b) = Self::pool_key(asset_a, asset_b);
+		let info = Pools::<T, I>::get(a, b).ok_or(Error::<T, I>::UnknownPool)?;
+		let pool = Self::pool_account(a, b);
+		let (reserve_a, reserve_b) = Self::pool_reserves(asset_a, asset_b)?;
+		let lp_supply = Self::total_supply(info.lp_asset);
+		ensure!(lp_amount <= lp_supply, Error::<T, I>::BalanceLow);
+
+		let amount_a = Self::quote(lp_amount, lp_supply, reserve_a)?;
+		let amount_b = Self::quote(lp_amount, lp_supply, reserve_b)?;
+		ensure!(amount_a >= amount_a_min, Error::<T, I>::SlippageExceeded);
+		ensure!(amount_b >= amount_b_min, Error::<T, I>::SlippageExceeded);
+
+		<Self as Mutate<T::AccountId>>::burn_from(info.lp_asset, who, lp_amount)?;
+		<Self as Transfer<T::AccountId>>::transfer(asset_a, &pool, who, amount_a, false)?;
+		<Self as Transfer<T::AccountId>>::transfer(asset_b, &pool, who, amount_b, false)?;
+
+		Self::deposit_event(Event::LiquidityRemoved(
+			who.clone(),
+			asset_a,
+			asset_b,
+			amount_a,
+			amount_b,
+			lp_amount,
+		));
+		Ok(())
+	}
+
+	pub(super) fn do_swap_exact_in(
+		who: &T::AccountId,
+		asset_in: T::AssetId,
+		asset_out: T::AssetId,
+		amount_in: T::Balance,
+		amount_out_min: T::Balance,
+	) -> DispatchResult {
+		let (reserve_in, reserve_out) = Self::pool_reserves(asset_in, asset_out)?;
+		let amount_out = Self::get_amount_out(amount_in, reserve_in, reserve_out)?;
+		ensure!(amount_out >= amount_out_min, Error::<T, I>::SlippageExceeded);
+		Self::do_swap(who, asset_in, asset_out, amount_in, amount_out)
+	}
+
+	pub(super) fn do_swap_exact_out(
+		who: &T::AccountId,
+		asset_in: T::AssetId,
+		asset_out: T::AssetId,
+		amount_out: T::Balance,
+		amount_in_max: T::Balance,
+	) -> DispatchResult {
+		let (reserve_in, reserve_out) = Self::pool_reserves(asset_in, asset_out)?;
+		let amount_in = Self::get_amount_in(amount_out, reserve_in, reserve_out)?;
+		ensure!(amount_in <= amount_in_max, Error::<T, I>::SlippageExceeded);
+		Self::do_swap(who, asset_in, asset_out, amount_in, amount_out)
+	}
+
+	fn do_swap(
+		who: &T::AccountId,
+		asset_in: T::AssetId,
+		asset_out: T::AssetId,
+		amount_in: T::Balance,
+		amount_out: T::Balance,
+	) -> DispatchResult {
+		let pool = Self::pool_account(asset_in, asset_out);
+		<Self as Transfer<T::AccountId>>::transfer(asset_in, who, &pool, amount_in, false)?;
+		// The pool must never be reaped by a swap.
+		<Self as Transfer<T::AccountId>>::transfer(asset_out, &pool, who, amount_out, true)?;
+
+		Self::deposit_event(Event::Swapped(
+			who.clone(),
+			asset_in,
+			asset_out,
+			amount_in,
+			amount_out,
+		));
+		Ok(())
+	}
+
+	/// `amount * to / from`, rounded down.
+	fn quote(
+		amount: T::Balance,
+		from: T::Balance,
+		to: T::Balance,
+	) -> Result<T::Balance, DispatchError> {
+		ensure!(!from.is_zero(), Error::<T, I>::InsufficientLiquidity);
+		Self::from_u256(Self::mul_div(amount.into(), to.into(), from.into())?)
+	}
+
+	/// `x * numerator / denominator`, rounded down, without overflowing on the product.
+	fn mul_div(x: U256, numerator: U256, denominator: U256) -> Result<U256, DispatchError> {
+		ensure!(!denominator.is_zero(), ArithmeticError::DivisionByZero);
+		U256::try_from(x.full_mul(numerator) / U512::from(denominator))
+			.map_err(|_| ArithmeticError::Overflow.into())
+	}
+
+	/// The fraction of every swap input that is traded, i.e. `1 - PoolFee`, as `(num, denom)`.
+	fn fee_ratio() -> (U256, U256) {
+		let denom = Permill::ACCURACY;
+		((denom - T::PoolFee::get().deconstruct()).into(), denom.into())
+	}
+
+	fn from_u256(x: U256) -> Result<T::Balance, DispatchError> {
+		T::Balance::try_from(x).map_err(|_| ArithmeticError::Overflow.into())
+	}
+}
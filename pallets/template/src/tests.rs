@@ -0,0 +1,2353 @@
+//! Tests for the ERC20 assets pallet.
+
+use super::*;
+use crate::{mock::*, Error, Event};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	pallet_prelude::MaxEncodedLen,
+	traits::tokens::{fungibles, BalanceConversion},
+};
+use pallet_balances::Error as BalancesError;
+use sp_runtime::{
+	traits::{
+		CheckedDiv, CheckedMul, CheckedShl, Convert, ConvertInto, IntegerSquareRoot,
+		SaturatedConversion,
+	},
+	DispatchError, TokenError,
+};
+use sp_std::convert::TryFrom;
+
+fn assert_last_event(event: Event<Test>) {
+	System::assert_last_event(event.into());
+}
+
+fn assert_has_event(event: Event<Test>) {
+	System::assert_has_event(event.into());
+}
+
+fn asset_ids() -> Vec<u32> {
+	let mut s: Vec<_> = Asset::<Test>::iter_keys().collect();
+	s.sort();
+	s
+}
+
+fn accounts(id: u32) -> u32 {
+	Asset::<Test>::get(id).unwrap().accounts
+}
+
+#[test]
+fn basic_minting_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_last_event(Event::ForceCreated(0, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_last_event(Event::Issued(0, 1, 100));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+		assert_eq!(Assets::balance(0, 2), 100);
+		assert_eq!(Assets::total_supply(0), 200);
+		assert_eq!(asset_ids(), vec![0]);
+	});
+}
+
+#[test]
+fn minting_requires_issuer_and_known_asset() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_noop!(Assets::mint(Origin::signed(2), 0, 2, 100), Error::<Test>::NoPermission);
+		assert_noop!(Assets::mint(Origin::signed(1), 1, 2, 100), TokenError::UnknownAsset);
+		assert_noop!(Assets::mint(Origin::signed(1), 0, 2, 5), TokenError::BelowMinimum);
+	});
+}
+
+#[test]
+fn create_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_noop!(Assets::create(Origin::signed(1), 0, 1, 1), Error::<Test>::ReservedId);
+		assert_ok!(Assets::create(Origin::signed(1), 100, 1, 1));
+		assert_last_event(Event::Created(100, 1, 1));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert_noop!(Assets::create(Origin::signed(1), 100, 1, 1), Error::<Test>::InUse);
+		assert_noop!(Assets::create(Origin::signed(1), 101, 1, 0), Error::<Test>::MinBalanceZero);
+		assert_noop!(
+			Assets::create(Origin::signed(2), 101, 2, 1),
+			BalancesError::<Test>::InsufficientBalance
+		);
+		assert_eq!(Assets::next_asset_id(), 101);
+	});
+}
+
+#[test]
+fn create_auto_skips_taken_ids() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_eq!(Assets::next_asset_id(), 100);
+		assert_ok!(Assets::create_auto(Origin::signed(1), 1, 1));
+		assert_last_event(Event::Created(100, 1, 1));
+		assert_eq!(Assets::next_asset_id(), 101);
+
+		assert_ok!(Assets::create(Origin::signed(1), 150, 1, 1));
+		assert_eq!(Assets::next_asset_id(), 151);
+		assert_ok!(Assets::create(Origin::signed(1), 120, 1, 1));
+		assert_eq!(Assets::next_asset_id(), 151);
+		assert_ok!(Assets::create_auto(Origin::signed(1), 1, 1));
+		assert_last_event(Event::Created(151, 1, 1));
+
+		// Reserved ids leave the counter alone, others move it on.
+		assert_ok!(Assets::force_create(Origin::root(), 5, 1, true, 1));
+		assert_eq!(Assets::next_asset_id(), 152);
+		assert_ok!(Assets::force_create(Origin::root(), 200, 1, true, 1));
+		assert_eq!(Assets::next_asset_id(), 201);
+
+		assert_noop!(Assets::create_auto(Origin::signed(1), 1, 0), Error::<Test>::MinBalanceZero);
+	});
+}
+
+#[test]
+fn create_auto_stops_at_the_largest_id() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::force_create(Origin::root(), u32::MAX, 1, true, 1));
+		assert_eq!(Assets::next_asset_id(), u32::MAX);
+		assert_noop!(Assets::create_auto(Origin::signed(1), 1, 1), Error::<Test>::InUse);
+	});
+}
+
+#[test]
+fn force_create_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Assets::force_create(Origin::signed(1), 0, 1, true, 1),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_last_event(Event::ForceCreated(0, 1));
+		assert_noop!(Assets::force_create(Origin::root(), 0, 1, true, 1), Error::<Test>::InUse);
+		assert_noop!(
+			Assets::force_create(Origin::root(), 1, 1, true, 0),
+			Error::<Test>::MinBalanceZero
+		);
+	});
+}
+
+#[test]
+fn lifecycle_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		Balances::make_free_balance_be(&2, 100);
+		assert_ok!(Assets::create(Origin::signed(1), 100, 1, 1));
+		assert_ok!(Assets::touch(Origin::signed(2), 100));
+		assert_ok!(Assets::mint(Origin::signed(1), 100, 1, 10));
+		assert_ok!(Assets::mint(Origin::signed(1), 100, 2, 10));
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 100, 2, 5));
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 100, vec![0; 10], vec![0; 10], 12));
+		assert_ok!(Assets::set_extended_metadata(
+			Origin::signed(1),
+			100,
+			vec![0; 10],
+			vec![],
+			None,
+			vec![]
+		));
+		// Asset, approval, metadata and extended metadata deposits, and the deposit of the
+		// account 1 minted to itself.
+		assert_eq!(Balances::reserved_balance(&1), 1 + 1 + 21 + 11 + 10);
+		assert_eq!(Balances::reserved_balance(&2), 10);
+
+		assert_noop!(Assets::start_destroy(Origin::signed(2), 100), Error::<Test>::NoPermission);
+		assert_ok!(Assets::start_destroy(Origin::signed(1), 100));
+		assert_last_event(Event::DestructionStarted(100));
+		assert_noop!(Assets::start_destroy(Origin::signed(1), 100), Error::<Test>::AssetNotLive);
+		assert_noop!(Assets::finish_destroy(Origin::signed(2), 100), Error::<Test>::InUse);
+
+		// Nothing but the destruction may make progress.
+		assert_noop!(Assets::transfer(Origin::signed(2), 100, 1, 5), Error::<Test>::AssetNotLive);
+		assert_noop!(Assets::mint(Origin::signed(1), 100, 1, 5), TokenError::UnknownAsset);
+		assert_noop!(Assets::freeze(Origin::signed(1), 100, 2), Error::<Test>::AssetNotLive);
+		assert_noop!(Assets::thaw(Origin::signed(1), 100, 2), Error::<Test>::AssetNotLive);
+		assert_noop!(Assets::freeze_asset(Origin::signed(1), 100), Error::<Test>::AssetNotLive);
+		assert_noop!(Assets::thaw_asset(Origin::signed(1), 100), Error::<Test>::AssetNotLive);
+		assert_noop!(
+			Assets::set_team(Origin::signed(1), 100, 1, 1, 1),
+			Error::<Test>::AssetNotLive
+		);
+		assert_noop!(
+			Assets::transfer_ownership(Origin::signed(1), 100, 2),
+			Error::<Test>::AssetNotLive
+		);
+		assert_noop!(
+			Assets::approve_transfer(Origin::signed(1), 100, 3, 5),
+			Error::<Test>::AssetNotLive
+		);
+		assert_noop!(
+			Assets::cancel_approval(Origin::signed(1), 100, 2),
+			Error::<Test>::AssetNotLive
+		);
+		assert_noop!(Assets::clear_metadata(Origin::signed(1), 100), Error::<Test>::AssetNotLive);
+		assert_noop!(
+			Assets::clear_extended_metadata(Origin::signed(1), 100),
+			Error::<Test>::AssetNotLive
+		);
+		assert_noop!(Assets::touch(Origin::signed(3), 100), Error::<Test>::AssetNotLive);
+		assert_noop!(Assets::refund(Origin::signed(2), 100, true), Error::<Test>::AssetNotLive);
+		assert_noop!(
+			Assets::force_asset_status(Origin::root(), 100, 1, 1, 1, 1, 1, false, false),
+			Error::<Test>::AssetNotLive
+		);
+
+		assert_ok!(Assets::destroy_accounts(Origin::signed(2), 100, 1));
+		assert_last_event(Event::AccountsDestroyed(100, 1, 1));
+		assert_ok!(Assets::destroy_accounts(Origin::signed(2), 100, 10));
+		assert_last_event(Event::AccountsDestroyed(100, 1, 0));
+		assert_eq!(Assets::total_supply(100), 0);
+		assert_eq!(Balances::reserved_balance(&2), 0);
+		let mut died = hooks();
+		died.sort_by_key(|Hook::Died(_, who)| *who);
+		assert_eq!(died, vec![Hook::Died(100, 1), Hook::Died(100, 2)]);
+
+		assert_noop!(Assets::finish_destroy(Origin::signed(2), 100), Error::<Test>::InUse);
+		assert_ok!(Assets::destroy_approvals(Origin::signed(2), 100, 10));
+		assert_last_event(Event::ApprovalsDestroyed(100, 1, 0));
+
+		assert_eq!(created_assets(), vec![100]);
+		assert_ok!(Assets::finish_destroy(Origin::signed(2), 100));
+		assert_last_event(Event::Destroyed(100));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert!(asset_ids().is_empty());
+		assert!(created_assets().is_empty());
+		assert!(!Metadata::<Test>::contains_key(100));
+		assert!(ExtendedMetadata::<Test>::get(100).is_none());
+		assert_noop!(Assets::finish_destroy(Origin::signed(2), 100), Error::<Test>::Unknown);
+	});
+}
+
+#[test]
+fn destruction_requires_destroying_status() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_noop!(
+			Assets::destroy_accounts(Origin::signed(1), 0, 10),
+			Error::<Test>::IncorrectStatus
+		);
+		assert_noop!(
+			Assets::destroy_approvals(Origin::signed(1), 0, 10),
+			Error::<Test>::IncorrectStatus
+		);
+		assert_noop!(Assets::finish_destroy(Origin::signed(1), 0), Error::<Test>::IncorrectStatus);
+		assert_noop!(Assets::start_destroy(Origin::signed(1), 1), Error::<Test>::Unknown);
+		assert_noop!(Assets::destroy_accounts(Origin::signed(1), 1, 10), Error::<Test>::Unknown);
+		assert_noop!(Assets::destroy_approvals(Origin::signed(1), 1, 10), Error::<Test>::Unknown);
+
+		// The force origin may start the destruction of any asset.
+		assert_ok!(Assets::start_destroy(Origin::root(), 0));
+		assert_ok!(Assets::finish_destroy(Origin::signed(2), 0));
+		assert_last_event(Event::Destroyed(0));
+	});
+}
+
+#[test]
+fn non_sufficient_accounts_need_a_provider() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, false, 1));
+		assert_noop!(Assets::mint(Origin::signed(1), 0, 1, 100), TokenError::CannotCreate);
+
+		Balances::make_free_balance_be(&1, 100);
+		Balances::make_free_balance_be(&2, 5);
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(Account::<Test>::get(0, 1).reason, ExistenceReason::DepositFrom(1, 10));
+		assert_eq!(System::consumers(&1), 0);
+
+		// Receiving the asset reserves nothing of the recipient's balance, however small.
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+		assert_last_event(Event::Transferred(0, 1, 2, 50));
+		assert_eq!(Account::<Test>::get(0, 2).reason, ExistenceReason::DepositFrom(1, 10));
+		assert_eq!(Balances::reserved_balance(&1), 20);
+		assert_eq!(Balances::reserved_balance(&2), 0);
+		assert_eq!(accounts(0), 2);
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 3, 50), TokenError::CannotCreate);
+	});
+}
+
+#[test]
+fn accounts_with_a_deposit_survive_being_emptied() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, false, 1));
+		Balances::make_free_balance_be(&1, 100);
+		Balances::make_free_balance_be(&2, 100);
+		assert_ok!(Assets::touch(Origin::signed(1), 0));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 100));
+
+		assert!(Account::<Test>::contains_key(0, 1));
+		assert_eq!(Assets::balance(0, 1), 0);
+		// Its own deposit and the one it paid for the new account of 2.
+		assert_eq!(Balances::reserved_balance(&1), 20);
+		assert_eq!(accounts(0), 2);
+		assert!(hooks().is_empty());
+
+		assert_ok!(Assets::refund(Origin::signed(1), 0, false));
+		assert_last_event(Event::Refunded(0, 1, 10));
+		assert!(!Account::<Test>::contains_key(0, 1));
+		assert_eq!(Balances::reserved_balance(&1), 10);
+		assert_eq!(accounts(0), 1);
+		assert_eq!(hooks(), vec![Hook::Died(0, 1)]);
+	});
+}
+
+#[test]
+fn senders_pay_the_deposit_of_new_recipients() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, false, 1));
+		Balances::make_free_balance_be(&1, 100);
+		Balances::make_free_balance_be(&2, 15);
+		Balances::make_free_balance_be(&3, 100);
+		Balances::make_free_balance_be(&4, 100);
+
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+		assert_eq!(Account::<Test>::get(0, 2).reason, ExistenceReason::DepositFrom(1, 10));
+		assert_eq!(Balances::reserved_balance(&1), 10);
+		assert_eq!(Balances::reserved_balance(&2), 0);
+
+		assert_ok!(Assets::transfer(Origin::signed(2), 0, 3, 50));
+		assert_eq!(Account::<Test>::get(0, 3).reason, ExistenceReason::DepositFrom(2, 10));
+		assert_eq!(Balances::reserved_balance(&2), 10);
+		assert_eq!(Balances::reserved_balance(&3), 0);
+		assert_noop!(
+			Assets::transfer(Origin::signed(2), 0, 4, 10),
+			BalancesError::<Test>::InsufficientBalance
+		);
+
+		// Emptying the account returns the deposit to the account that paid it.
+		assert_ok!(Assets::transfer(Origin::signed(3), 0, 2, 50));
+		assert!(!Account::<Test>::contains_key(0, 3));
+		assert_eq!(Balances::reserved_balance(&2), 0);
+		assert_eq!(hooks(), vec![Hook::Died(0, 3)]);
+
+		// As does refunding it.
+		assert_ok!(Assets::refund(Origin::signed(2), 0, true));
+		assert_last_event(Event::Refunded(0, 2, 10));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::reserved_balance(&2), 0);
+		assert_eq!(accounts(0), 0);
+	});
+}
+
+#[test]
+fn zero_account_deposit_takes_a_consumer_reference() {
+	new_test_ext().execute_with(|| {
+		set_account_deposit(0);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, false, 1));
+		assert_noop!(Assets::touch(Origin::signed(1), 0), Error::<Test>::NoProvider);
+		Balances::make_free_balance_be(&1, 100);
+		Balances::make_free_balance_be(&2, 100);
+		assert_ok!(Assets::touch(Origin::signed(1), 0));
+		assert_last_event(Event::Touched(0, 1, 0));
+		assert_eq!(Account::<Test>::get(0, 1).reason, ExistenceReason::Consumer);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(System::consumers(&1), 1);
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(System::consumers(&1), 1);
+
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 100));
+		assert_eq!(System::consumers(&1), 0);
+		assert_eq!(System::consumers(&2), 1);
+		assert!(!Account::<Test>::contains_key(0, 1));
+		assert_eq!(hooks(), vec![Hook::Died(0, 1)]);
+
+		// Without a provider there is nothing for the consumer reference to depend on.
+		let mut details = Asset::<Test>::get(0).unwrap();
+		assert_noop!(Assets::new_account(&5, &mut details, None), Error::<Test>::NoProvider);
+	});
+}
+
+#[test]
+fn touch_and_refund_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, false, 10));
+		Balances::make_free_balance_be(&2, 100);
+		Balances::make_free_balance_be(&3, 5);
+
+		assert_noop!(Assets::touch(Origin::signed(2), 1), Error::<Test>::Unknown);
+		assert_noop!(
+			Assets::touch(Origin::signed(3), 0),
+			BalancesError::<Test>::InsufficientBalance
+		);
+		assert_ok!(Assets::touch(Origin::signed(2), 0));
+		assert_last_event(Event::Touched(0, 2, 10));
+		assert_eq!(Balances::reserved_balance(&2), 10);
+		assert_eq!(accounts(0), 1);
+		assert_noop!(Assets::touch(Origin::signed(2), 0), Error::<Test>::AlreadyExists);
+
+		// Minting to a touched account takes no further deposit.
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+		assert_eq!(Balances::reserved_balance(&2), 10);
+
+		assert_noop!(Assets::refund(Origin::signed(3), 0, true), Error::<Test>::NoDeposit);
+		assert_noop!(Assets::refund(Origin::signed(2), 0, false), Error::<Test>::WouldBurn);
+		assert_ok!(Assets::refund(Origin::signed(2), 0, true));
+		assert_has_event(Event::Burned(0, 2, 100));
+		assert_last_event(Event::Refunded(0, 2, 10));
+		assert_eq!(Assets::total_supply(0), 0);
+		assert_eq!(Balances::reserved_balance(&2), 0);
+		assert_eq!(accounts(0), 0);
+	});
+}
+
+#[test]
+fn refund_requires_a_deposit_and_a_live_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, false, 1));
+		assert_ok!(Assets::force_create(Origin::root(), 1, 1, true, 1));
+		Balances::make_free_balance_be(&2, 100);
+
+		assert_ok!(Assets::mint(Origin::signed(1), 1, 2, 100));
+		assert_noop!(Assets::refund(Origin::signed(2), 1, true), Error::<Test>::NoDeposit);
+
+		assert_ok!(Assets::touch(Origin::signed(2), 0));
+		assert_ok!(Assets::freeze(Origin::signed(1), 0, 2));
+		assert_noop!(Assets::refund(Origin::signed(2), 0, false), Error::<Test>::Frozen);
+		assert_ok!(Assets::thaw(Origin::signed(1), 0, 2));
+		assert_ok!(Assets::freeze_asset(Origin::signed(1), 0));
+		assert_noop!(Assets::refund(Origin::signed(2), 0, false), Error::<Test>::Frozen);
+		assert_ok!(Assets::thaw_asset(Origin::signed(1), 0));
+		assert_ok!(Assets::refund(Origin::signed(2), 0, false));
+	});
+}
+
+#[test]
+fn transferring_amount_below_available_balance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+		assert_last_event(Event::Transferred(0, 1, 2, 50));
+		assert_eq!(Assets::balance(0, 1), 50);
+		assert_eq!(Assets::balance(0, 2), 50);
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 51), Error::<Test>::BalanceLow);
+		assert_noop!(Assets::transfer(Origin::signed(1), 1, 2, 1), Error::<Test>::Unknown);
+
+		// Self-transfers and empty transfers change nothing but are reported.
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 1, 10));
+		assert_last_event(Event::Transferred(0, 1, 1, 10));
+		assert_eq!(Assets::balance(0, 1), 50);
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 0));
+		assert_last_event(Event::Transferred(0, 1, 2, 0));
+		assert_eq!(Assets::total_supply(0), 100);
+	});
+}
+
+#[test]
+fn transferring_leaving_dust_moves_everything() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 91));
+		assert_last_event(Event::Transferred(0, 1, 2, 100));
+		assert_eq!(Assets::balance(0, 2), 100);
+		assert!(!Account::<Test>::contains_key(0, 1));
+		assert_eq!(accounts(0), 1);
+		assert_eq!(hooks(), vec![Hook::Died(0, 1)]);
+	});
+}
+
+#[test]
+fn transfer_keep_alive_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_noop!(
+			Assets::transfer_keep_alive(Origin::signed(1), 0, 2, 91),
+			Error::<Test>::BalanceLow
+		);
+		assert_ok!(Assets::transfer_keep_alive(Origin::signed(1), 0, 2, 90));
+		assert_last_event(Event::Transferred(0, 1, 2, 90));
+		assert_eq!(Assets::balance(0, 1), 10);
+	});
+}
+
+#[test]
+fn force_transfer_requires_admin() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+		assert_noop!(
+			Assets::force_transfer(Origin::signed(2), 0, 2, 3, 50),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Assets::force_transfer(Origin::signed(1), 0, 2, 3, 50));
+		assert_last_event(Event::Transferred(0, 2, 3, 50));
+		assert_eq!(Assets::balance(0, 3), 50);
+	});
+}
+
+#[test]
+fn burn_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+		assert_noop!(Assets::burn(Origin::signed(2), 0, 2, 10), Error::<Test>::NoPermission);
+		assert_ok!(Assets::burn(Origin::signed(1), 0, 2, 10));
+		assert_last_event(Event::Burned(0, 2, 10));
+		assert_eq!(Assets::balance(0, 2), 90);
+
+		// Burning is best effort.
+		assert_ok!(Assets::burn(Origin::signed(1), 0, 2, u64::MAX));
+		assert_last_event(Event::Burned(0, 2, 90));
+		assert_eq!(Assets::total_supply(0), 0);
+		assert!(!Account::<Test>::contains_key(0, 2));
+	});
+}
+
+#[test]
+fn burning_from_a_missing_account_changes_nothing() {
+	new_test_ext().execute_with(|| {
+		set_account_deposit(0);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, false, 10));
+		Balances::make_free_balance_be(&2, 100);
+		Balances::make_free_balance_be(&3, 100);
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+		assert_eq!(System::consumers(&2), 1);
+
+		assert_noop!(Assets::burn(Origin::signed(2), 0, 3, 10), Error::<Test>::NoPermission);
+		assert_ok!(Assets::burn(Origin::signed(1), 0, 3, 10));
+		assert_last_event(Event::Burned(0, 3, 0));
+		assert_eq!(accounts(0), 1);
+		assert_eq!(System::consumers(&3), 0);
+		assert!(hooks().is_empty());
+	});
+}
+
+#[test]
+fn freezing_accounts_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_noop!(Assets::freeze(Origin::signed(2), 0, 1), Error::<Test>::NoPermission);
+		assert_noop!(Assets::freeze(Origin::signed(1), 0, 3), Error::<Test>::BalanceZero);
+		assert_noop!(Assets::freeze(Origin::signed(1), 1, 1), Error::<Test>::Unknown);
+		assert_ok!(Assets::freeze(Origin::signed(1), 0, 1));
+		assert_last_event(Event::Frozen(0, 1));
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::Frozen);
+
+		// Frozen accounts may still receive.
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 10));
+
+		assert_noop!(Assets::thaw(Origin::signed(2), 0, 1), Error::<Test>::NoPermission);
+		assert_noop!(Assets::thaw(Origin::signed(1), 0, 3), Error::<Test>::BalanceZero);
+		assert_noop!(Assets::thaw(Origin::signed(1), 1, 1), Error::<Test>::Unknown);
+		assert_ok!(Assets::thaw(Origin::signed(1), 0, 1));
+		assert_last_event(Event::Thawed(0, 1));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+	});
+}
+
+#[test]
+fn freezing_assets_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_noop!(Assets::freeze_asset(Origin::signed(2), 0), Error::<Test>::NoPermission);
+		assert_noop!(Assets::freeze_asset(Origin::signed(1), 1), Error::<Test>::Unknown);
+		assert_ok!(Assets::freeze_asset(Origin::signed(1), 0));
+		assert_last_event(Event::AssetFrozen(0));
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::Frozen);
+		assert_noop!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::Frozen);
+
+		assert_noop!(Assets::thaw_asset(Origin::signed(2), 0), Error::<Test>::NoPermission);
+		assert_noop!(Assets::thaw_asset(Origin::signed(1), 1), Error::<Test>::Unknown);
+		assert_ok!(Assets::thaw_asset(Origin::signed(1), 0));
+		assert_last_event(Event::AssetThawed(0));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+	});
+}
+
+#[test]
+fn frozen_balance_is_respected() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		set_frozen_balance(0, 1, 50);
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::BalanceLow);
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 49));
+		assert_eq!(
+			<Assets as fungibles::Inspect<u64>>::can_withdraw(0, &1, 2),
+			WithdrawConsequence::Frozen
+		);
+
+		clear_frozen_balance(0, 1);
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 51));
+		assert_eq!(hooks(), vec![Hook::Died(0, 1)]);
+	});
+}
+
+#[test]
+fn frozen_amounts_are_respected() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_noop!(
+			Assets::freeze_amount(Origin::signed(2), 0, 1, 30, 7),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Assets::freeze_amount(Origin::signed(1), 0, 2, 30, 7),
+			Error::<Test>::BalanceZero
+		);
+		assert_noop!(
+			Assets::freeze_amount(Origin::signed(1), 0, 1, 0, 7),
+			Error::<Test>::BalanceZero
+		);
+
+		assert_ok!(Assets::freeze_amount(Origin::signed(1), 0, 1, 30, 7));
+		assert_last_event(Event::AmountFrozen(0, 1, 7, 30));
+		assert_ok!(Assets::freeze_amount(Origin::signed(1), 0, 1, 20, 8));
+		assert_eq!(Assets::frozen_amount(0, &1), 50);
+		assert_noop!(
+			Assets::freeze_amount(Origin::signed(1), 0, 1, 10, 9),
+			Error::<Test>::TooManyFreezes
+		);
+		// Freezing for a reason again replaces its amount.
+		assert_ok!(Assets::freeze_amount(Origin::signed(1), 0, 1, 10, 7));
+		assert_eq!(Assets::frozen_amount(0, &1), 30);
+		// The amounts frozen for all reasons cannot exceed the balance.
+		assert_noop!(
+			Assets::freeze_amount(Origin::signed(1), 0, 1, 81, 7),
+			Error::<Test>::BalanceLow
+		);
+		assert_ok!(Assets::freeze_amount(Origin::signed(1), 0, 1, 80, 7));
+		assert_eq!(Assets::frozen_amount(0, &1), 100);
+		assert_ok!(Assets::freeze_amount(Origin::signed(1), 0, 1, 10, 7));
+
+		// The amounts frozen here and by `Freezer` add up.
+		set_frozen_balance(0, 1, 20);
+		assert_eq!(<Assets as fungibles::Inspect<u64>>::reducible_balance(0, &1, false), 49);
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::BalanceLow);
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 49));
+		assert_eq!(
+			<Assets as fungibles::Inspect<u64>>::can_withdraw(0, &1, 1),
+			WithdrawConsequence::Frozen
+		);
+		clear_frozen_balance(0, 1);
+
+		// Only the freezer may thaw, not the admin.
+		assert_ok!(Assets::set_team(Origin::signed(1), 0, 1, 1, 3));
+		assert_noop!(Assets::thaw_amount(Origin::signed(1), 0, 1, 7), Error::<Test>::NoPermission);
+		assert_noop!(Assets::thaw_amount(Origin::signed(3), 0, 1, 9), Error::<Test>::NotFrozen);
+		assert_ok!(Assets::thaw_amount(Origin::signed(3), 0, 1, 7));
+		assert_last_event(Event::AmountThawed(0, 1, 7, 10));
+		assert_eq!(Assets::frozen_amount(0, &1), 20);
+		assert_ok!(Assets::thaw_amount(Origin::signed(3), 0, 1, 8));
+		assert!(!Freezes::<Test>::contains_key(0, 1));
+
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 51));
+		assert_eq!(Assets::balance(0, 1), 0);
+	});
+}
+
+#[test]
+fn transfer_ownership_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		Balances::make_free_balance_be(&2, 100);
+		assert_ok!(Assets::create(Origin::signed(1), 100, 1, 1));
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 100, vec![0; 10], vec![0; 10], 12));
+		assert_ok!(Assets::set_extended_metadata(
+			Origin::signed(1),
+			100,
+			vec![0; 10],
+			vec![],
+			None,
+			vec![]
+		));
+		assert_eq!(Balances::reserved_balance(&1), 33);
+
+		assert_noop!(
+			Assets::transfer_ownership(Origin::signed(2), 100, 2),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(Assets::transfer_ownership(Origin::signed(1), 101, 2), Error::<Test>::Unknown);
+		assert_ok!(Assets::transfer_ownership(Origin::signed(1), 100, 2));
+		assert_last_event(Event::OwnerChanged(100, 2));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::reserved_balance(&2), 33);
+
+		// Only the new owner may now act as owner.
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 100, vec![0; 10], vec![0; 10], 12),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Assets::transfer_ownership(Origin::signed(2), 100, 1));
+		assert_eq!(Balances::reserved_balance(&1), 33);
+	});
+}
+
+#[test]
+fn set_team_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_noop!(Assets::set_team(Origin::signed(2), 0, 2, 3, 4), Error::<Test>::NoPermission);
+		assert_noop!(Assets::set_team(Origin::signed(1), 1, 2, 3, 4), Error::<Test>::Unknown);
+		assert_ok!(Assets::set_team(Origin::signed(1), 0, 2, 3, 4));
+		assert_last_event(Event::TeamChanged(0, 2, 3, 4));
+
+		assert_noop!(Assets::mint(Origin::signed(1), 0, 2, 100), Error::<Test>::NoPermission);
+		assert_ok!(Assets::mint(Origin::signed(2), 0, 2, 100));
+		assert_ok!(Assets::freeze(Origin::signed(4), 0, 2));
+		assert_ok!(Assets::thaw(Origin::signed(3), 0, 2));
+		assert_ok!(Assets::force_transfer(Origin::signed(3), 0, 2, 3, 100));
+	});
+}
+
+#[test]
+fn set_metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		Balances::make_free_balance_be(&1, 30);
+
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(2), 0, vec![0; 10], vec![0; 10], 12),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 1, vec![0; 10], vec![0; 10], 12),
+			Error::<Test>::Unknown
+		);
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 0, vec![0; 100], vec![0; 10], 12),
+			Error::<Test>::BadMetadata
+		);
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 0, vec![0; 10], vec![0; 100], 12),
+			Error::<Test>::BadMetadata
+		);
+
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0; 10], vec![0; 10], 12));
+		assert_last_event(Event::MetadataSet(0, vec![0; 10], vec![0; 10], 12, false));
+		assert_eq!(Balances::reserved_balance(&1), 21);
+		assert_eq!(Assets::asset_metadata(0).decimals, 12);
+
+		// The deposit follows the size of the metadata.
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0; 10], vec![0; 5], 12));
+		assert_eq!(Balances::reserved_balance(&1), 16);
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 0, vec![0; 20], vec![0; 20], 12),
+			BalancesError::<Test>::InsufficientBalance
+		);
+
+		assert_noop!(Assets::clear_metadata(Origin::signed(2), 0), Error::<Test>::NoPermission);
+		assert_noop!(Assets::clear_metadata(Origin::signed(1), 1), Error::<Test>::Unknown);
+		assert_ok!(Assets::clear_metadata(Origin::signed(1), 0));
+		assert_last_event(Event::MetadataCleared(0));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_noop!(Assets::clear_metadata(Origin::signed(1), 0), Error::<Test>::Unknown);
+	});
+}
+
+#[test]
+fn force_metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		Balances::make_free_balance_be(&1, 30);
+
+		assert_noop!(
+			Assets::force_set_metadata(Origin::signed(1), 0, vec![0; 10], vec![0; 10], 8, true),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Assets::force_set_metadata(Origin::root(), 1, vec![0; 10], vec![0; 10], 8, true),
+			Error::<Test>::Unknown
+		);
+		assert_noop!(
+			Assets::force_set_metadata(Origin::root(), 0, vec![0; 51], vec![0; 10], 8, true),
+			Error::<Test>::BadMetadata
+		);
+		assert_ok!(Assets::force_set_metadata(
+			Origin::root(),
+			0,
+			vec![0; 10],
+			vec![0; 10],
+			8,
+			true
+		));
+		assert_last_event(Event::MetadataSet(0, vec![0; 10], vec![0; 10], 8, true));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+
+		// Frozen metadata can't be changed by the owner.
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 0, vec![0; 10], vec![0; 10], 12),
+			Error::<Test>::NoPermission
+		);
+
+		assert_noop!(Assets::force_clear_metadata(Origin::signed(1), 0), DispatchError::BadOrigin);
+		assert_noop!(Assets::force_clear_metadata(Origin::root(), 1), Error::<Test>::Unknown);
+		assert_ok!(Assets::force_clear_metadata(Origin::root(), 0));
+		assert_last_event(Event::MetadataCleared(0));
+		assert_noop!(Assets::force_clear_metadata(Origin::root(), 0), Error::<Test>::Unknown);
+	});
+}
+
+#[test]
+fn set_extended_metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		Balances::make_free_balance_be(&1, 100);
+
+		assert_noop!(
+			Assets::set_extended_metadata(Origin::signed(2), 0, vec![], vec![], None, vec![]),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Assets::set_extended_metadata(Origin::signed(1), 1, vec![], vec![], None, vec![]),
+			Error::<Test>::Unknown
+		);
+		assert_noop!(
+			Assets::set_extended_metadata(Origin::signed(1), 0, vec![0; 101], vec![], None, vec![]),
+			Error::<Test>::BadMetadata
+		);
+		assert_noop!(
+			Assets::set_extended_metadata(Origin::signed(1), 0, vec![], vec![0; 51], None, vec![]),
+			Error::<Test>::BadMetadata
+		);
+		assert_noop!(
+			Assets::set_extended_metadata(Origin::signed(1), 0, vec![], vec![], None, vec![0; 51]),
+			Error::<Test>::BadMetadata
+		);
+
+		assert_ok!(Assets::set_extended_metadata(
+			Origin::signed(1),
+			0,
+			vec![1; 10],
+			vec![2; 10],
+			Some([3; 32]),
+			vec![4; 10]
+		));
+		assert_last_event(Event::ExtendedMetadataSet(
+			0,
+			vec![1; 10],
+			vec![2; 10],
+			Some([3; 32]),
+			vec![4; 10],
+			false,
+		));
+		assert_eq!(Balances::reserved_balance(&1), 1 + 30 + 32);
+
+		assert_ok!(Assets::set_extended_metadata(
+			Origin::signed(1),
+			0,
+			vec![],
+			vec![],
+			None,
+			vec![]
+		));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+
+		assert_noop!(
+			Assets::clear_extended_metadata(Origin::signed(2), 0),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(Assets::clear_extended_metadata(Origin::signed(1), 1), Error::<Test>::Unknown);
+		assert_ok!(Assets::clear_extended_metadata(Origin::signed(1), 0));
+		assert_last_event(Event::ExtendedMetadataCleared(0));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_noop!(Assets::clear_extended_metadata(Origin::signed(1), 0), Error::<Test>::Unknown);
+	});
+}
+
+#[test]
+fn force_extended_metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		Balances::make_free_balance_be(&1, 100);
+
+		assert_noop!(
+			Assets::force_set_extended_metadata(
+				Origin::signed(1),
+				0,
+				vec![],
+				vec![],
+				None,
+				vec![],
+				true
+			),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Assets::force_set_extended_metadata(
+			Origin::root(),
+			0,
+			vec![1; 10],
+			vec![],
+			None,
+			vec![],
+			true
+		));
+		assert_last_event(Event::ExtendedMetadataSet(0, vec![1; 10], vec![], None, vec![], true));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+
+		// Frozen extended metadata can't be changed by the owner.
+		assert_noop!(
+			Assets::set_extended_metadata(Origin::signed(1), 0, vec![], vec![], None, vec![]),
+			Error::<Test>::NoPermission
+		);
+
+		assert_noop!(
+			Assets::force_clear_extended_metadata(Origin::signed(1), 0),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Assets::force_clear_extended_metadata(Origin::root(), 0));
+		assert_last_event(Event::ExtendedMetadataCleared(0));
+		assert_noop!(
+			Assets::force_clear_extended_metadata(Origin::root(), 0),
+			Error::<Test>::Unknown
+		);
+	});
+}
+
+#[test]
+fn force_asset_status_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 50));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 150));
+
+		assert_noop!(
+			Assets::force_asset_status(Origin::signed(1), 0, 1, 1, 1, 1, 100, true, true),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Assets::force_asset_status(Origin::root(), 1, 1, 1, 1, 1, 100, true, true),
+			Error::<Test>::Unknown
+		);
+		assert_ok!(Assets::force_asset_status(Origin::root(), 0, 1, 1, 1, 1, 100, true, true));
+		assert_last_event(Event::AssetStatusChanged(0));
+		assert_noop!(Assets::transfer(Origin::signed(2), 0, 1, 50), Error::<Test>::Frozen);
+
+		// An account below the raised minimum is emptied by any transfer out of it.
+		assert_ok!(Assets::force_asset_status(Origin::root(), 0, 1, 1, 1, 1, 100, true, false));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 20));
+		assert_last_event(Event::Transferred(0, 1, 2, 50));
+		assert_eq!(Assets::balance(0, 2), 200);
+	});
+}
+
+#[test]
+fn approval_lifecycle_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		Balances::make_free_balance_be(&1, 1);
+
+		assert_noop!(Assets::approve_transfer(Origin::signed(1), 1, 2, 50), Error::<Test>::Unknown);
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+		assert_last_event(Event::ApprovedTransfer(0, 1, 2, 50, 50));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert_eq!(Asset::<Test>::get(0).unwrap().approvals, 1);
+
+		// Topping up an approval takes no further deposit, a new one can't be paid for.
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 10));
+		assert_last_event(Event::ApprovedTransfer(0, 1, 2, 10, 60));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert_noop!(
+			Assets::approve_transfer(Origin::signed(1), 0, 3, 10),
+			BalancesError::<Test>::InsufficientBalance
+		);
+
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 61),
+			Error::<Test>::Unapproved
+		);
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(3), 0, 1, 3, 10),
+			Error::<Test>::Unapproved
+		);
+		assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 40));
+		assert_has_event(Event::Transferred(0, 1, 3, 40));
+		assert_has_event(Event::TransferredApproved(0, 1, 2, 3, 40));
+		assert_last_event(Event::ApprovalUsed(0, 1, 2, 3, 40, 20));
+		assert_eq!(Assets::balance(0, 3), 40);
+		assert_eq!(Approvals::<Test>::get((0, 1, 2)).unwrap().amount, 20);
+		assert_eq!(Assets::allowance(0, &1, &2), 20);
+
+		assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 20));
+		assert_last_event(Event::ApprovalUsed(0, 1, 2, 3, 20, 0));
+		assert!(Approvals::<Test>::get((0, 1, 2)).is_none());
+		assert_eq!(Assets::allowance(0, &1, &2), 0);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Asset::<Test>::get(0).unwrap().approvals, 0);
+	});
+}
+
+#[test]
+fn unlimited_approvals_are_never_decremented() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		Balances::make_free_balance_be(&1, 1);
+
+		assert_noop!(Assets::approve_unlimited(Origin::signed(1), 1, 2), Error::<Test>::Unknown);
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 10));
+		assert_ok!(Assets::approve_unlimited(Origin::signed(1), 0, 2));
+		assert_last_event(Event::ApprovedUnlimited(0, 1, 2));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert_eq!(Asset::<Test>::get(0).unwrap().approvals, 1);
+		assert_eq!(Assets::allowance(0, &1, &2), u64::max_value());
+		assert!(Assets::is_unlimited_approval(0, &1, &2));
+
+		let approval = Approvals::<Test>::get((0, 1, 2)).unwrap();
+		assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 40));
+		assert_last_event(Event::ApprovalUsed(0, 1, 2, 3, 40, u64::max_value()));
+		assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 50));
+		assert_eq!(Assets::balance(0, 3), 90);
+		assert_eq!(Approvals::<Test>::get((0, 1, 2)), Some(approval));
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 11),
+			Error::<Test>::BalanceLow
+		);
+
+		// Topping up keeps the approval unlimited, until it is cancelled.
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 5));
+		assert_last_event(Event::ApprovedTransfer(0, 1, 2, 5, u64::max_value()));
+		assert_ok!(Assets::cancel_approval(Origin::signed(1), 0, 2));
+		assert!(!Assets::is_unlimited_approval(0, &1, &2));
+		assert_eq!(Assets::allowance(0, &1, &2), 0);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+	});
+}
+
+#[test]
+fn transfer_approved_requires_enough_balance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 10));
+		Balances::make_free_balance_be(&1, 1);
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 20),
+			Error::<Test>::BalanceLow
+		);
+	});
+}
+
+#[test]
+fn cancel_approval_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		Balances::make_free_balance_be(&1, 1);
+
+		assert_noop!(Assets::cancel_approval(Origin::signed(1), 0, 2), Error::<Test>::Unknown);
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+		assert_noop!(Assets::cancel_approval(Origin::signed(1), 1, 2), Error::<Test>::Unknown);
+		assert_noop!(Assets::cancel_approval(Origin::signed(2), 0, 1), Error::<Test>::Unknown);
+		assert_ok!(Assets::cancel_approval(Origin::signed(1), 0, 2));
+		assert_last_event(Event::ApprovalCancelled(0, 1, 2));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Asset::<Test>::get(0).unwrap().approvals, 0);
+	});
+}
+
+#[test]
+fn force_cancel_approval_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		Balances::make_free_balance_be(&1, 1);
+
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+		assert_noop!(
+			Assets::force_cancel_approval(Origin::signed(2), 0, 1, 2),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Assets::force_cancel_approval(Origin::signed(1), 1, 1, 2),
+			Error::<Test>::Unknown
+		);
+		assert_noop!(
+			Assets::force_cancel_approval(Origin::signed(1), 0, 1, 3),
+			Error::<Test>::Unknown
+		);
+		assert_ok!(Assets::force_cancel_approval(Origin::signed(1), 0, 1, 2));
+		assert_last_event(Event::ApprovalCancelled(0, 1, 2));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+		assert_ok!(Assets::force_cancel_approval(Origin::root(), 0, 1, 2));
+		assert_last_event(Event::ApprovalCancelled(0, 1, 2));
+		assert_eq!(Asset::<Test>::get(0).unwrap().approvals, 0);
+	});
+}
+
+#[test]
+fn last_sufficient_reference_is_kept_while_consumers_remain() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+		assert_eq!(System::providers(&2), 0);
+		assert_eq!(System::sufficients(&2), 1);
+		assert_eq!(
+			<Assets as fungibles::Inspect<u64>>::can_withdraw(0, &2, 100),
+			WithdrawConsequence::ReducedToZero(0)
+		);
+
+		// Something now depends on account 2, which only the asset keeps alive.
+		frame_system::Account::<Test>::mutate(&2, |a| a.consumers = 1);
+		assert_eq!(
+			<Assets as fungibles::Inspect<u64>>::can_withdraw(0, &2, 100),
+			WithdrawConsequence::WouldDie
+		);
+		assert_eq!(
+			<Assets as fungibles::Inspect<u64>>::can_withdraw(0, &2, 90),
+			WithdrawConsequence::Success
+		);
+		assert_eq!(<Assets as fungibles::Inspect<u64>>::reducible_balance(0, &2, false), 90);
+		assert_noop!(Assets::transfer(Origin::signed(2), 0, 3, 100), Error::<Test>::BalanceLow);
+		assert_noop!(
+			Assets::transfer_keep_alive(Origin::signed(2), 0, 3, 100),
+			Error::<Test>::BalanceLow
+		);
+		assert_noop!(
+			<Assets as fungibles::Transfer<u64>>::transfer(0, &2, &3, 100, false),
+			Error::<Test>::BalanceLow
+		);
+		assert_ok!(Assets::transfer(Origin::signed(2), 0, 3, 90));
+		assert_eq!(Assets::balance(0, 2), 10);
+
+		frame_system::Account::<Test>::mutate(&2, |a| a.consumers = 0);
+		assert_ok!(Assets::transfer(Origin::signed(2), 0, 3, 10));
+		assert!(!Account::<Test>::contains_key(0, 2));
+		assert!(!System::account_exists(&2));
+	});
+}
+
+#[test]
+fn fungibles_inspect_works() {
+	new_test_ext().execute_with(|| {
+		type A = Assets;
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::force_create(Origin::root(), 1, 1, false, 10));
+		assert_eq!(<A as fungibles::Inspect<u64>>::minimum_balance(0), 10);
+		assert_eq!(<A as fungibles::Inspect<u64>>::minimum_balance(5), 0);
+
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_deposit(5, &1, 100),
+			DepositConsequence::UnknownAsset
+		);
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_deposit(0, &1, 5),
+			DepositConsequence::BelowMinimum
+		);
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_deposit(1, &3, 10),
+			DepositConsequence::CannotCreate
+		);
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_deposit(0, &1, 100),
+			DepositConsequence::Success
+		);
+
+		assert_ok!(<A as fungibles::Mutate<u64>>::mint_into(0, &1, 100));
+		assert_last_event(Event::Issued(0, 1, 100));
+		assert_eq!(<A as fungibles::Inspect<u64>>::total_issuance(0), 100);
+		assert_eq!(<A as fungibles::Inspect<u64>>::balance(0, &1), 100);
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_deposit(0, &2, u64::MAX),
+			DepositConsequence::Overflow
+		);
+
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_withdraw(5, &1, 1),
+			WithdrawConsequence::UnknownAsset
+		);
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_withdraw(0, &1, 101),
+			WithdrawConsequence::Underflow
+		);
+		assert_ok!(<A as fungibles::Mutate<u64>>::mint_into(0, &2, 100));
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_withdraw(0, &1, 101),
+			WithdrawConsequence::NoFunds
+		);
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_withdraw(0, &1, 95),
+			WithdrawConsequence::ReducedToZero(5)
+		);
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_withdraw(0, &1, 90),
+			WithdrawConsequence::Success
+		);
+		assert_eq!(<A as fungibles::Inspect<u64>>::reducible_balance(0, &1, true), 90);
+		assert_eq!(<A as fungibles::Inspect<u64>>::reducible_balance(0, &1, false), 100);
+
+		assert_ok!(Assets::freeze(Origin::signed(1), 0, 1));
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_withdraw(0, &1, 10),
+			WithdrawConsequence::Frozen
+		);
+		assert_eq!(<A as fungibles::Inspect<u64>>::reducible_balance(0, &1, false), 0);
+	});
+}
+
+#[test]
+fn fungibles_mutate_works() {
+	new_test_ext().execute_with(|| {
+		type A = Assets;
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(<A as fungibles::Mutate<u64>>::mint_into(0, &1, 100));
+
+		assert_eq!(<A as fungibles::Mutate<u64>>::burn_from(0, &1, 10), Ok(10));
+		assert_last_event(Event::Burned(0, 1, 10));
+		assert_noop!(
+			<A as fungibles::Mutate<u64>>::burn_from(0, &1, 1000),
+			Error::<Test>::BalanceLow
+		);
+		assert_eq!(<A as fungibles::Mutate<u64>>::slash(0, &1, 1000), Ok(90));
+		assert_last_event(Event::Burned(0, 1, 90));
+		assert_eq!(Assets::total_supply(0), 0);
+		assert!(!Account::<Test>::contains_key(0, 1));
+	});
+}
+
+#[test]
+fn fungibles_transfer_works() {
+	new_test_ext().execute_with(|| {
+		type A = Assets;
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(<A as fungibles::Mutate<u64>>::mint_into(0, &1, 100));
+
+		assert_eq!(<A as fungibles::Transfer<u64>>::transfer(0, &1, &2, 50, true), Ok(50));
+		assert_last_event(Event::Transferred(0, 1, 2, 50));
+		assert_noop!(
+			<A as fungibles::Transfer<u64>>::transfer(0, &1, &2, 50, true),
+			Error::<Test>::BalanceLow
+		);
+		assert_eq!(<A as fungibles::Transfer<u64>>::transfer(0, &1, &2, 50, false), Ok(50));
+		assert_eq!(Assets::balance(0, 2), 100);
+	});
+}
+
+#[test]
+fn fungibles_unbalanced_works() {
+	new_test_ext().execute_with(|| {
+		type A = Assets;
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+
+		assert_eq!(<A as fungibles::Unbalanced<u64>>::increase_balance(0, &3, 20), Ok(20));
+		assert_eq!(Assets::balance(0, 3), 20);
+		assert_eq!(Assets::total_supply(0), 100);
+		assert_eq!(<A as fungibles::Unbalanced<u64>>::decrease_balance(0, &3, 20), Ok(20));
+		assert!(!Account::<Test>::contains_key(0, 3));
+
+		assert_eq!(<A as fungibles::Unbalanced<u64>>::increase_balance_at_most(0, &3, 5), 0);
+		assert_eq!(<A as fungibles::Unbalanced<u64>>::increase_balance_at_most(0, &3, 20), 20);
+		assert_eq!(<A as fungibles::Unbalanced<u64>>::decrease_balance_at_most(0, &3, 100), 20);
+		assert_eq!(Assets::balance(0, 3), 0);
+
+		<A as fungibles::Unbalanced<u64>>::set_total_issuance(0, 7);
+		assert_eq!(Assets::total_supply(0), 7);
+		<A as fungibles::Unbalanced<u64>>::set_total_issuance(1, 7);
+		assert_eq!(Assets::total_supply(1), 0);
+	});
+}
+
+#[test]
+fn stored_map_only_writes_to_existing_accounts() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+
+		assert_eq!(<Assets as StoredMap<(u32, u64), u64>>::get(&(0, 1)), 0);
+		assert_ok!(<Assets as StoredMap<(u32, u64), u64>>::insert(&(0, 1), 5));
+		assert_eq!(<Assets as StoredMap<(u32, u64), u64>>::get(&(0, 1)), 5);
+		assert_eq!(Assets::balance(0, 1), 100);
+
+		assert_noop!(
+			<Assets as StoredMap<(u32, u64), u64>>::insert(&(0, 2), 5),
+			DispatchError::NoProviders
+		);
+		assert_noop!(
+			<Assets as StoredMap<(u32, u64), u64>>::remove(&(0, 1)),
+			DispatchError::ConsumerRemaining
+		);
+		assert_ok!(<Assets as StoredMap<(u32, u64), u64>>::remove(&(0, 2)));
+		assert_eq!(<Assets as StoredMap<(u32, u64), u64>>::get(&(0, 2)), 0);
+	});
+}
+
+#[test]
+fn extra_mutator_commits_and_reverts() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert!(Assets::adjust_extra(0, 2).is_none());
+
+		let mut m = Assets::adjust_extra(0, 1).unwrap();
+		*m = 5;
+		assert_eq!(*m, 5);
+		assert_eq!(Account::<Test>::get(0, 1).extra, 0);
+		assert_ok!(m.commit());
+		assert_eq!(Account::<Test>::get(0, 1).extra, 5);
+		*m = 7;
+		assert_ok!(m.revert());
+		assert_eq!(Account::<Test>::get(0, 1).extra, 0);
+
+		// Dropping the mutator commits any pending change.
+		{
+			let mut m = Assets::adjust_extra(0, 1).unwrap();
+			*m = 9;
+		}
+		assert_eq!(Account::<Test>::get(0, 1).extra, 9);
+	});
+}
+
+#[test]
+fn extra_mutator_fails_once_the_account_is_gone() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+
+		let mut m = Assets::adjust_extra(0, 1).unwrap();
+		*m = 3;
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 100));
+		assert_eq!(m.commit(), Err(()));
+		assert!(!Account::<Test>::contains_key(0, 1));
+	});
+}
+
+#[test]
+fn balance_conversion_should_work() {
+	new_test_ext().execute_with(|| {
+		type Conv = BalanceToAssetBalance<Balances, Test, ConvertInto>;
+
+		struct ConvertToZero;
+		impl Convert<u64, u64> for ConvertToZero {
+			fn convert(_: u64) -> u64 {
+				0
+			}
+		}
+
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::force_create(Origin::root(), 1, 1, false, 10));
+		assert_eq!(Conv::to_asset_balance(100, 0), Ok(1000));
+		assert_eq!(Conv::to_asset_balance(100, 1), Err(ConversionError::AssetNotSufficient));
+		assert_eq!(Conv::to_asset_balance(100, 2), Err(ConversionError::AssetMissing));
+		assert_eq!(
+			BalanceToAssetBalance::<Balances, Test, ConvertToZero>::to_asset_balance(100, 0),
+			Err(ConversionError::MinBalanceZero)
+		);
+	});
+}
+
+fn setup_pool() {
+	assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+	assert_ok!(Assets::force_create(Origin::root(), 1, 1, true, 1));
+	assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 10_000));
+	assert_ok!(Assets::mint(Origin::signed(1), 1, 1, 10_000));
+	Balances::make_free_balance_be(&1, 100);
+}
+
+#[test]
+fn create_pool_should_work() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		assert_ok!(Assets::force_create(Origin::root(), 150, 1, true, 1));
+
+		assert_noop!(Assets::create_pool(Origin::signed(1), 0, 0, 100), Error::<Test>::SameAsset);
+		assert_noop!(Assets::create_pool(Origin::signed(1), 0, 5, 100), Error::<Test>::Unknown);
+		assert_noop!(Assets::create_pool(Origin::signed(1), 0, 1, 5), Error::<Test>::ReservedId);
+		assert_noop!(Assets::create_pool(Origin::signed(1), 0, 1, 150), Error::<Test>::InUse);
+		// The creator pays the deposit of the LP asset.
+		assert_noop!(
+			Assets::create_pool(Origin::signed(5), 0, 1, 100),
+			BalancesError::<Test>::InsufficientBalance
+		);
+
+		assert_ok!(Assets::create_pool(Origin::signed(1), 1, 0, 100));
+		let pool = Assets::pool_account(0, 1);
+		assert_eq!(pool, Assets::pool_account(1, 0));
+		assert_last_event(Event::PoolCreated(0, 1, 1, pool, 100));
+		assert_eq!(System::providers(&pool), 1);
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert_eq!(Asset::<Test>::get(100).unwrap().deposit, 1);
+		assert_eq!(Pools::<Test>::get(0, 1), Some(PoolInfo { lp_asset: 100, creator: 1 }));
+		let report = Assets::integrity_report();
+		assert!(report.is_ok(), "{:?}", report.issues);
+		assert_eq!(Assets::pool_reserves(0, 1), Ok((0, 0)));
+		assert_eq!(Assets::next_asset_id(), 151);
+		assert_eq!(created_assets(), vec![0, 1, 100, 150]);
+
+		assert_noop!(Assets::create_pool(Origin::signed(1), 0, 1, 101), Error::<Test>::PoolExists);
+		assert_noop!(Assets::pool_reserves(0, 150), Error::<Test>::UnknownPool);
+	});
+}
+
+#[test]
+fn liquidity_should_work() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		assert_ok!(Assets::create_pool(Origin::signed(1), 0, 1, 100));
+
+		assert_noop!(
+			Assets::add_liquidity(Origin::signed(1), 0, 2, 1000, 4000, 0, 0),
+			Error::<Test>::UnknownPool
+		);
+		// The first deposit must mint more than the minimum liquidity it locks.
+		assert_noop!(
+			Assets::add_liquidity(Origin::signed(1), 0, 1, 500, 2000, 0, 0),
+			Error::<Test>::InsufficientLiquidity
+		);
+		assert_ok!(Assets::add_liquidity(Origin::signed(1), 0, 1, 1000, 4000, 0, 0));
+		assert_last_event(Event::LiquidityAdded(1, 0, 1, 1000, 4000, 1000));
+		assert_eq!(Assets::pool_reserves(0, 1), Ok((1000, 4000)));
+		assert_eq!(Assets::balance(100, 1), 1000);
+		let pool = Assets::pool_account(0, 1);
+		assert_eq!(Assets::balance(100, pool), MINIMUM_LIQUIDITY as u64);
+		assert_eq!(Assets::total_supply(100), 2000);
+
+		// Later deposits are matched to the pool's price.
+		assert_ok!(Assets::add_liquidity(Origin::signed(1), 0, 1, 100, 1000, 0, 0));
+		assert_last_event(Event::LiquidityAdded(1, 0, 1, 100, 400, 200));
+		assert_ok!(Assets::add_liquidity(Origin::signed(1), 0, 1, 100, 100, 0, 0));
+		assert_last_event(Event::LiquidityAdded(1, 0, 1, 25, 100, 50));
+		assert_eq!(Assets::pool_reserves(0, 1), Ok((1125, 4500)));
+		assert_eq!(Assets::total_supply(100), 2250);
+
+		assert_noop!(
+			Assets::add_liquidity(Origin::signed(1), 0, 1, 100, 1000, 0, 500),
+			Error::<Test>::SlippageExceeded
+		);
+		assert_noop!(
+			Assets::add_liquidity(Origin::signed(1), 0, 1, 0, 0, 0, 0),
+			Error::<Test>::BalanceZero
+		);
+
+		assert_noop!(
+			Assets::remove_liquidity(Origin::signed(1), 0, 1, 0, 0, 0),
+			Error::<Test>::BalanceZero
+		);
+		assert_noop!(
+			Assets::remove_liquidity(Origin::signed(1), 0, 1, 3000, 0, 0),
+			Error::<Test>::BalanceLow
+		);
+		assert_noop!(
+			Assets::remove_liquidity(Origin::signed(1), 0, 2, 250, 0, 0),
+			Error::<Test>::UnknownPool
+		);
+		assert_noop!(
+			Assets::remove_liquidity(Origin::signed(1), 0, 1, 250, 126, 0),
+			Error::<Test>::SlippageExceeded
+		);
+		assert_ok!(Assets::remove_liquidity(Origin::signed(1), 0, 1, 250, 125, 500));
+		assert_last_event(Event::LiquidityRemoved(1, 0, 1, 125, 500, 250));
+		assert_eq!(Assets::pool_reserves(0, 1), Ok((1000, 4000)));
+		assert_eq!(Assets::balance(100, 1), 1000);
+	});
+}
+
+#[test]
+fn first_deposit_should_count_donated_reserves() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		assert_ok!(Assets::create_pool(Origin::signed(1), 0, 1, 100));
+		let pool = Assets::pool_account(0, 1);
+		// One side of the pool is funded before anyone adds liquidity.
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, pool, 1));
+		assert_eq!(Assets::pool_reserves(0, 1), Ok((1, 0)));
+
+		// sqrt(1001 * 4000) = 2000, of which 1000 is locked.
+		assert_ok!(Assets::add_liquidity(Origin::signed(1), 0, 1, 1000, 4000, 0, 0));
+		assert_last_event(Event::LiquidityAdded(1, 0, 1, 1000, 4000, 1000));
+		assert_eq!(Assets::pool_reserves(0, 1), Ok((1001, 4000)));
+		assert_eq!(Assets::total_supply(100), 2000);
+
+		// Later deposits are matched to the price the donation is part of.
+		assert_ok!(Assets::add_liquidity(Origin::signed(1), 0, 1, 100, 1000, 0, 0));
+		assert_last_event(Event::LiquidityAdded(1, 0, 1, 100, 399, 199));
+	});
+}
+
+#[test]
+fn swaps_should_work() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		assert_ok!(Assets::create_pool(Origin::signed(1), 0, 1, 100));
+		assert_noop!(
+			Assets::swap_exact_in(Origin::signed(1), 0, 1, 100, 0),
+			Error::<Test>::InsufficientLiquidity
+		);
+		assert_ok!(Assets::add_liquidity(Origin::signed(1), 0, 1, 1000, 4000, 0, 0));
+
+		// 100 in, 99 of it after the fee: 99 * 4000 / 1099.
+		assert_eq!(Assets::quote_exact_in(0, 1, 100), Some(360));
+		assert_noop!(
+			Assets::swap_exact_in(Origin::signed(1), 0, 1, 100, 361),
+			Error::<Test>::SlippageExceeded
+		);
+		assert_noop!(
+			Assets::swap_exact_in(Origin::signed(1), 0, 1, 0, 0),
+			Error::<Test>::BalanceZero
+		);
+		assert_ok!(Assets::swap_exact_in(Origin::signed(1), 0, 1, 100, 360));
+		assert_last_event(Event::Swapped(1, 0, 1, 100, 360));
+		assert_eq!(Assets::pool_reserves(0, 1), Ok((1100, 3640)));
+
+		assert_eq!(Assets::quote_exact_out(0, 1, 100), Some(32));
+		assert_noop!(
+			Assets::swap_exact_out(Origin::signed(1), 0, 1, 100, 31),
+			Error::<Test>::SlippageExceeded
+		);
+		assert_ok!(Assets::swap_exact_out(Origin::signed(1), 0, 1, 100, 32));
+		assert_last_event(Event::Swapped(1, 0, 1, 32, 100));
+		assert_eq!(Assets::pool_reserves(0, 1), Ok((1132, 3540)));
+
+		assert_eq!(Assets::quote_exact_out(0, 1, 3540), None);
+		assert_noop!(
+			Assets::swap_exact_out(Origin::signed(1), 0, 1, 3540, u64::MAX),
+			Error::<Test>::InsufficientLiquidity
+		);
+		assert_noop!(
+			Assets::swap_exact_in(Origin::signed(1), 0, 2, 100, 0),
+			Error::<Test>::UnknownPool
+		);
+		assert_eq!(Assets::quote_exact_in(0, 2, 100), None);
+	});
+}
+
+#[test]
+fn integrity_report_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		Balances::make_free_balance_be(&2, 100);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::create(Origin::signed(1), 100, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_ok!(Assets::mint(Origin::signed(1), 100, 2, 50));
+		assert_ok!(Assets::touch(Origin::signed(2), 0));
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 10));
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 100, vec![0u8; 4], vec![0u8; 4], 12));
+
+		let report = Assets::integrity_report();
+		assert!(report.is_ok(), "{:?}", report.issues);
+		assert_eq!((report.assets, report.accounts, report.approvals), (2, 3, 1));
+	});
+}
+
+#[test]
+fn integrity_report_finds_inconsistencies() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::create(Origin::signed(1), 100, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_ok!(Assets::mint(Origin::signed(1), 100, 1, 100));
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 100, 2, 10));
+
+		Asset::<Test>::mutate(0, |d| {
+			let d = d.as_mut().unwrap();
+			d.supply = 90;
+			d.accounts = 2;
+			d.sufficients = 0;
+		});
+		Asset::<Test>::mutate(100, |d| d.as_mut().unwrap().approvals = 0);
+		Account::<Test>::insert(7, 1, AssetBalance::default());
+		Metadata::<Test>::insert(7, AssetMetadata::default());
+		Freezes::<Test>::insert(0, 3, BoundedVec::try_from(vec![(1, 10)]).unwrap());
+		let reserved = Balances::reserved_balance(&1);
+		Balances::unreserve(&1, reserved);
+
+		// Assets are visited in storage order, so only the set of issues is fixed.
+		let issues = Assets::integrity_report().issues;
+		let expected = vec![
+			IntegrityIssue::Supply(0, 90, 100),
+			IntegrityIssue::Accounts(0, 2, 1),
+			IntegrityIssue::Sufficients(0, 0, 1),
+			IntegrityIssue::Approvals(100, 0, 1),
+			IntegrityIssue::OrphanedAccount(7, 1),
+			IntegrityIssue::OrphanedMetadata(7),
+			IntegrityIssue::Reserved(1, reserved, 0),
+			IntegrityIssue::OrphanedFreeze(0, 3),
+		];
+		assert_eq!(issues.len(), expected.len(), "{:?}", issues);
+		for issue in expected {
+			assert!(issues.contains(&issue), "{:?} not in {:?}", issue, issues);
+		}
+	});
+}
+
+#[test]
+fn holders_should_page_through_all_accounts() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		for who in 1..=5 {
+			assert_ok!(Assets::mint(Origin::signed(1), 0, who, who * 10));
+		}
+		assert_ok!(Assets::force_create(Origin::root(), 1, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 1, 6, 100));
+
+		let mut holders = Vec::new();
+		let mut start_after = None;
+		loop {
+			let page = Assets::holders(0, start_after, 2);
+			assert!(page.len() <= 2);
+			match page.last() {
+				Some((who, _)) => start_after = Some(*who),
+				None => break,
+			}
+			holders.extend(page);
+		}
+		holders.sort();
+		assert_eq!(holders, vec![(1, 10), (2, 20), (3, 30), (4, 40), (5, 50)]);
+		assert_eq!(Assets::holders(1, None, 10), vec![(6, 100)]);
+		assert!(Assets::holders(2, None, 10).is_empty());
+		assert!(Assets::holders(0, None, 0).is_empty());
+	});
+}
+
+#[test]
+fn approvals_of_should_page_through_all_delegates() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		for delegate in 2..=4 {
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, delegate, delegate));
+		}
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+		Balances::make_free_balance_be(&2, 100);
+		assert_ok!(Assets::approve_transfer(Origin::signed(2), 0, 1, 50));
+
+		let first = Assets::approvals_of(0, 1, None, 2);
+		assert_eq!(first.len(), 2);
+		let rest = Assets::approvals_of(0, 1, first.last().map(|(d, _)| *d), 2);
+		assert_eq!(rest.len(), 1);
+		let mut approvals = [first, rest].concat();
+		approvals.sort();
+		assert_eq!(approvals, vec![(2, 2), (3, 3), (4, 4)]);
+		assert_eq!(Assets::approvals_of(0, 2, None, 10), vec![(1, 50)]);
+		assert!(Assets::approvals_of(0, 3, None, 10).is_empty());
+	});
+}
+
+#[test]
+fn approvals_by_owner_and_delegate_should_page_across_assets() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		for id in 0..2 {
+			assert_ok!(Assets::force_create(Origin::root(), id, 1, true, 1));
+			assert_ok!(Assets::mint(Origin::signed(1), id, 1, 100));
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), id, 2, 10 + id as u64));
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), id, 3, 20 + id as u64));
+		}
+
+		let first = Assets::approvals_by_owner(1, None, 3);
+		assert_eq!(first.len(), 3);
+		let last = first.last().map(|(id, delegate, _)| (*id, *delegate));
+		let rest = Assets::approvals_by_owner(1, last, 3);
+		assert_eq!(rest.len(), 1);
+		let mut approvals = [first, rest].concat();
+		approvals.sort();
+		assert_eq!(approvals, vec![(0, 2, 10), (0, 3, 20), (1, 2, 11), (1, 3, 21)]);
+
+		let mut approvals = Assets::approvals_by_delegate(2, None, 10);
+		approvals.sort();
+		assert_eq!(approvals, vec![(0, 1, 10), (1, 1, 11)]);
+		assert!(Assets::approvals_by_owner(2, None, 10).is_empty());
+		assert!(Assets::approvals_by_delegate(1, None, 0).is_empty());
+
+		// Cancelled, fully used and destroyed approvals leave the indexes.
+		assert_ok!(Assets::cancel_approval(Origin::signed(1), 0, 2));
+		assert_ok!(Assets::transfer_approved(Origin::signed(3), 0, 1, 4, 20));
+		assert!(Assets::approvals_by_owner(1, None, 10).iter().all(|(id, ..)| *id == 1));
+		assert_eq!(Assets::approvals_by_delegate(3, None, 10), vec![(1, 1, 21)]);
+
+		assert_ok!(Assets::start_destroy(Origin::signed(1), 1));
+		assert_ok!(Assets::destroy_approvals(Origin::signed(1), 1, 10));
+		assert!(Assets::approvals_by_owner(1, None, 10).is_empty());
+		assert!(Assets::approvals_by_delegate(2, None, 10).is_empty());
+		assert!(Assets::approvals_by_delegate(3, None, 10).is_empty());
+	});
+}
+
+#[test]
+fn top_holders_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		for (who, amount) in [(1, 30), (2, 50), (3, 10), (4, 40), (5, 20)].iter() {
+			assert_ok!(Assets::mint(Origin::signed(1), 0, *who, *amount));
+		}
+
+		assert_eq!(Assets::top_holders(0, 3), vec![(2, 50), (4, 40), (1, 30)]);
+		assert_eq!(Assets::top_holders(0, 10).len(), 5);
+		assert!(Assets::top_holders(0, 0).is_empty());
+		assert!(Assets::top_holders(1, 3).is_empty());
+	});
+}
+
+#[test]
+fn genesis_config_should_work() {
+	use frame_support::traits::GenesisBuild;
+
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(2, 100)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	crate::GenesisConfig::<Test> {
+		assets: vec![(0, 1, true, 1), (150, 1, false, 1)],
+		metadata: vec![(0, b"Token".to_vec(), b"TKN".to_vec(), 6)],
+		accounts: vec![(0, 1, 100), (0, 2, 50), (150, 2, 30)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_eq!(Assets::balance(0, 2), 50);
+		assert_eq!(Assets::balance(150, 2), 30);
+		assert_eq!(Asset::<Test>::get(0).unwrap().supply, 150);
+		assert_eq!(Asset::<Test>::get(150).unwrap().accounts, 1);
+		assert_eq!(Account::<Test>::get(150, 2).reason, ExistenceReason::Consumer);
+		assert_eq!(System::sufficients(&1), 1);
+		assert_eq!(Metadata::<Test>::get(0).symbol.to_vec(), b"TKN".to_vec());
+		assert_eq!(Assets::next_asset_id(), 151);
+		assert_eq!(created_assets(), vec![0, 150]);
+		assert!(Assets::integrity_report().is_ok());
+	});
+}
+
+fn airdrop_tree(claims: &[(u64, u64)]) -> MerkleTree {
+	MerkleTree::new(
+		claims
+			.iter()
+			.enumerate()
+			.map(|(index, (who, amount))| airdrop_leaf(index as u32, who, *amount))
+			.collect(),
+	)
+}
+
+#[test]
+fn merkle_tree_proofs_should_work() {
+	for n in 1..=9u64 {
+		let claims: Vec<_> = (0..n).map(|i| (i, i * 10 + 1)).collect();
+		let tree = airdrop_tree(&claims);
+		let root = tree.root().unwrap();
+		for (index, (who, amount)) in claims.iter().enumerate() {
+			let leaf = airdrop_leaf(index as u32, who, *amount);
+			assert_eq!(merkle_root_from_proof(leaf, &tree.proof(index).unwrap()), root);
+		}
+		assert_eq!(tree.proof(n as usize), None);
+	}
+	assert_eq!(MerkleTree::new(vec![]).root(), None);
+}
+
+#[test]
+fn airdrop_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		let tree = airdrop_tree(&[(2, 100), (3, 200), (4, 300)]);
+		let root = tree.root().unwrap();
+
+		assert_noop!(
+			Assets::create_airdrop(Origin::signed(2), 0, root, 3, 600, 10, true),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Assets::create_airdrop(Origin::signed(1), 0, root, 3, 600, 1, true),
+			Error::<Test>::AirdropExpired
+		);
+		assert_ok!(Assets::create_airdrop(Origin::signed(1), 0, root, 3, 600, 10, true));
+		assert_last_event(Event::AirdropCreated(0, 0, 1, root, 600));
+		assert_eq!(Assets::balance(0, Assets::airdrop_account(0)), 600);
+		assert_eq!(Assets::total_supply(0), 600);
+		assert_eq!(Balances::reserved_balance(&1), 1);
+
+		// Recipients need no native balance to claim.
+		let proof = tree.proof(1).unwrap();
+		assert_ok!(Assets::claim_airdrop(Origin::none(), 0, 1, 3, 200, proof.clone()));
+		assert_last_event(Event::AirdropClaimed(0, 0, 3, 200));
+		assert_eq!(Assets::balance(0, 3), 200);
+		assert_eq!(Airdrops::<Test>::get(0).unwrap().claimed, 200);
+		assert!(Assets::is_claimed(0, 1));
+		assert!(!Assets::is_claimed(0, 0));
+		assert_noop!(
+			Assets::claim_airdrop(Origin::none(), 0, 1, 3, 200, proof),
+			Error::<Test>::AlreadyClaimed
+		);
+
+		// The proof of one leaf does not prove another, nor another amount.
+		assert_noop!(
+			Assets::claim_airdrop(Origin::none(), 0, 0, 2, 100, tree.proof(2).unwrap()),
+			Error::<Test>::InvalidProof
+		);
+		assert_noop!(
+			Assets::claim_airdrop(Origin::none(), 0, 0, 2, 101, tree.proof(0).unwrap()),
+			Error::<Test>::InvalidProof
+		);
+		assert_noop!(
+			Assets::claim_airdrop(Origin::signed(2), 0, 0, 2, 100, tree.proof(0).unwrap()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Assets::claim_airdrop(Origin::none(), 1, 0, 2, 100, tree.proof(0).unwrap()),
+			Error::<Test>::UnknownAirdrop
+		);
+		assert!(Assets::integrity_report().is_ok());
+	});
+}
+
+#[test]
+fn airdrop_claims_should_be_validated_as_unsigned() {
+	use sp_runtime::{
+		traits::ValidateUnsigned,
+		transaction_validity::{InvalidTransaction, TransactionSource},
+	};
+
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		let tree = airdrop_tree(&[(2, 100), (3, 200)]);
+		let root = tree.root().unwrap();
+		assert_ok!(Assets::create_airdrop(Origin::signed(1), 0, root, 2, 300, 10, true));
+
+		let validate = |airdrop, index: usize, who, amount| {
+			let proof = tree.proof(index).unwrap();
+			let call = crate::Call::claim_airdrop(airdrop, index as u32, who, amount, proof);
+			Assets::validate_unsigned(TransactionSource::External, &call)
+		};
+		let valid = validate(0, 0, 2, 100).unwrap();
+		assert_eq!(valid.priority, 100);
+		assert_eq!(valid.longevity, 9);
+		assert_eq!(validate(0, 0, 2, 101), Err(InvalidTransaction::BadProof.into()));
+		assert_eq!(validate(1, 0, 2, 100), Err(InvalidTransaction::Call.into()));
+
+		assert_ok!(Assets::claim_airdrop(Origin::none(), 0, 0, 2, 100, tree.proof(0).unwrap()));
+		assert_eq!(validate(0, 0, 2, 100), Err(InvalidTransaction::Stale.into()));
+		System::set_block_number(10);
+		assert_eq!(validate(0, 1, 3, 200), Err(InvalidTransaction::Stale.into()));
+	});
+}
+
+#[test]
+fn airdrops_need_a_sufficient_asset() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, false, 1));
+		let root = airdrop_tree(&[(2, 100)]).root().unwrap();
+		// Account 2 has no native balance, so it could never hold its share.
+		assert_noop!(
+			Assets::create_airdrop(Origin::signed(1), 0, root, 1, 100, 10, true),
+			Error::<Test>::AirdropNotSufficient
+		);
+	});
+}
+
+#[test]
+fn reclaim_airdrop_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 1000));
+		let tree = airdrop_tree(&[(2, 100), (3, 200), (4, 300)]);
+		assert_ok!(Assets::create_airdrop(
+			Origin::signed(1),
+			0,
+			tree.root().unwrap(),
+			3,
+			600,
+			10,
+			false
+		));
+		assert_eq!(Assets::balance(0, 1), 400);
+		assert_ok!(Assets::claim_airdrop(Origin::none(), 0, 0, 2, 100, tree.proof(0).unwrap()));
+
+		assert_noop!(
+			Assets::reclaim_airdrop(Origin::signed(1), 0, 1),
+			Error::<Test>::AirdropNotExpired
+		);
+		System::set_block_number(10);
+		assert_noop!(
+			Assets::claim_airdrop(Origin::none(), 0, 1, 3, 200, tree.proof(1).unwrap()),
+			Error::<Test>::AirdropExpired
+		);
+		assert_noop!(Assets::reclaim_airdrop(Origin::signed(2), 0, 1), Error::<Test>::NoPermission);
+		assert_noop!(Assets::reclaim_airdrop(Origin::signed(1), 0, 0), Error::<Test>::BadWitness);
+		assert_ok!(Assets::reclaim_airdrop(Origin::signed(1), 0, 1));
+		assert_last_event(Event::AirdropReclaimed(0, 0, 1, 500));
+
+		let pot = Assets::airdrop_account(0);
+		assert_eq!(Assets::balance(0, 1), 900);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert!(!Account::<Test>::contains_key(0, &pot));
+		assert_eq!(System::providers(&pot), 0);
+		assert!(Airdrops::<Test>::get(0).is_none());
+		assert!(!Assets::is_claimed(0, 0));
+		assert!(Assets::integrity_report().is_ok());
+	});
+}
+
+#[test]
+fn reclaim_airdrop_of_an_asset_being_destroyed_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		let tree = airdrop_tree(&[(2, 100), (3, 200)]);
+		assert_ok!(Assets::create_airdrop(
+			Origin::signed(1),
+			0,
+			tree.root().unwrap(),
+			2,
+			300,
+			10,
+			true
+		));
+		let pot = Assets::airdrop_account(0);
+		assert!(Assets::integrity_report().is_ok());
+		// The airdrop deposit is one of those the report requires to be reserved.
+		Balances::unreserve(&1, 1);
+		assert_eq!(Assets::integrity_report().issues, vec![IntegrityIssue::Reserved(1, 1, 0)]);
+		assert_ok!(Balances::reserve(&1, 1));
+
+		assert_ok!(Assets::start_destroy(Origin::signed(1), 0));
+		System::set_block_number(10);
+		assert_ok!(Assets::reclaim_airdrop(Origin::signed(1), 0, 1));
+		assert_last_event(Event::AirdropReclaimed(0, 0, 1, 0));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(System::providers(&pot), 0);
+		assert!(Airdrops::<Test>::get(0).is_none());
+
+		// The budget goes with the asset.
+		assert_ok!(Assets::destroy_accounts(Origin::signed(1), 0, 1));
+		assert!(!Account::<Test>::contains_key(0, &pot));
+		assert!(!System::account_exists(&pot));
+	});
+}
+
+#[test]
+fn instances_should_be_independent() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(WideAssets::force_create(Origin::root(), 0, 1, true, 1u8.into()));
+
+		let large = U256Balance::from(u128::MAX) + 1u8.into();
+		assert_ok!(WideAssets::mint(Origin::signed(1), 0, 2, large));
+		assert_eq!(WideAssets::balance(0, 2), large);
+		assert_eq!(u128::try_from(large), Err(()));
+		assert_eq!(WideAssets::total_supply(0), large);
+		assert_eq!(Assets::balance(0, 2), 0);
+		assert_eq!(Assets::total_supply(0), 0);
+
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 11], vec![0u8; 3], 6));
+		assert_noop!(
+			WideAssets::set_metadata(Origin::signed(1), 0, vec![0u8; 11], vec![0u8; 3], 6),
+			Error::<Test, Instance2>::BadMetadata
+		);
+		assert!(Metadata::<Test, Instance2>::get(0).name.is_empty());
+		assert_ne!(Assets::airdrop_account(0), WideAssets::airdrop_account(0));
+	});
+}
+
+#[test]
+fn pools_should_work_with_balances_beyond_128_bits() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		let large = U256Balance::from(u128::MAX) + 1u8.into();
+		for id in 0..2 {
+			assert_ok!(WideAssets::force_create(Origin::root(), id, 1, true, 1u8.into()));
+			assert_ok!(WideAssets::mint(Origin::signed(1), id, 1, large * 4u8.into()));
+		}
+		assert_ok!(WideAssets::create_pool(Origin::signed(1), 0, 1, 100));
+
+		let zero = U256Balance::zero();
+		assert_ok!(WideAssets::add_liquidity(Origin::signed(1), 0, 1, large, large, zero, zero));
+		assert_eq!(WideAssets::balance(100, 1), large - MINIMUM_LIQUIDITY.into());
+		assert_eq!(WideAssets::total_supply(100), large);
+
+		// Selling as much again as the reserve gets a little under half of the other reserve.
+		let amount_out = WideAssets::quote_exact_in(0, 1, large).unwrap();
+		assert!(amount_out > large / 4u8.into() && amount_out < large / 2u8.into());
+		assert_ok!(WideAssets::swap_exact_in(Origin::signed(1), 0, 1, large, amount_out));
+		assert_eq!(WideAssets::pool_reserves(0, 1), Ok((large * 2u8.into(), large - amount_out)));
+	});
+}
+
+#[test]
+fn u256_balance_should_work() {
+	let max = U256Balance::max_value();
+	let two_128 = U256Balance::from(u128::MAX) + 1u8.into();
+	assert_eq!(two_128 - 1u8.into(), U256Balance::from(u128::MAX));
+	assert!(two_128 > U256Balance::from(u128::MAX));
+	assert_eq!(U256Balance::from(u128::MAX) * two_128 + u128::MAX.into(), max);
+	assert_eq!(max / two_128, U256Balance::from(u128::MAX));
+	assert_eq!(max % two_128, U256Balance::from(u128::MAX));
+	assert_eq!(max.integer_sqrt(), U256Balance::from(u128::MAX));
+	assert_eq!(U256Balance::one() << 128, two_128);
+	assert_eq!(two_128 >> 128, U256Balance::one());
+	assert_eq!(U256Balance::one().checked_shl(256), None);
+
+	assert_eq!(max.checked_add(&1u8.into()), None);
+	assert_eq!(U256Balance::zero().checked_sub(&1u8.into()), None);
+	assert_eq!(max.checked_mul(&2u8.into()), None);
+	assert_eq!(max.checked_div(&0u8.into()), None);
+	assert_eq!(max.saturating_add(1u8.into()), max);
+	assert_eq!(U256Balance::zero().saturating_sub(1u8.into()), U256Balance::zero());
+	assert_eq!(max.saturating_mul(2u8.into()), max);
+
+	assert_eq!(u64::try_from(U256Balance::from(u64::MAX)), Ok(u64::MAX));
+	assert_eq!(u64::try_from(U256Balance::from(u64::MAX as u128 + 1)), Err(()));
+	assert_eq!(u128::try_from(two_128), Err(()));
+	assert_eq!(two_128.saturated_into::<u128>(), u128::MAX);
+
+	let text = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+	assert_eq!(max.to_string(), text);
+	assert_eq!(text.parse(), Ok(max));
+	assert!("115792089237316195423570985008687907853269984665640564039457584007913129639936"
+		.parse::<U256Balance>()
+		.is_err());
+
+	assert_eq!(two_128.encode(), sp_core::U256::from(two_128).encode());
+	assert_eq!(U256Balance::decode(&mut &two_128.encode()[..]), Ok(two_128));
+	assert_eq!(U256Balance::max_encoded_len(), 32);
+	assert_eq!(codec::Compact(U256Balance::from(5u8)).encode(), codec::Compact(5u128).encode());
+	let compact = |x: U256Balance| {
+		codec::Compact::<U256Balance>::decode(&mut &codec::Compact(x).encode()[..]).map(|x| x.0)
+	};
+	let below = U256Balance::from(u128::MAX - 1);
+	assert_eq!(compact(below), Ok(below));
+	assert!(compact(U256Balance::from(u128::MAX)).is_err());
+	assert!(compact(two_128).is_err());
+	assert!(compact(max).is_err());
+}
+
+#[test]
+fn u256_deposit_consequences_should_overflow() {
+	new_test_ext().execute_with(|| {
+		type A = WideAssets;
+		let max = U256Balance::max_value();
+		assert_ok!(WideAssets::force_create(Origin::root(), 0, 1, true, 1u8.into()));
+		assert_ok!(WideAssets::mint(Origin::signed(1), 0, 1, max - 1u8.into()));
+		assert_eq!(WideAssets::total_supply(0), max - 1u8.into());
+
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_deposit(0, &2, 1u8.into()),
+			DepositConsequence::Success
+		);
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_deposit(0, &2, 2u8.into()),
+			DepositConsequence::Overflow
+		);
+		assert_noop!(
+			WideAssets::mint(Origin::signed(1), 0, 2, 2u8.into()),
+			ArithmeticError::Overflow
+		);
+		assert_ok!(WideAssets::mint(Origin::signed(1), 0, 2, 1u8.into()));
+		assert_eq!(WideAssets::total_supply(0), max);
+		assert_eq!(
+			<A as fungibles::Inspect<u64>>::can_withdraw(0, &2, 2u8.into()),
+			WithdrawConsequence::NoFunds
+		);
+	});
+}
+
+#[test]
+fn u256_balance_conversion_should_work() {
+	new_test_ext().execute_with(|| {
+		type Conv = BalanceToAssetBalance<Balances, Test, ConvertInto, Instance2>;
+
+		// 10^30 asset units for every unit of the native existential deposit of 1.
+		let ratio: U256Balance = "1000000000000000000000000000000".parse().unwrap();
+		assert_ok!(WideAssets::force_create(Origin::root(), 0, 1, true, ratio));
+		assert_eq!(Conv::to_asset_balance(u64::MAX, 0), Ok(U256Balance::from(u64::MAX) * ratio));
+		assert!(u128::try_from(Conv::to_asset_balance(u64::MAX, 0).unwrap()).is_err());
+	});
+}
+
+#[test]
+fn migration_to_v2_should_widen_balances() {
+	use crate::migration::{
+		v2::{MigrateToV2, OldApproval},
+		v3::MigrateToV3,
+		v4::MigrateToV4,
+	};
+	use frame_support::{
+		storage::unhashed,
+		traits::{OnRuntimeUpgrade, StorageVersion},
+	};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<WideAssets>();
+		let details = AssetDetails::<u64, u64, u64> {
+			owner: 1,
+			issuer: 1,
+			admin: 1,
+			freezer: 1,
+			supply: u64::MAX,
+			deposit: 0,
+			min_balance: 1,
+			is_sufficient: true,
+			accounts: 1,
+			sufficients: 1,
+			approvals: 1,
+			status: AssetStatus::Live,
+		};
+		let account = AssetBalance::<u64, u64, (), u64> {
+			balance: u64::MAX,
+			is_frozen: false,
+			reason: ExistenceReason::Sufficient,
+			extra: (),
+		};
+		let approval = OldApproval::<u64, u64> { amount: 7, deposit: 1 };
+		unhashed::put(&Asset::<Test, Instance2>::hashed_key_for(0), &details);
+		unhashed::put(&Account::<Test, Instance2>::hashed_key_for(0, 1), &account);
+		unhashed::put(&Approvals::<Test, Instance2>::hashed_key_for((0u32, 1u64, 2u64)), &approval);
+
+		MigrateToV2::<Test, Instance2, u64>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<WideAssets>(), 2);
+		let asset = Asset::<Test, Instance2>::get(0).unwrap();
+		assert_eq!(asset.supply, U256Balance::from(u64::MAX));
+		assert_eq!(asset.min_balance, U256Balance::one());
+		assert_eq!(WideAssets::balance(0, 1), U256Balance::from(u64::MAX));
+		// The approval is widened, and still has the two fields of version 2.
+		let key = Approvals::<Test, Instance2>::hashed_key_for((0u32, 1u64, 2u64));
+		let widened = OldApproval::<U256Balance, u64> { amount: 7u8.into(), deposit: 1 };
+		assert_eq!(unhashed::get_raw(&key), Some(widened.encode()));
+
+		// Running it again changes nothing.
+		MigrateToV2::<Test, Instance2, u64>::on_runtime_upgrade();
+		assert_eq!(WideAssets::balance(0, 1), U256Balance::from(u64::MAX));
+		assert_eq!(unhashed::get_raw(&key), Some(widened.encode()));
+
+		// The later migrations add the flag.
+		MigrateToV3::<Test, Instance2>::on_runtime_upgrade();
+		MigrateToV4::<Test, Instance2>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get::<WideAssets>(), 4);
+		let approval = Approval { amount: 7u8.into(), deposit: 1, unlimited: false };
+		assert_eq!(Approvals::<Test, Instance2>::get((0, 1, 2)), Some(approval));
+		assert_eq!(WideAssets::allowance(0, &1, &2), 7u8.into());
+	});
+}
+
+#[test]
+fn migration_to_v3_should_index_approvals() {
+	use crate::migration::v3::MigrateToV3;
+	use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(2).put::<Assets>();
+		Approvals::<Test>::insert((0, 1, 2), Approval { amount: 7, deposit: 1, unlimited: false });
+		Approvals::<Test>::insert((1, 1, 3), Approval { amount: 9, deposit: 1, unlimited: false });
+		assert!(Assets::approvals_by_owner(1, None, 10).is_empty());
+
+		MigrateToV3::<Test>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<Assets>(), 3);
+		let mut approvals = Assets::approvals_by_owner(1, None, 10);
+		approvals.sort();
+		assert_eq!(approvals, vec![(0, 2, 7), (1, 3, 9)]);
+		assert_eq!(Assets::approvals_by_delegate(3, None, 10), vec![(1, 1, 9)]);
+	});
+}
+
+#[test]
+fn migration_to_v4_should_add_the_unlimited_flag() {
+	use crate::migration::v4::{MigrateToV4, OldApproval};
+	use frame_support::{
+		storage::unhashed,
+		traits::{OnRuntimeUpgrade, StorageVersion},
+	};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(3).put::<Assets>();
+		let approval = OldApproval::<u64, u64> { amount: 7, deposit: 1 };
+		unhashed::put(&Approvals::<Test>::hashed_key_for((0u32, 1u64, 2u64)), &approval);
+
+		MigrateToV4::<Test>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<Assets>(), 4);
+		assert_eq!(
+			Approvals::<Test>::get((0, 1, 2)),
+			Some(Approval { amount: 7, deposit: 1, unlimited: false })
+		);
+		assert_eq!(Assets::allowance(0, &1, &2), 7);
+	});
+}
+
+#[test]
+fn migration_to_v5_should_notify_the_assets_once() {
+	use crate::migration::v5::{MigrateToV5, OldPoolInfo};
+	use frame_support::{
+		storage::unhashed,
+		traits::{Get, OnRuntimeUpgrade, StorageVersion},
+	};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(4).put::<Assets>();
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		// An asset created before there was a callback.
+		Asset::<Test>::insert(1, Asset::<Test>::get(0).unwrap());
+		let old_pool = OldPoolInfo { lp_asset: 100u32 };
+		unhashed::put(&Pools::<Test>::hashed_key_for(0u32, 1u32), &old_pool);
+		assert_eq!(created_assets(), vec![0]);
+
+		MigrateToV5::<Test>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get::<Assets>(), 5);
+		assert_eq!(created_assets(), vec![0, 1]);
+
+		// Pools from before version 5 took no deposit, and are recorded as their own creators.
+		let pool = Assets::pool_account(0, 1);
+		assert_eq!(Pools::<Test>::get(0, 1), Some(PoolInfo { lp_asset: 100, creator: pool }));
+
+		// Once at version 5, it reads the version and stops.
+		Asset::<Test>::insert(2, Asset::<Test>::get(0).unwrap());
+		let weight = MigrateToV5::<Test>::on_runtime_upgrade();
+		assert_eq!(weight, <Test as frame_system::Config>::DbWeight::get().reads(1));
+		assert_eq!(created_assets(), vec![0, 1]);
+	});
+}
+
+fn address_word(who: u64) -> Vec<u8> {
+	sp_core::H256::from(H160::from_low_u64_be(who)).as_bytes().to_vec()
+}
+
+fn abi_input(selector: [u8; 4], args: &[Vec<u8>]) -> Vec<u8> {
+	let mut input = selector.to_vec();
+	args.iter().for_each(|arg| input.extend_from_slice(arg));
+	input
+}
+
+#[test]
+fn abi_calls_should_work() {
+	use abi::*;
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		let (token, tkn) = (b"Token".to_vec(), b"TKN".to_vec());
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, token, tkn, 12));
+
+		let mut name = encode_uint(32u32.into());
+		name.extend(encode_uint(5u32.into()));
+		name.extend(b"Token");
+		name.extend([0u8; 27].iter());
+		assert_eq!(Assets::call_abi(0, &SELECTOR_NAME), Ok(name));
+		assert_eq!(Assets::call_abi(0, &SELECTOR_SYMBOL), Ok(encode_string(b"TKN")));
+		assert_eq!(Assets::call_abi(0, &SELECTOR_DECIMALS), Ok(encode_uint(12u32.into())));
+
+		let transfer = abi_input(SELECTOR_TRANSFER, &[address_word(2), encode_uint(30u32.into())]);
+		assert_ok!(Assets::dispatch_abi(Origin::signed(1), 0, transfer));
+		assert_last_event(Event::Transferred(0, 1, 2, 30));
+		let balance_of = abi_input(SELECTOR_BALANCE_OF, &[address_word(2)]);
+		assert_eq!(Assets::call_abi(0, &balance_of), Ok(encode_uint(30u32.into())));
+		assert_eq!(Assets::call_abi(0, &SELECTOR_TOTAL_SUPPLY), Ok(encode_uint(100u32.into())));
+
+		// `approve` replaces the approval, where `approve_transfer` would add to it.
+		let approve = |amount: u32| {
+			abi_input(SELECTOR_APPROVE, &[address_word(2), encode_uint(amount.into())])
+		};
+		let allowance = abi_input(SELECTOR_ALLOWANCE, &[address_word(1), address_word(2)]);
+		assert_ok!(Assets::dispatch_abi(Origin::signed(1), 0, approve(50)));
+		assert_ok!(Assets::dispatch_abi(Origin::signed(1), 0, approve(20)));
+		assert_eq!(Assets::call_abi(0, &allowance), Ok(encode_uint(20u32.into())));
+		assert_eq!(Asset::<Test>::get(0).unwrap().approvals, 1);
+
+		let transfer_from = abi_input(
+			SELECTOR_TRANSFER_FROM,
+			&[address_word(1), address_word(3), encode_uint(15u32.into())],
+		);
+		assert_ok!(Assets::dispatch_abi(Origin::signed(2), 0, transfer_from));
+		assert_has_event(Event::Transferred(0, 1, 3, 15));
+		assert_last_event(Event::ApprovalUsed(0, 1, 2, 3, 15, 5));
+		assert_eq!(Assets::balance(0, 3), 15);
+		assert_eq!(Assets::call_abi(0, &allowance), Ok(encode_uint(5u32.into())));
+
+		assert_ok!(Assets::dispatch_abi(Origin::signed(1), 0, approve(0)));
+		assert!(Approvals::<Test>::get((0, 1, 2)).is_none());
+		assert_eq!(Assets::call_abi(0, &allowance), Ok(encode_uint(0u32.into())));
+
+		// An `approve` of the maximum `uint256` is unlimited.
+		let approve_max = abi_input(SELECTOR_APPROVE, &[address_word(2), encode_uint(U256::MAX)]);
+		assert_ok!(Assets::dispatch_abi(Origin::signed(1), 0, approve_max));
+		assert!(Assets::is_unlimited_approval(0, &1, &2));
+		assert_eq!(Assets::call_abi(0, &allowance), Ok(encode_uint(U256::MAX)));
+		let transfer_from = abi_input(
+			SELECTOR_TRANSFER_FROM,
+			&[address_word(1), address_word(3), encode_uint(15u32.into())],
+		);
+		assert_ok!(Assets::dispatch_abi(Origin::signed(2), 0, transfer_from));
+		assert_eq!(Assets::call_abi(0, &allowance), Ok(encode_uint(U256::MAX)));
+	});
+}
+
+#[test]
+fn abi_calls_should_reject_bad_input() {
+	use abi::*;
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+
+		assert_noop!(
+			Assets::dispatch_abi(Origin::signed(1), 0, vec![0xde, 0xad, 0xbe, 0xef]),
+			Error::<Test>::UnsupportedSelector
+		);
+		assert_noop!(
+			Assets::dispatch_abi(Origin::signed(1), 0, vec![0xa9, 0x05]),
+			Error::<Test>::UnsupportedSelector
+		);
+		assert_noop!(
+			Assets::dispatch_abi(
+				Origin::signed(1),
+				0,
+				abi_input(SELECTOR_TRANSFER, &[address_word(2)])
+			),
+			Error::<Test>::InvalidAbiInput
+		);
+		let mut dirty = address_word(2);
+		dirty[0] = 1;
+		assert_noop!(
+			Assets::dispatch_abi(
+				Origin::signed(1),
+				0,
+				abi_input(SELECTOR_TRANSFER, &[dirty, encode_uint(1u32.into())])
+			),
+			Error::<Test>::InvalidAbiInput
+		);
+		let too_much = encode_uint(sp_core::U256::from(u64::MAX) + 1);
+		assert_noop!(
+			Assets::dispatch_abi(
+				Origin::signed(1),
+				0,
+				abi_input(SELECTOR_TRANSFER, &[address_word(2), too_much])
+			),
+			ArithmeticError::Overflow
+		);
+
+		assert_noop!(
+			Assets::dispatch_abi(Origin::signed(1), 0, SELECTOR_TOTAL_SUPPLY.to_vec()),
+			Error::<Test>::ReadOnlyFunction
+		);
+		let transfer = abi_input(SELECTOR_TRANSFER, &[address_word(2), encode_uint(1u32.into())]);
+		assert_eq!(
+			Assets::call_abi(0, &transfer),
+			Err(Error::<Test>::StateChangingFunction.into())
+		);
+		assert_eq!(Assets::balance(0, 2), 0);
+	});
+}
+
+#[test]
+fn abi_calls_should_cover_u256_balances() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(WideAssets::force_create(Origin::root(), 0, 1, true, 1u8.into()));
+		let large = U256Balance::from(u128::MAX) + 1u8.into();
+		assert_ok!(WideAssets::mint(Origin::signed(1), 0, 1, large));
+
+		let transfer =
+			abi_input(abi::SELECTOR_TRANSFER, &[address_word(2), abi::encode_uint(large.into())]);
+		assert_ok!(WideAssets::dispatch_abi(Origin::signed(1), 0, transfer));
+		assert_eq!(WideAssets::balance(0, 2), large);
+		let balance_of = abi_input(abi::SELECTOR_BALANCE_OF, &[address_word(2)]);
+		assert_eq!(WideAssets::call_abi(0, &balance_of), Ok(abi::encode_uint(large.into())));
+	});
+}
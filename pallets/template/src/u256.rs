@@ -0,0 +1,287 @@
+//! A 256-bit balance, for assets whose amounts follow the `uint256` of ERC20.
+//!
+//! `U256Balance` keeps its low and high 128 bits, which together encode the same as a little-endian
+//! `U256`, and does its arithmetic as a `U256`. It serializes as a decimal string, since JSON
+//! numbers lose precision far below its range, and deserializes from a string or a number.
+//!
+//! SCALE compact encodings go up to 128 bits. The compact form of a `U256Balance` below
+//! `u128::MAX` is that of the `u128`; every larger balance compacts to `u128::MAX`, which never
+//! decodes, so a compact balance either decodes to the value it was encoded from or fails. The
+//! pallet takes balances in full in its calls.
+
+use codec::{Compact, CompactAs};
+use frame_support::pallet_prelude::{Decode, Encode, MaxEncodedLen};
+use sp_core::U256;
+use sp_runtime::traits::{
+	Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedRem, CheckedShl, CheckedShr, CheckedSub,
+	IntegerSquareRoot, One, Unsigned, Zero,
+};
+use sp_std::{
+	cmp::Ordering,
+	convert::TryFrom,
+	fmt,
+	ops::{
+		Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, Shr, Sub, SubAssign,
+	},
+	str::FromStr,
+};
+
+/// An unsigned 256-bit balance.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Encode, Decode, MaxEncodedLen)]
+pub struct U256Balance {
+	low: u128,
+	high: u128,
+}
+
+impl U256Balance {
+	/// The largest balance, `2^256 - 1`.
+	pub const MAX: Self = U256Balance { low: u128::MAX, high: u128::MAX };
+}
+
+impl From<U256> for U256Balance {
+	fn from(x: U256) -> Self {
+		let U256([a, b, c, d]) = x;
+		U256Balance { low: ((b as u128) << 64) | a as u128, high: ((d as u128) << 64) | c as u128 }
+	}
+}
+
+impl From<U256Balance> for U256 {
+	fn from(x: U256Balance) -> Self {
+		U256([x.low as u64, (x.low >> 64) as u64, x.high as u64, (x.high >> 64) as u64])
+	}
+}
+
+macro_rules! impl_primitive_conversions {
+	($($t:ty),*) => {$(
+		impl From<$t> for U256Balance {
+			fn from(x: $t) -> Self {
+				U256Balance { low: x as u128, high: 0 }
+			}
+		}
+
+		impl TryFrom<U256Balance> for $t {
+			type Error = ();
+
+			fn try_from(x: U256Balance) -> Result<Self, ()> {
+				if x.high != 0 {
+					return Err(())
+				}
+				<$t>::try_from(x.low).map_err(|_| ())
+			}
+		}
+	)*};
+}
+
+impl_primitive_conversions!(u8, u16, u32, u64, u128, usize);
+
+impl Ord for U256Balance {
+	fn cmp(&self, other: &Self) -> Ordering {
+		(self.high, self.low).cmp(&(other.high, other.low))
+	}
+}
+
+impl PartialOrd for U256Balance {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+macro_rules! impl_ops {
+	($($op:ident, $method:ident, $op_assign:ident, $method_assign:ident, $checked:ident,
+		$checked_method:ident;)*) => {$(
+		impl $op for U256Balance {
+			type Output = Self;
+
+			fn $method(self, rhs: Self) -> Self {
+				U256::from(self).$method(U256::from(rhs)).into()
+			}
+		}
+
+		impl $op_assign for U256Balance {
+			fn $method_assign(&mut self, rhs: Self) {
+				*self = $op::$method(*self, rhs);
+			}
+		}
+
+		impl $checked for U256Balance {
+			fn $checked_method(&self, rhs: &Self) -> Option<Self> {
+				U256::from(*self).$checked_method(U256::from(*rhs)).map(Into::into)
+			}
+		}
+	)*};
+}
+
+impl_ops! {
+	Add, add, AddAssign, add_assign, CheckedAdd, checked_add;
+	Sub, sub, SubAssign, sub_assign, CheckedSub, checked_sub;
+	Mul, mul, MulAssign, mul_assign, CheckedMul, checked_mul;
+	Div, div, DivAssign, div_assign, CheckedDiv, checked_div;
+	Rem, rem, RemAssign, rem_assign, CheckedRem, checked_rem;
+}
+
+impl Shl<u32> for U256Balance {
+	type Output = Self;
+
+	fn shl(self, rhs: u32) -> Self {
+		(U256::from(self) << rhs).into()
+	}
+}
+
+impl Shr<u32> for U256Balance {
+	type Output = Self;
+
+	fn shr(self, rhs: u32) -> Self {
+		(U256::from(self) >> rhs).into()
+	}
+}
+
+impl CheckedShl for U256Balance {
+	fn checked_shl(&self, rhs: u32) -> Option<Self> {
+		(rhs < 256).then(|| *self << rhs)
+	}
+}
+
+impl CheckedShr for U256Balance {
+	fn checked_shr(&self, rhs: u32) -> Option<Self> {
+		(rhs < 256).then(|| *self >> rhs)
+	}
+}
+
+impl num_traits::Saturating for U256Balance {
+	fn saturating_add(self, rhs: Self) -> Self {
+		U256::from(self).saturating_add(U256::from(rhs)).into()
+	}
+
+	fn saturating_sub(self, rhs: Self) -> Self {
+		U256::from(self).saturating_sub(U256::from(rhs)).into()
+	}
+}
+
+impl Zero for U256Balance {
+	fn zero() -> Self {
+		U256Balance::default()
+	}
+
+	fn is_zero(&self) -> bool {
+		self.low == 0 && self.high == 0
+	}
+}
+
+impl One for U256Balance {
+	fn one() -> Self {
+		1u8.into()
+	}
+}
+
+impl Bounded for U256Balance {
+	fn min_value() -> Self {
+		Zero::zero()
+	}
+
+	fn max_value() -> Self {
+		U256Balance::MAX
+	}
+}
+
+impl num_traits::Num for U256Balance {
+	type FromStrRadixErr = &'static str;
+
+	fn from_str_radix(s: &str, radix: u32) -> Result<Self, &'static str> {
+		U256::from_str_radix(s, radix).map(Into::into).map_err(|_| "invalid number")
+	}
+}
+
+impl Unsigned for U256Balance {}
+
+impl IntegerSquareRoot for U256Balance {
+	fn integer_sqrt_checked(&self) -> Option<Self> {
+		Some(U256::from(*self).integer_sqrt().into())
+	}
+}
+
+/// The compact form of the balances that do not have one of their own.
+const COMPACT_OVERFLOW: u128 = u128::MAX;
+
+impl CompactAs for U256Balance {
+	type As = u128;
+
+	fn encode_as(&self) -> &u128 {
+		if self.high == 0 && self.low != COMPACT_OVERFLOW {
+			&self.low
+		} else {
+			&COMPACT_OVERFLOW
+		}
+	}
+
+	fn decode_from(low: u128) -> Result<Self, codec::Error> {
+		if low == COMPACT_OVERFLOW {
+			return Err("U256Balance too large for its compact form".into())
+		}
+		Ok(low.into())
+	}
+}
+
+impl From<Compact<U256Balance>> for U256Balance {
+	fn from(x: Compact<U256Balance>) -> Self {
+		x.0
+	}
+}
+
+impl FromStr for U256Balance {
+	type Err = &'static str;
+
+	/// Parse a decimal number.
+	fn from_str(s: &str) -> Result<Self, &'static str> {
+		U256::from_dec_str(s)
+			.map(Into::into)
+			.map_err(|_| "invalid or too large decimal number")
+	}
+}
+
+impl fmt::Display for U256Balance {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&U256::from(*self), f)
+	}
+}
+
+impl fmt::Debug for U256Balance {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(self, f)
+	}
+}
+
+#[cfg(feature = "std")]
+impl serde::Serialize for U256Balance {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<'de> serde::Deserialize<'de> for U256Balance {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct Visitor;
+
+		impl<'de> serde::de::Visitor<'de> for Visitor {
+			type Value = U256Balance;
+
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("a decimal string or a non-negative integer")
+			}
+
+			fn visit_u64<E: serde::de::Error>(self, x: u64) -> Result<U256Balance, E> {
+				Ok(x.into())
+			}
+
+			fn visit_u128<E: serde::de::Error>(self, x: u128) -> Result<U256Balance, E> {
+				Ok(x.into())
+			}
+
+			fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<U256Balance, E> {
+				s.parse().map_err(E::custom)
+			}
+		}
+
+		deserializer.deserialize_any(Visitor)
+	}
+}
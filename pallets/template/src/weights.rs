@@ -1,6 +1,13 @@
-//! Autogenerated weights for pallet_assets
+//! Weights for pallet_assets
+//!
+//! The weights of the functions up to `force_cancel_approval` were generated for upstream
+//! `pallet_assets` by the run below, with the writes of the approval indexes added by hand.
+//!
+//! THE WEIGHTS OF ALL LATER FUNCTIONS ARE PLACEHOLDERS, estimated by hand from their storage
+//! accesses and from comparable generated weights. Replace this file by running `benchmarking.rs`
+//! on reference hardware with the command below, with `--pallet` and `--output` pointing at this
+//! pallet, before relying on any of them.
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
 //! DATE: 2021-08-07, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
 
@@ -30,7 +37,6 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn create() -> Weight;
 	fn force_create() -> Weight;
-	fn destroy(c: u32, s: u32, a: u32, ) -> Weight;
 	fn mint() -> Weight;
 	fn burn() -> Weight;
 	fn transfer() -> Weight;
@@ -51,6 +57,29 @@ pub trait WeightInfo {
 	fn transfer_approved() -> Weight;
 	fn cancel_approval() -> Weight;
 	fn force_cancel_approval() -> Weight;
+	fn create_pool() -> Weight;
+	fn add_liquidity() -> Weight;
+	fn remove_liquidity() -> Weight;
+	fn swap_exact_in() -> Weight;
+	fn swap_exact_out() -> Weight;
+	fn start_destroy() -> Weight;
+	fn destroy_accounts(c: u32, ) -> Weight;
+	fn destroy_approvals(a: u32, ) -> Weight;
+	fn finish_destroy() -> Weight;
+	fn touch() -> Weight;
+	fn refund() -> Weight;
+	fn create_auto() -> Weight;
+	fn set_extended_metadata(n: u32, ) -> Weight;
+	fn clear_extended_metadata() -> Weight;
+	fn force_set_extended_metadata(n: u32, ) -> Weight;
+	fn force_clear_extended_metadata() -> Weight;
+	fn create_airdrop() -> Weight;
+	fn claim_airdrop(p: u32, ) -> Weight;
+	fn reclaim_airdrop(w: u32, ) -> Weight;
+	fn freeze_amount() -> Weight;
+	fn thaw_amount() -> Weight;
+	fn approve_unlimited() -> Weight;
+	fn transfer_approved_unlimited() -> Weight;
 }
 
 /// Weights for pallet_assets using the Substrate node and recommended hardware.
@@ -69,28 +98,6 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
 	// Storage: Assets Asset (r:1 w:1)
-	// Storage: Assets Account (r:5002 w:5001)
-	// Storage: System Account (r:5000 w:5000)
-	// Storage: Assets Metadata (r:1 w:0)
-	// Storage: Assets Approvals (r:501 w:500)
-	fn destroy(c: u32, s: u32, a: u32, ) -> Weight {
-		(0 as Weight)
-			// Standard Error: 32_000
-			.saturating_add((21_163_000 as Weight).saturating_mul(c as Weight))
-			// Standard Error: 32_000
-			.saturating_add((26_932_000 as Weight).saturating_mul(s as Weight))
-			// Standard Error: 329_000
-			.saturating_add((29_714_000 as Weight).saturating_mul(a as Weight))
-			.saturating_add(T::DbWeight::get().reads(5 as Weight))
-			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
-			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(s as Weight)))
-			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(a as Weight)))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
-			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(s as Weight)))
-			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(a as Weight)))
-	}
-	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:1 w:1)
 	fn mint() -> Weight {
 		(47_913_000 as Weight)
@@ -209,34 +216,240 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	}
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
 	fn approve_transfer() -> Weight {
 		(56_998_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
 	}
 	// Storage: Assets Approvals (r:1 w:1)
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
 	fn transfer_approved() -> Weight {
 		(107_171_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(5 as Weight))
-			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
 	}
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
 	fn cancel_approval() -> Weight {
 		(57_358_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
 	}
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
 	fn force_cancel_approval() -> Weight {
 		(58_330_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Assets Pools (r:1 w:1)
+	// Storage: Assets Asset (r:3 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn create_pool() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Assets Pools (r:1 w:0)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn add_liquidity() -> Weight {
+		(131_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	// Storage: Assets Pools (r:1 w:0)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn remove_liquidity() -> Weight {
+		(126_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	// Storage: Assets Pools (r:1 w:0)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	fn swap_exact_in() -> Weight {
+		(104_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// Storage: Assets Pools (r:1 w:0)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	fn swap_exact_out() -> Weight {
+		(105_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	fn start_destroy() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn destroy_accounts(c: u32, ) -> Weight {
+		(16_000_000 as Weight)
+			.saturating_add((21_500_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
+	fn destroy_approvals(a: u32, ) -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add((27_800_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(a as Weight)))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Metadata (r:1 w:1)
+	// Storage: Assets ExtendedMetadata (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn finish_destroy() -> Weight {
+		(31_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn touch() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn refund() -> Weight {
+		(41_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Assets NextAssetId (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	fn create_auto() -> Weight {
+		(44_000_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(2 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets ExtendedMetadata (r:1 w:1)
+	fn set_extended_metadata(n: u32, ) -> Weight {
+		(50_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets ExtendedMetadata (r:1 w:1)
+	fn clear_extended_metadata() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets ExtendedMetadata (r:1 w:1)
+	fn force_set_extended_metadata(n: u32, ) -> Weight {
+		(27_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets ExtendedMetadata (r:1 w:1)
+	fn force_clear_extended_metadata() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets NextAirdropId (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Assets Airdrops (r:0 w:1)
+	fn create_airdrop() -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// Storage: Assets Airdrops (r:1 w:1)
+	// Storage: Assets AirdropClaims (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn claim_airdrop(p: u32, ) -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// Storage: Assets Airdrops (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Assets AirdropClaims (r:0 w:1)
+	fn reclaim_airdrop(w: u32, ) -> Weight {
+		(90_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(w as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets Account (r:1 w:0)
+	// Storage: Assets Freezes (r:1 w:1)
+	fn freeze_amount() -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets Freezes (r:1 w:1)
+	fn thaw_amount() -> Weight {
+		(34_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
+	fn approve_unlimited() -> Weight {
+		(56_998_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Assets Approvals (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn transfer_approved_unlimited() -> Weight {
+		(98_407_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
@@ -254,28 +467,6 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
 	// Storage: Assets Asset (r:1 w:1)
-	// Storage: Assets Account (r:5002 w:5001)
-	// Storage: System Account (r:5000 w:5000)
-	// Storage: Assets Metadata (r:1 w:0)
-	// Storage: Assets Approvals (r:501 w:500)
-	fn destroy(c: u32, s: u32, a: u32, ) -> Weight {
-		(0 as Weight)
-			// Standard Error: 32_000
-			.saturating_add((21_163_000 as Weight).saturating_mul(c as Weight))
-			// Standard Error: 32_000
-			.saturating_add((26_932_000 as Weight).saturating_mul(s as Weight))
-			// Standard Error: 329_000
-			.saturating_add((29_714_000 as Weight).saturating_mul(a as Weight))
-			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
-			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
-			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(s as Weight)))
-			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(a as Weight)))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
-			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
-			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(s as Weight)))
-			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(a as Weight)))
-	}
-	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:1 w:1)
 	fn mint() -> Weight {
 		(47_913_000 as Weight)
@@ -394,32 +585,238 @@ impl WeightInfo for () {
 	}
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
 	fn approve_transfer() -> Weight {
 		(56_998_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
 	}
 	// Storage: Assets Approvals (r:1 w:1)
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
 	fn transfer_approved() -> Weight {
 		(107_171_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
 	}
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
 	fn cancel_approval() -> Weight {
 		(57_358_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
 	}
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
 	fn force_cancel_approval() -> Weight {
 		(58_330_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Assets Pools (r:1 w:1)
+	// Storage: Assets Asset (r:3 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn create_pool() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Assets Pools (r:1 w:0)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn add_liquidity() -> Weight {
+		(131_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+	// Storage: Assets Pools (r:1 w:0)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn remove_liquidity() -> Weight {
+		(126_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+	// Storage: Assets Pools (r:1 w:0)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	fn swap_exact_in() -> Weight {
+		(104_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	// Storage: Assets Pools (r:1 w:0)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	fn swap_exact_out() -> Weight {
+		(105_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	fn start_destroy() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn destroy_accounts(c: u32, ) -> Weight {
+		(16_000_000 as Weight)
+			.saturating_add((21_500_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
+	fn destroy_approvals(a: u32, ) -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add((27_800_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(a as Weight)))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Metadata (r:1 w:1)
+	// Storage: Assets ExtendedMetadata (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn finish_destroy() -> Weight {
+		(31_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn touch() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn refund() -> Weight {
+		(41_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Assets NextAssetId (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	fn create_auto() -> Weight {
+		(44_000_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets ExtendedMetadata (r:1 w:1)
+	fn set_extended_metadata(n: u32, ) -> Weight {
+		(50_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets ExtendedMetadata (r:1 w:1)
+	fn clear_extended_metadata() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets ExtendedMetadata (r:1 w:1)
+	fn force_set_extended_metadata(n: u32, ) -> Weight {
+		(27_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets ExtendedMetadata (r:1 w:1)
+	fn force_clear_extended_metadata() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets NextAirdropId (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Assets Airdrops (r:0 w:1)
+	fn create_airdrop() -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	// Storage: Assets Airdrops (r:1 w:1)
+	// Storage: Assets AirdropClaims (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn claim_airdrop(p: u32, ) -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	// Storage: Assets Airdrops (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Assets AirdropClaims (r:0 w:1)
+	fn reclaim_airdrop(w: u32, ) -> Weight {
+		(90_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(w as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets Account (r:1 w:0)
+	// Storage: Assets Freezes (r:1 w:1)
+	fn freeze_amount() -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets Freezes (r:1 w:1)
+	fn thaw_amount() -> Weight {
+		(34_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: Assets OwnerApprovals (r:0 w:1)
+	// Storage: Assets DelegateApprovals (r:0 w:1)
+	fn approve_unlimited() -> Weight {
+		(56_998_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Assets Approvals (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn transfer_approved_unlimited() -> Weight {
+		(98_407_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
 }
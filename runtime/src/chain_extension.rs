@@ -0,0 +1,300 @@
+//! The chain extension that gives contracts the ERC20 assets of `pallet_template`, read and moved
+//! as native assets with the contract's account as origin.
+//!
+//! A function id is the registry in its second byte, `0x00` for the community assets and `0x01`
+//! for the stable ones, and the function in its first byte:
+//!
+//! | id       | function            | input                                      | output    |
+//! |----------|---------------------|--------------------------------------------|-----------|
+//! | `0x__01` | `balance`           | `(AssetId, AccountId)`                     | `Balance` |
+//! | `0x__02` | `total_supply`      | `AssetId`                                  | `Balance` |
+//! | `0x__03` | `allowance`         | `(AssetId, AccountId, AccountId)`          | `Balance` |
+//! | `0x__04` | `transfer`          | `(AssetId, AccountId, Balance)`            |           |
+//! | `0x__05` | `approve_transfer`  | `(AssetId, AccountId, Balance)`            |           |
+//! | `0x__06` | `transfer_approved` | `(AssetId, AccountId, AccountId, Balance)` |           |
+//!
+//! `Balance` is the balance of the registry: a `U256Balance`, encoded as its low and then its high
+//! 128 bits, for the community assets and a `u128` for the stable ones. The last three functions
+//! dispatch the calls of the same name, and return a status code: `0` on success, `1 + index` when
+//! the call fails with the error `index` of the registry's pallet, and `u32::MAX` when it fails
+//! otherwise. The extension is declared to ink! contracts as:
+//!
+//! ```ignore
+//! /// The community asset balance: its low and then its high 128 bits.
+//! pub type AssetBalance = [u128; 2];
+//!
+//! #[ink::chain_extension]
+//! pub trait Erc20Extension {
+//!     type ErrorCode = Erc20Error;
+//!
+//!     #[ink(extension = 0x0001, returns_result = false, handle_status = false)]
+//!     fn balance(asset: u32, who: AccountId) -> AssetBalance;
+//!
+//!     #[ink(extension = 0x0002, returns_result = false, handle_status = false)]
+//!     fn total_supply(asset: u32) -> AssetBalance;
+//!
+//!     #[ink(extension = 0x0003, returns_result = false, handle_status = false)]
+//!     fn allowance(asset: u32, owner: AccountId, delegate: AccountId) -> AssetBalance;
+//!
+//!     #[ink(extension = 0x0004, returns_result = false)]
+//!     fn transfer(asset: u32, to: AccountId, amount: AssetBalance);
+//!
+//!     #[ink(extension = 0x0005, returns_result = false)]
+//!     fn approve_transfer(asset: u32, delegate: AccountId, amount: AssetBalance);
+//!
+//!     #[ink(extension = 0x0006, returns_result = false)]
+//!     fn transfer_approved(asset: u32, owner: AccountId, to: AccountId, amount: AssetBalance);
+//! }
+//!
+//! #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+//! #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+//! pub enum Erc20Error {
+//!     /// The error of `pallet_template` with this index.
+//!     Pallet(u8),
+//!     /// Any other error, such as too low a native balance for a deposit.
+//!     Other,
+//! }
+//!
+//! impl ink_env::chain_extension::FromStatusCode for Erc20Error {
+//!     fn from_status_code(status_code: u32) -> Result<(), Self> {
+//!         match status_code {
+//!             0 => Ok(()),
+//!             u32::MAX => Err(Erc20Error::Other),
+//!             code => Err(Erc20Error::Pallet((code - 1) as u8)),
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The stable assets are declared the same way, with ids `0x0101` to `0x0106` and `u128` balances.
+
+use crate::{AccountId, AssetId, AssetRegistry, Runtime, StableInstance};
+use codec::Encode;
+use frame_support::{
+	traits::{Get, PalletInfo},
+	weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RetVal, SysConfig, UncheckedFrom,
+};
+use pallet_template::weights::WeightInfo;
+use sp_runtime::{DispatchError, DispatchResult, MultiAddress};
+
+/// The status code of a call that failed with an error of another pallet.
+const OTHER_ERROR: u32 = u32::MAX;
+
+/// The ERC20 assets chain extension.
+pub struct Erc20Extension;
+
+impl ChainExtension<Runtime> for Erc20Extension {
+	fn call<E>(func_id: u32, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
+	where
+		E: Ext<T = Runtime>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		match Function::from_id(func_id) {
+			Some((AssetRegistry::Community, func)) => call::<E, ()>(func, env),
+			Some((AssetRegistry::Stable, func)) => call::<E, StableInstance>(func, env),
+			None => Err(DispatchError::Other("unknown chain extension function")),
+		}
+	}
+}
+
+/// A function of the extension, the first byte of its id.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Function {
+	Balance,
+	TotalSupply,
+	Allowance,
+	Transfer,
+	ApproveTransfer,
+	TransferApproved,
+}
+
+impl Function {
+	/// The registry and function `func_id` names, if any.
+	fn from_id(func_id: u32) -> Option<(AssetRegistry, Self)> {
+		let registry = match func_id >> 8 {
+			0x00 => AssetRegistry::Community,
+			0x01 => AssetRegistry::Stable,
+			_ => return None,
+		};
+		let func = match func_id & 0xff {
+			0x01 => Function::Balance,
+			0x02 => Function::TotalSupply,
+			0x03 => Function::Allowance,
+			0x04 => Function::Transfer,
+			0x05 => Function::ApproveTransfer,
+			0x06 => Function::TransferApproved,
+			_ => return None,
+		};
+		Some((registry, func))
+	}
+
+	/// The weight charged for the function on the assets of instance `I`.
+	fn weight<I: 'static>(self) -> Weight
+	where
+		Runtime: pallet_template::Config<I>,
+	{
+		match self {
+			Function::Balance | Function::TotalSupply | Function::Allowance =>
+				<Runtime as frame_system::Config>::DbWeight::get().reads(1),
+			Function::Transfer => Weights::<I>::transfer(),
+			Function::ApproveTransfer => Weights::<I>::approve_transfer(),
+			Function::TransferApproved => Weights::<I>::transfer_approved(),
+		}
+	}
+}
+
+type Assets<I> = pallet_template::Pallet<Runtime, I>;
+type Balance<I> = <Runtime as pallet_template::Config<I>>::Balance;
+type Weights<I> = <Runtime as pallet_template::Config<I>>::WeightInfo;
+
+/// Run function `func` on the assets of instance `I`.
+fn call<E, I: 'static>(
+	func: Function,
+	env: Environment<E, InitState>,
+) -> Result<RetVal, DispatchError>
+where
+	E: Ext<T = Runtime>,
+	Runtime: pallet_template::Config<I, AssetId = AssetId>,
+{
+	let mut env = env.buf_in_buf_out();
+	let origin = frame_system::RawOrigin::Signed(env.ext().address().clone()).into();
+
+	let status = match func {
+		Function::Balance => {
+			let (id, who): (AssetId, AccountId) = env.read_as()?;
+			env.charge_weight(func.weight::<I>())?;
+			env.write(&Assets::<I>::balance(id, who).encode(), false, None)?;
+			0
+		},
+		Function::TotalSupply => {
+			let id: AssetId = env.read_as()?;
+			env.charge_weight(func.weight::<I>())?;
+			env.write(&Assets::<I>::total_supply(id).encode(), false, None)?;
+			0
+		},
+		Function::Allowance => {
+			let (id, owner, delegate): (AssetId, AccountId, AccountId) = env.read_as()?;
+			env.charge_weight(func.weight::<I>())?;
+			env.write(&Assets::<I>::allowance(id, &owner, &delegate).encode(), false, None)?;
+			0
+		},
+		Function::Transfer => {
+			let (id, to, amount): (AssetId, AccountId, Balance<I>) = env.read_as()?;
+			env.charge_weight(func.weight::<I>())?;
+			status_code::<I>(Assets::<I>::transfer(origin, id, MultiAddress::Id(to), amount))
+		},
+		Function::ApproveTransfer => {
+			let (id, delegate, amount): (AssetId, AccountId, Balance<I>) = env.read_as()?;
+			env.charge_weight(func.weight::<I>())?;
+			status_code::<I>(Assets::<I>::approve_transfer(
+				origin,
+				id,
+				MultiAddress::Id(delegate),
+				amount,
+			))
+		},
+		Function::TransferApproved => {
+			let (id, owner, to, amount): (AssetId, AccountId, AccountId, Balance<I>) =
+				env.read_as()?;
+			env.charge_weight(func.weight::<I>())?;
+			status_code::<I>(
+				Assets::<I>::transfer_approved(
+					origin,
+					id,
+					MultiAddress::Id(owner),
+					MultiAddress::Id(to),
+					amount,
+				)
+				.map(|_| ())
+				.map_err(|e| e.error),
+			)
+		},
+	};
+	Ok(RetVal::Converging(status))
+}
+
+/// The status code the contract sees for the result of a call to instance `I`.
+fn status_code<I: 'static>(result: DispatchResult) -> u32
+where
+	Runtime: pallet_template::Config<I>,
+{
+	let index = <Runtime as frame_system::Config>::PalletInfo::index::<Assets<I>>();
+	match result {
+		Ok(()) => 0,
+		Err(DispatchError::Module { index: module, error, .. })
+			if Some(module as usize) == index =>
+			1 + error as u32,
+		Err(_) => OTHER_ERROR,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::TemplateModule;
+	use pallet_template::Error;
+
+	fn read() -> Weight {
+		<Runtime as frame_system::Config>::DbWeight::get().reads(1)
+	}
+
+	#[test]
+	fn function_ids_name_a_registry_and_a_function() {
+		assert_eq!(Function::from_id(0x0001), Some((AssetRegistry::Community, Function::Balance)));
+		assert_eq!(
+			Function::from_id(0x0004),
+			Some((AssetRegistry::Community, Function::Transfer))
+		);
+		assert_eq!(
+			Function::from_id(0x0102),
+			Some((AssetRegistry::Stable, Function::TotalSupply))
+		);
+		assert_eq!(
+			Function::from_id(0x0106),
+			Some((AssetRegistry::Stable, Function::TransferApproved))
+		);
+		assert_eq!(Function::from_id(0x0000), None);
+		assert_eq!(Function::from_id(0x0007), None);
+		assert_eq!(Function::from_id(0x0201), None);
+		assert_eq!(Function::from_id(0x1_0001), None);
+	}
+
+	#[test]
+	fn status_codes_are_the_error_index_of_the_registry_pallet() {
+		assert_eq!(status_code::<()>(Ok(())), 0);
+		assert_eq!(status_code::<()>(Err(Error::<Runtime>::BalanceLow.into())), 1);
+		assert_eq!(status_code::<()>(Err(Error::<Runtime>::Unknown.into())), 4);
+		assert_eq!(
+			status_code::<StableInstance>(Err(Error::<Runtime, StableInstance>::Unknown.into())),
+			4
+		);
+		// An error of the other registry is not one of the pallet's.
+		let community: DispatchError = Error::<Runtime>::Unknown.into();
+		assert_eq!(status_code::<StableInstance>(Err(community)), OTHER_ERROR);
+		assert_eq!(status_code::<()>(Err(DispatchError::BadOrigin)), OTHER_ERROR);
+		let balances = pallet_balances::Error::<Runtime>::InsufficientBalance;
+		assert_eq!(status_code::<()>(Err(balances.into())), OTHER_ERROR);
+		assert_ne!(
+			<Runtime as frame_system::Config>::PalletInfo::index::<TemplateModule>(),
+			<Runtime as frame_system::Config>::PalletInfo::index::<Assets<StableInstance>>()
+		);
+	}
+
+	#[test]
+	fn functions_charge_a_read_or_the_weight_of_their_call() {
+		for func in [Function::Balance, Function::TotalSupply, Function::Allowance] {
+			assert_eq!(func.weight::<()>(), read());
+			assert_eq!(func.weight::<StableInstance>(), read());
+		}
+		assert_eq!(Function::Transfer.weight::<()>(), Weights::<()>::transfer());
+		assert_eq!(
+			Function::ApproveTransfer.weight::<StableInstance>(),
+			Weights::<StableInstance>::approve_transfer()
+		);
+		assert_eq!(Function::TransferApproved.weight::<()>(), Weights::<()>::transfer_approved());
+		assert!(Function::Transfer.weight::<()>() > read());
+	}
+}
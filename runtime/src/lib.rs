@@ -6,19 +6,26 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use codec::{Decode, Encode};
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
+use sp_core::{
+	crypto::KeyTypeId,
+	u32_trait::{_1, _2, _3},
+	OpaqueMetadata, U256,
+};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, Verify},
-	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
+	ApplyExtrinsicResult, DispatchError, MultiSignature, RuntimeDebug,
 };
-use sp_std::prelude::*;
+use sp_std::{convert::TryInto, prelude::*};
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
@@ -31,9 +38,10 @@ pub use frame_support::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use pallet_balances::Call as BalancesCall;
+use pallet_evm::{EnsureAddressTruncated, HashedAddressMapping};
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::CurrencyAdapter;
 #[cfg(any(feature = "std", test))]
@@ -45,6 +53,9 @@ use frame_system::{EnsureOneOf, EnsureRoot};
 /// Import the template pallet.
 pub use pallet_template;
 
+mod chain_extension;
+pub mod precompiles;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -58,6 +69,36 @@ pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::Account
 /// Balance of an account.
 pub type Balance = u128;
 
+/// Identifier of an asset of `pallet_template`.
+pub type AssetId = u32;
+
+/// Balance of an account in an asset of `pallet_template`. It spans the `uint256` of ERC20, and is
+/// the widest balance the runtime APIs report in.
+pub type AssetBalance = pallet_template::U256Balance;
+
+/// Balance of an account in an asset of the `StableAssets` instance of `pallet_template`.
+pub type StableBalance = u128;
+
+/// The instance of `pallet_template` registering regulated stable tokens.
+pub type StableInstance = pallet_template::Instance2;
+
+/// The registries of ERC20 assets, one for each instance of `pallet_template`.
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum AssetRegistry {
+	/// Permissionless community tokens, in `TemplateModule`.
+	Community,
+	/// Regulated stable tokens, in `StableAssets`.
+	Stable,
+}
+
+impl Default for AssetRegistry {
+	fn default() -> Self {
+		AssetRegistry::Community
+	}
+}
+
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
@@ -100,10 +141,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 110,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 5,
 };
 
 /// This determines the average expected block time that we are targeting.
@@ -122,6 +163,11 @@ pub const MILLICENTS: Balance = 1_000_000_000;
 pub const DOLLARS: Balance = 100 * CENTS;
 pub const CENTS: Balance = 1_000 * MILLICENTS; // assume this is worth about a cent.
 
+/// The ticker symbol of the native token, as shown by wallets.
+pub const TOKEN_SYMBOL: &str = "UNIT";
+/// The decimals of the native token: one token is `DOLLARS`.
+pub const TOKEN_DECIMALS: u8 = 14;
+
 // Time is measured by number of blocks.
 pub const MINUTES: BlockNumber = 60_000 / (MILLISECS_PER_BLOCK as BlockNumber);
 pub const HOURS: BlockNumber = MINUTES * 60;
@@ -272,29 +318,197 @@ impl pallet_sudo::Config for Runtime {
 	type Call = Call;
 }
 
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 3 * DAYS;
+	pub const CouncilMaxProposals: u32 = 100;
+	pub const CouncilMaxMembers: u32 = 100;
+}
+
+/// The token council, which governs the privileged operations of the asset registries by motion.
+pub type CouncilCollective = pallet_collective::Instance1;
+
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = CouncilMotionDuration;
+	type MaxProposals = CouncilMaxProposals;
+	type MaxMembers = CouncilMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+/// Root, or a motion passed by two thirds of the token council.
+pub type EnsureRootOrTwoThirdsCouncil = EnsureOneOf<
+	AccountId,
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>,
+>;
+
+/// Root, or a motion passed by the whole token council. Regulated stable tokens take unanimity
+/// for what two thirds may do to community tokens.
+pub type EnsureRootOrAllCouncil = EnsureOneOf<
+	AccountId,
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>,
+>;
+
+parameter_types! {
+	pub TombstoneDeposit: Balance = 10 * DOLLARS;
+	pub DepositPerContract: Balance = TombstoneDeposit::get();
+	pub const DepositPerStorageByte: Balance = 1 * MILLICENTS;
+	pub const DepositPerStorageItem: Balance = 1 * CENTS;
+	pub RentFraction: Perbill = Perbill::from_rational(1u32, 30 * DAYS);
+	pub const SurchargeReward: Balance = 150 * MILLICENTS;
+	pub const SignedClaimHandicap: u32 = 2;
+	// The lazy deletion of contracts runs in `on_initialize`, within a tenth of the block.
+	pub DeletionWeightLimit: Weight = Perbill::from_percent(10) * BlockWeights::get().max_block;
+	pub const DeletionQueueDepth: u32 = 128;
+	pub Schedule: pallet_contracts::Schedule<Runtime> = Default::default();
+}
+
+/// Smart contracts, which reach the ERC20 assets through `chain_extension::Erc20Extension`.
+impl pallet_contracts::Config for Runtime {
+	type Time = Timestamp;
+	type Randomness = RandomnessCollectiveFlip;
+	type Currency = Balances;
+	type Event = Event;
+	type RentPayment = ();
+	type SignedClaimHandicap = SignedClaimHandicap;
+	type TombstoneDeposit = TombstoneDeposit;
+	type DepositPerContract = DepositPerContract;
+	type DepositPerStorageByte = DepositPerStorageByte;
+	type DepositPerStorageItem = DepositPerStorageItem;
+	type RentFraction = RentFraction;
+	type SurchargeReward = SurchargeReward;
+	type CallStack = [pallet_contracts::Frame<Self>; 31];
+	type WeightPrice = pallet_transaction_payment::Pallet<Self>;
+	type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
+	type ChainExtension = chain_extension::Erc20Extension;
+	type DeletionQueueDepth = DeletionQueueDepth;
+	type DeletionWeightLimit = DeletionWeightLimit;
+	type Schedule = Schedule;
+}
+
+parameter_types! {
+	pub const ChainId: u64 = 42;
+	pub BlockGasLimit: U256 = U256::from(
+		NORMAL_DISPATCH_RATIO * BlockWeights::get().max_block / precompiles::WEIGHT_PER_GAS,
+	);
+}
+
+/// The EVM, whose precompiles include an ERC20 contract for every asset.
+impl pallet_evm::Config for Runtime {
+	// EVM calls come in signed extrinsics, which pay for the weight of their gas limit.
+	type FeeCalculator = ();
+	type GasWeightMapping = precompiles::GasWeight;
+	type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+	type CallOrigin = EnsureAddressTruncated;
+	type WithdrawOrigin = EnsureAddressTruncated;
+	type AddressMapping = HashedAddressMapping<BlakeTwo256>;
+	type Currency = Balances;
+	type Event = Event;
+	type Runner = pallet_evm::runner::stack::Runner<Self>;
+	type Precompiles = precompiles::Precompiles;
+	type ChainId = ChainId;
+	type BlockGasLimit = BlockGasLimit;
+	type OnChargeTransaction = ();
+	type FindAuthor = ();
+}
+
 // Первичная указание данных для pallet_template
 parameter_types! {
 	pub const AssetDeposit: Balance = 100 * DOLLARS;
 	pub const ApprovalDeposit: Balance = 1 * DOLLARS;
+	pub const AssetAccountDeposit: Balance = 1 * DOLLARS;
 	pub const StringLimit: u32 = 50;
+	pub const DescriptionLimit: u32 = 512;
+	pub const UrlLimit: u32 = 128;
+	pub const ReservedAssetIds: AssetId = 1_000;
 	pub const MetadataDepositBase: Balance = 10 * DOLLARS;
 	pub const MetadataDepositPerByte: Balance = 1 * DOLLARS;
+	pub const TemplatePalletId: PalletId = PalletId(*b"py/erc20");
+	pub const PoolFee: Permill = Permill::from_perthousand(3);
+	pub const TopHolders: u32 = 100;
+	pub const TopHoldersInterval: BlockNumber = 10 * MINUTES;
+	pub const AirdropDeposit: Balance = 10 * DOLLARS;
+	pub const AirdropUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+	pub const MaxFreezes: u32 = 16;
+	pub const CommunityRegistry: AssetRegistry = AssetRegistry::Community;
 }
 
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type Event = Event;
-	type Balance = u64;
-	type AssetId = u32;
+	type Balance = AssetBalance;
+	type AssetId = AssetId;
 	type Currency = Balances;
-	type ForceOrigin = EnsureRoot<AccountId>;
+	type ForceOrigin = EnsureRootOrTwoThirdsCouncil;
 	type AssetDeposit = AssetDeposit;
 	type MetadataDepositBase = MetadataDepositBase;
 	type MetadataDepositPerByte = MetadataDepositPerByte;
 	type ApprovalDeposit = ApprovalDeposit;
+	type AssetAccountDeposit = AssetAccountDeposit;
 	type StringLimit = StringLimit;
+	type DescriptionLimit = DescriptionLimit;
+	type UrlLimit = UrlLimit;
+	type ReservedAssetIds = ReservedAssetIds;
 	type Freezer = ();
+	type CallbackHandle = precompiles::PrecompileCode<CommunityRegistry>;
 	type Extra = ();
+	type PalletId = TemplatePalletId;
+	type PoolFee = PoolFee;
+	type TopHolders = TopHolders;
+	type TopHoldersInterval = TopHoldersInterval;
+	type AirdropDeposit = AirdropDeposit;
+	type AirdropUnsignedPriority = AirdropUnsignedPriority;
+	type MaxFreezes = MaxFreezes;
+	type AddressMapping = precompiles::AddressToAccount;
+	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const StableAssetDeposit: Balance = 1_000 * DOLLARS;
+	pub const StableApprovalDeposit: Balance = 1 * DOLLARS;
+	pub const StableAssetAccountDeposit: Balance = 1 * DOLLARS;
+	pub const StableStringLimit: u32 = 32;
+	pub const StableDescriptionLimit: u32 = 1_024;
+	pub const StableUrlLimit: u32 = 256;
+	pub const StableReservedAssetIds: AssetId = 1_000;
+	pub const StableMetadataDepositBase: Balance = 100 * DOLLARS;
+	pub const StableMetadataDepositPerByte: Balance = 1 * DOLLARS;
+	pub const StablePalletId: PalletId = PalletId(*b"py/stabl");
+	pub const StableAirdropDeposit: Balance = 100 * DOLLARS;
+	pub const StableRegistry: AssetRegistry = AssetRegistry::Stable;
+}
+
+/// Configure the second instance of pallet-template, for regulated stable tokens.
+impl pallet_template::Config<StableInstance> for Runtime {
+	type Event = Event;
+	type Balance = StableBalance;
+	type AssetId = AssetId;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRootOrAllCouncil;
+	type AssetDeposit = StableAssetDeposit;
+	type MetadataDepositBase = StableMetadataDepositBase;
+	type MetadataDepositPerByte = StableMetadataDepositPerByte;
+	type ApprovalDeposit = StableApprovalDeposit;
+	type AssetAccountDeposit = StableAssetAccountDeposit;
+	type StringLimit = StableStringLimit;
+	type DescriptionLimit = StableDescriptionLimit;
+	type UrlLimit = StableUrlLimit;
+	type ReservedAssetIds = StableReservedAssetIds;
+	type Freezer = ();
+	type CallbackHandle = precompiles::PrecompileCode<StableRegistry>;
+	type Extra = ();
+	type PalletId = StablePalletId;
+	type PoolFee = PoolFee;
+	type TopHolders = TopHolders;
+	type TopHoldersInterval = TopHoldersInterval;
+	type AirdropDeposit = StableAirdropDeposit;
+	type AirdropUnsignedPriority = AirdropUnsignedPriority;
+	type MaxFreezes = MaxFreezes;
+	type AddressMapping = precompiles::AddressToAccount;
 	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
 }
 
@@ -314,7 +528,11 @@ construct_runtime!(
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
 		// Include the custom logic from the pallet-template in the runtime.
-		TemplateModule: pallet_template::{Pallet, Call, Storage, Event<T>},
+		TemplateModule: pallet_template::{Pallet, Call, Storage, Config<T>, Event<T>, ValidateUnsigned},
+		StableAssets: pallet_template::<Instance2>::{Pallet, Call, Storage, Config<T>, Event<T>, ValidateUnsigned},
+		Council: pallet_collective::<Instance1>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>},
+		Contracts: pallet_contracts::{Pallet, Call, Storage, Event<T>},
+		EVM: pallet_evm::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -336,6 +554,17 @@ pub type SignedExtra = (
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
+/// Storage migrations run on runtime upgrade, oldest first.
+pub type Migrations = (
+	pallet_template::migration::v2::MigrateToV2<Runtime, (), u64>,
+	pallet_template::migration::v2::MigrateToV2<Runtime, StableInstance, StableBalance>,
+	pallet_template::migration::v3::MigrateToV3<Runtime>,
+	pallet_template::migration::v3::MigrateToV3<Runtime, StableInstance>,
+	pallet_template::migration::v4::MigrateToV4<Runtime>,
+	pallet_template::migration::v4::MigrateToV4<Runtime, StableInstance>,
+	pallet_template::migration::v5::MigrateToV5<Runtime>,
+	pallet_template::migration::v5::MigrateToV5<Runtime, StableInstance>,
+);
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -343,6 +572,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPallets,
+	Migrations,
 >;
 
 impl_runtime_apis! {
@@ -472,6 +702,186 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_template_runtime_api::Erc20Api<Block, AssetRegistry, AssetId, AssetBalance>
+		for Runtime
+	{
+		fn quote_exact_in(
+			registry: AssetRegistry,
+			asset_in: AssetId,
+			asset_out: AssetId,
+			amount_in: AssetBalance,
+		) -> Option<AssetBalance> {
+			match registry {
+				AssetRegistry::Community =>
+					TemplateModule::quote_exact_in(asset_in, asset_out, amount_in),
+				AssetRegistry::Stable => StableAssets::quote_exact_in(
+					asset_in,
+					asset_out,
+					amount_in.try_into().ok()?,
+				)
+				.map(Into::into),
+			}
+		}
+
+		fn quote_exact_out(
+			registry: AssetRegistry,
+			asset_in: AssetId,
+			asset_out: AssetId,
+			amount_out: AssetBalance,
+		) -> Option<AssetBalance> {
+			match registry {
+				AssetRegistry::Community =>
+					TemplateModule::quote_exact_out(asset_in, asset_out, amount_out),
+				AssetRegistry::Stable => StableAssets::quote_exact_out(
+					asset_in,
+					asset_out,
+					amount_out.try_into().ok()?,
+				)
+				.map(Into::into),
+			}
+		}
+
+		fn next_asset_id(registry: AssetRegistry) -> AssetId {
+			match registry {
+				AssetRegistry::Community => TemplateModule::next_asset_id(),
+				AssetRegistry::Stable => StableAssets::next_asset_id(),
+			}
+		}
+
+		fn call_abi(
+			registry: AssetRegistry,
+			asset: AssetId,
+			input: Vec<u8>,
+		) -> Result<Vec<u8>, DispatchError> {
+			match registry {
+				AssetRegistry::Community => TemplateModule::call_abi(asset, &input),
+				AssetRegistry::Stable => StableAssets::call_abi(asset, &input),
+			}
+		}
+	}
+
+	impl pallet_template_runtime_api::Erc20HoldersApi<
+		Block,
+		AssetRegistry,
+		AssetId,
+		AccountId,
+		AssetBalance,
+	> for Runtime
+	{
+		fn holders(
+			registry: AssetRegistry,
+			asset: AssetId,
+			start_after: Option<AccountId>,
+			limit: u32,
+		) -> Vec<(AccountId, AssetBalance)> {
+			match registry {
+				AssetRegistry::Community => TemplateModule::holders(asset, start_after, limit),
+				AssetRegistry::Stable => StableAssets::holders(asset, start_after, limit)
+					.into_iter()
+					.map(|(who, balance)| (who, balance.into()))
+					.collect(),
+			}
+		}
+
+		fn approvals_of(
+			registry: AssetRegistry,
+			asset: AssetId,
+			owner: AccountId,
+			start_after: Option<AccountId>,
+			limit: u32,
+		) -> Vec<(AccountId, AssetBalance)> {
+			match registry {
+				AssetRegistry::Community =>
+					TemplateModule::approvals_of(asset, owner, start_after, limit),
+				AssetRegistry::Stable =>
+					StableAssets::approvals_of(asset, owner, start_after, limit)
+						.into_iter()
+						.map(|(delegate, amount)| (delegate, amount.into()))
+						.collect(),
+			}
+		}
+
+		fn approvals_by_owner(
+			registry: AssetRegistry,
+			owner: AccountId,
+			start_after: Option<(AssetId, AccountId)>,
+			limit: u32,
+		) -> Vec<(AssetId, AccountId, AssetBalance)> {
+			match registry {
+				AssetRegistry::Community =>
+					TemplateModule::approvals_by_owner(owner, start_after, limit),
+				AssetRegistry::Stable => StableAssets::approvals_by_owner(owner, start_after, limit)
+					.into_iter()
+					.map(|(asset, delegate, amount)| (asset, delegate, amount.into()))
+					.collect(),
+			}
+		}
+
+		fn approvals_by_delegate(
+			registry: AssetRegistry,
+			delegate: AccountId,
+			start_after: Option<(AssetId, AccountId)>,
+			limit: u32,
+		) -> Vec<(AssetId, AccountId, AssetBalance)> {
+			match registry {
+				AssetRegistry::Community =>
+					TemplateModule::approvals_by_delegate(delegate, start_after, limit),
+				AssetRegistry::Stable =>
+					StableAssets::approvals_by_delegate(delegate, start_after, limit)
+						.into_iter()
+						.map(|(asset, owner, amount)| (asset, owner, amount.into()))
+						.collect(),
+			}
+		}
+
+		fn allowance(
+			registry: AssetRegistry,
+			asset: AssetId,
+			owner: AccountId,
+			delegate: AccountId,
+		) -> (AssetBalance, bool) {
+			match registry {
+				AssetRegistry::Community => (
+					TemplateModule::allowance(asset, &owner, &delegate),
+					TemplateModule::is_unlimited_approval(asset, &owner, &delegate),
+				),
+				AssetRegistry::Stable => (
+					StableAssets::allowance(asset, &owner, &delegate).into(),
+					StableAssets::is_unlimited_approval(asset, &owner, &delegate),
+				),
+			}
+		}
+	}
+
+	#[cfg(feature = "dev")]
+	impl pallet_template_runtime_api::Erc20DevApi<
+		Block,
+		(
+			pallet_template::IntegrityReportOf<Runtime>,
+			pallet_template::IntegrityReportOf<Runtime, StableInstance>,
+		),
+	> for Runtime
+	{
+		fn integrity_report() -> (
+			pallet_template::IntegrityReportOf<Runtime>,
+			pallet_template::IntegrityReportOf<Runtime, StableInstance>,
+		) {
+			(TemplateModule::integrity_report(), StableAssets::integrity_report())
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> Result<(Weight, Weight), sp_runtime::RuntimeString> {
+			let weight = Executive::try_runtime_upgrade()?;
+			Ok((weight, BlockWeights::get().max_block))
+		}
+
+		fn execute_block_no_check(block: Block) -> Weight {
+			Executive::execute_block_no_check(block)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -508,6 +918,8 @@ impl_runtime_apis! {
 			// add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
 			add_benchmark!(params, batches, pallet_template, TemplateModule);
+			add_benchmark!(params, batches, pallet_template_stable, StableAssets);
+			add_benchmark!(params, batches, pallet_collective, Council);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok((batches, storage_info))
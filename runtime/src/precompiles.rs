@@ -0,0 +1,459 @@
+//! The precompiles of the EVM: the standard ones, and one ERC20 contract for every asset of
+//! `pallet_template`.
+//!
+//! The ERC20 precompile of an asset lives at `asset_address`: four bytes naming the registry,
+//! `0xffffffff` for the community assets and `0xfffffffe` for the stable ones, and the asset id in
+//! the last four bytes. It implements `name`, `symbol`, `decimals`, `totalSupply`, `balanceOf`,
+//! `transfer`, `approve`, `allowance` and `transferFrom`, and logs `Transfer` and `Approval` as the
+//! pallet deposits the matching events. The calls are decoded and run by `pallet_template::abi`,
+//! as for `dispatch_abi`. EVM addresses hold assets as the account `account_of`
+//! maps them to, which is also the origin of the calls a precompile dispatches.
+//!
+//! Every asset's address holds `REVERT_STUB` as its code, so that contracts that check the code
+//! size of what they call, as Solidity does, accept the precompile. The code never runs: calls to
+//! the address are answered by the precompile.
+//!
+//! The precompiles only run as themselves: a `DELEGATECALL` or `CALLCODE` to one would have it act
+//! for the calling contract's address, so they are refused.
+//!
+//! On a `--dev` node, Alice can try a precompile with `evm.call`, from the address of the first 20
+//! bytes of her account, once its mapped account holds some of the asset.
+
+use crate::{AccountId, AssetId, AssetRegistry, Runtime, StableInstance};
+use frame_support::{
+	traits::Get,
+	weights::{constants::WEIGHT_PER_SECOND, Weight},
+};
+use pallet_evm::{
+	AddressMapping, Context, ExitError, ExitSucceed, GasWeightMapping, Log, PrecompileOutput,
+	PrecompileSet,
+};
+use pallet_evm_precompile_simple::{ECRecover, Identity, Ripemd160, Sha256};
+use pallet_template::{
+	abi::{AbiError, Erc20Call, TOPIC_APPROVAL, TOPIC_TRANSFER},
+	AssetsCallback,
+};
+use sp_core::{H160, H256, U256};
+use sp_runtime::{
+	traits::{Convert, Zero},
+	DispatchError,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// The weight of one unit of gas: 40 million gas per second of compute.
+pub const WEIGHT_PER_GAS: Weight = WEIGHT_PER_SECOND / 40_000_000;
+
+/// Converts between gas and weight at `WEIGHT_PER_GAS`.
+pub struct GasWeight;
+impl GasWeightMapping for GasWeight {
+	fn gas_to_weight(gas: u64) -> Weight {
+		gas.saturating_mul(WEIGHT_PER_GAS)
+	}
+
+	fn weight_to_gas(weight: Weight) -> u64 {
+		weight / WEIGHT_PER_GAS
+	}
+}
+
+const COMMUNITY_PREFIX: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+const STABLE_PREFIX: [u8; 4] = [0xff, 0xff, 0xff, 0xfe];
+
+/// The address of the ERC20 precompile of asset `id` of `registry`.
+pub fn asset_address(registry: AssetRegistry, id: AssetId) -> H160 {
+	let mut address = [0u8; 20];
+	address[..4].copy_from_slice(match registry {
+		AssetRegistry::Community => &COMMUNITY_PREFIX,
+		AssetRegistry::Stable => &STABLE_PREFIX,
+	});
+	address[16..].copy_from_slice(&id.to_be_bytes());
+	H160(address)
+}
+
+/// The code at the address of every asset: `PUSH1 0 PUSH1 0 REVERT`.
+pub const REVERT_STUB: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+/// Puts `REVERT_STUB` at the address of every asset of the registry `R` created, and removes it
+/// once the asset is destroyed.
+pub struct PrecompileCode<R>(PhantomData<R>);
+impl<R: Get<AssetRegistry>> AssetsCallback<AssetId, AccountId> for PrecompileCode<R> {
+	fn created(id: &AssetId, _: &AccountId) {
+		let address = asset_address(R::get(), *id);
+		pallet_evm::AccountCodes::<Runtime>::insert(address, REVERT_STUB.to_vec());
+	}
+
+	fn destroyed(id: &AssetId) {
+		pallet_evm::AccountCodes::<Runtime>::remove(asset_address(R::get(), *id));
+	}
+}
+
+/// The asset whose ERC20 precompile lives at `address`, if any.
+fn address_asset(address: H160) -> Option<(AssetRegistry, AssetId)> {
+	let bytes = address.as_bytes();
+	if bytes[4..16].iter().any(|b| *b != 0) {
+		return None
+	}
+	let registry = match [bytes[0], bytes[1], bytes[2], bytes[3]] {
+		COMMUNITY_PREFIX => AssetRegistry::Community,
+		STABLE_PREFIX => AssetRegistry::Stable,
+		_ => return None,
+	};
+	Some((registry, AssetId::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]])))
+}
+
+/// The account that holds the assets of the EVM address `address`.
+pub fn account_of(address: H160) -> AccountId {
+	<Runtime as pallet_evm::Config>::AddressMapping::into_account_id(address)
+}
+
+/// The standard precompiles at `0x01` to `0x04`, and the ERC20 precompiles of the assets.
+pub struct Precompiles;
+impl PrecompileSet for Precompiles {
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> Option<Result<PrecompileOutput, ExitError>> {
+		match address_asset(address) {
+			Some((AssetRegistry::Community, id)) =>
+				Some(Erc20::<()>::execute(address, id, input, target_gas, context)),
+			Some((AssetRegistry::Stable, id)) =>
+				Some(Erc20::<StableInstance>::execute(address, id, input, target_gas, context)),
+			None => <(ECRecover, Sha256, Ripemd160, Identity) as PrecompileSet>::execute(
+				address, input, target_gas, context,
+			),
+		}
+	}
+}
+
+type Assets<I> = pallet_template::Pallet<Runtime, I>;
+
+/// The ERC20 precompiles of the assets of instance `I`.
+struct Erc20<I>(sp_std::marker::PhantomData<I>);
+
+impl<I: 'static> Erc20<I>
+where
+	Runtime: pallet_template::Config<I, AssetId = AssetId>,
+{
+	fn execute(
+		address: H160,
+		id: AssetId,
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> Result<PrecompileOutput, ExitError> {
+		if context.address != address {
+			return Err(ExitError::Other(
+				"ERC20 precompiles cannot be called by DELEGATECALL or CALLCODE".into(),
+			))
+		}
+		if !context.apparent_value.is_zero() {
+			return Err(ExitError::Other("ERC20 functions are not payable".into()))
+		}
+		let call = Erc20Call::decode(input).map_err(|e| {
+			ExitError::Other(match e {
+				AbiError::UnsupportedSelector => "unknown ERC20 function".into(),
+				AbiError::InvalidInput => "invalid ERC20 arguments".into(),
+			})
+		})?;
+		let caller = context.caller;
+
+		let mut output = Output::new(target_gas);
+		output.charge(Assets::<I>::abi_weight(input))?;
+		if call.is_view() {
+			output.output = Assets::<I>::query_erc20(id, call).map_err(exit_error)?;
+			return Ok(output.finish())
+		}
+		output.output =
+			Assets::<I>::dispatch_erc20(account_of(caller), id, call).map_err(exit_error)?;
+		match call {
+			Erc20Call::Transfer(to, amount) =>
+				output.log(address, TOPIC_TRANSFER, caller, to, amount),
+			Erc20Call::Approve(spender, amount) =>
+				output.log(address, TOPIC_APPROVAL, caller, spender, amount),
+			Erc20Call::TransferFrom(from, to, amount) =>
+				output.log(address, TOPIC_TRANSFER, from, to, amount),
+			_ => {},
+		}
+		Ok(output.finish())
+	}
+}
+
+/// Maps EVM addresses to accounts with `account_of`, for the ABI calls of `pallet_template`.
+pub struct AddressToAccount;
+impl Convert<H160, AccountId> for AddressToAccount {
+	fn convert(address: H160) -> AccountId {
+		account_of(address)
+	}
+}
+
+fn exit_error(error: DispatchError) -> ExitError {
+	ExitError::Other(match error {
+		DispatchError::Module { message: Some(message), .. } => message.into(),
+		_ => "ERC20 call failed".into(),
+	})
+}
+
+/// The ABI encoded output of a call, with the gas and logs it took.
+struct Output {
+	target_gas: Option<u64>,
+	cost: u64,
+	output: Vec<u8>,
+	logs: Vec<Log>,
+}
+
+impl Output {
+	fn new(target_gas: Option<u64>) -> Self {
+		Output { target_gas, cost: 0, output: Vec::new(), logs: Vec::new() }
+	}
+
+	/// Charge the gas of `weight`, failing if it exceeds the gas left.
+	fn charge(&mut self, weight: Weight) -> Result<(), ExitError> {
+		self.cost = self.cost.saturating_add(GasWeight::weight_to_gas(weight));
+		match self.target_gas {
+			Some(target_gas) if self.cost > target_gas => Err(ExitError::OutOfGas),
+			_ => Ok(()),
+		}
+	}
+
+	/// Log the `Transfer` or `Approval` event `topic` of the precompile at `address`.
+	fn log(&mut self, address: H160, topic: [u8; 32], from: H160, to: H160, amount: U256) {
+		let mut data = [0u8; 32];
+		amount.to_big_endian(&mut data);
+		self.logs.push(Log {
+			address,
+			topics: vec![H256(topic), from.into(), to.into()],
+			data: data.to_vec(),
+		});
+	}
+
+	fn finish(self) -> PrecompileOutput {
+		PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: self.cost,
+			output: self.output,
+			logs: self.logs,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		AssetBalance, BalancesConfig, BuildStorage, GenesisConfig, Origin, StableAssets,
+		StableAssetsConfig, System, TemplateModule, TemplateModuleConfig, DOLLARS,
+	};
+	use frame_support::assert_ok;
+	use pallet_template::abi::{
+		encode_bool, encode_uint, SELECTOR_ALLOWANCE, SELECTOR_APPROVE, SELECTOR_BALANCE_OF,
+		SELECTOR_DECIMALS, SELECTOR_TOTAL_SUPPLY, SELECTOR_TRANSFER, SELECTOR_TRANSFER_FROM,
+	};
+	use sp_runtime::MultiAddress;
+
+	const ALICE: H160 = H160::repeat_byte(0xaa);
+	const BOB: H160 = H160::repeat_byte(0xbb);
+	const CAROL: H160 = H160::repeat_byte(0xcc);
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let storage = GenesisConfig {
+			balances: BalancesConfig { balances: vec![(account_of(ALICE), 10 * DOLLARS)] },
+			template_module: TemplateModuleConfig {
+				assets: vec![(7, account_of(ALICE), true, 1u32.into())],
+				metadata: vec![],
+				accounts: vec![(7, account_of(ALICE), 1_000u32.into())],
+			},
+			stable_assets: StableAssetsConfig {
+				assets: vec![(7, account_of(ALICE), true, 1)],
+				metadata: vec![],
+				accounts: vec![],
+			},
+			..Default::default()
+		}
+		.build_storage()
+		.unwrap();
+		let mut ext = sp_io::TestExternalities::new(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn address(address: H160) -> Vec<u8> {
+		H256::from(address).as_bytes().to_vec()
+	}
+
+	fn input(selector: [u8; 4], args: &[Vec<u8>]) -> Vec<u8> {
+		let mut input = selector.to_vec();
+		args.iter().for_each(|arg| input.extend_from_slice(arg));
+		input
+	}
+
+	/// Call the ERC20 precompile of community asset 7 from `caller`.
+	fn call(caller: H160, input: Vec<u8>) -> Result<PrecompileOutput, ExitError> {
+		call_with_value(caller, input, U256::zero())
+	}
+
+	fn call_with_value(
+		caller: H160,
+		input: Vec<u8>,
+		apparent_value: U256,
+	) -> Result<PrecompileOutput, ExitError> {
+		let address = asset_address(AssetRegistry::Community, 7);
+		call_in_context(Context { address, caller, apparent_value }, input)
+	}
+
+	/// Call the ERC20 precompile of community asset 7 in `context`.
+	fn call_in_context(context: Context, input: Vec<u8>) -> Result<PrecompileOutput, ExitError> {
+		let address = asset_address(AssetRegistry::Community, 7);
+		Precompiles::execute(address, &input, None, &context).expect("asset 7 has a precompile")
+	}
+
+	fn balance_of(who: H160) -> Vec<u8> {
+		call(BOB, input(SELECTOR_BALANCE_OF, &[address(who)])).unwrap().output
+	}
+
+	fn log(topic: [u8; 32], from: H160, to: H160, amount: u32) -> Log {
+		Log {
+			address: asset_address(AssetRegistry::Community, 7),
+			topics: vec![H256(topic), from.into(), to.into()],
+			data: encode_uint(amount.into()),
+		}
+	}
+
+	#[test]
+	fn asset_addresses_round_trip() {
+		let community = asset_address(AssetRegistry::Community, 0x0102_0304);
+		let mut expected = [0u8; 20];
+		expected[..4].copy_from_slice(&[0xff; 4]);
+		expected[16..].copy_from_slice(&[1, 2, 3, 4]);
+		assert_eq!(community, H160(expected));
+		assert_eq!(address_asset(community), Some((AssetRegistry::Community, 0x0102_0304)));
+		let stable = asset_address(AssetRegistry::Stable, 7);
+		assert_eq!(address_asset(stable), Some((AssetRegistry::Stable, 7)));
+		assert_eq!(address_asset(H160::from_low_u64_be(1)), None);
+		let mut padded = community;
+		padded.0[10] = 1;
+		assert_eq!(address_asset(padded), None);
+	}
+
+	#[test]
+	fn assets_have_revert_code_at_their_address() {
+		new_test_ext().execute_with(|| {
+			let stub = REVERT_STUB.to_vec();
+			let community = asset_address(AssetRegistry::Community, 7);
+			let stable = asset_address(AssetRegistry::Stable, 7);
+			assert_eq!(pallet_evm::AccountCodes::<Runtime>::get(community), stub);
+			assert_eq!(pallet_evm::AccountCodes::<Runtime>::get(stable), stub);
+
+			let created = asset_address(AssetRegistry::Stable, 8);
+			assert!(!pallet_evm::AccountCodes::<Runtime>::contains_key(created));
+			let owner = MultiAddress::Id(account_of(ALICE));
+			assert_ok!(StableAssets::force_create(Origin::root(), 8, owner, true, 1));
+			assert_eq!(pallet_evm::AccountCodes::<Runtime>::get(created), stub);
+		});
+	}
+
+	#[test]
+	fn view_selectors_should_work() {
+		new_test_ext().execute_with(|| {
+			let total_supply = call(BOB, input(SELECTOR_TOTAL_SUPPLY, &[])).unwrap();
+			assert_eq!(total_supply.output, encode_uint(1_000u32.into()));
+			assert!(total_supply.logs.is_empty());
+			assert_eq!(balance_of(ALICE), encode_uint(1_000u32.into()));
+			assert_eq!(balance_of(BOB), encode_uint(U256::zero()));
+			let decimals = call(BOB, input(SELECTOR_DECIMALS, &[])).unwrap();
+			assert_eq!(decimals.output, encode_uint(U256::zero()));
+
+			let unknown = call(BOB, input([0xde, 0xad, 0xbe, 0xef], &[]));
+			assert_eq!(unknown.err(), Some(ExitError::Other("unknown ERC20 function".into())));
+			let truncated = call(BOB, input(SELECTOR_BALANCE_OF, &[vec![0; 31]]));
+			assert_eq!(truncated.err(), Some(ExitError::Other("invalid ERC20 arguments".into())));
+		});
+	}
+
+	#[test]
+	fn transfer_should_move_assets_and_log() {
+		new_test_ext().execute_with(|| {
+			let amount = encode_uint(300u32.into());
+			let output = call(ALICE, input(SELECTOR_TRANSFER, &[address(BOB), amount])).unwrap();
+			assert_eq!(output.output, encode_bool(true));
+			assert_eq!(output.logs, vec![log(TOPIC_TRANSFER, ALICE, BOB, 300)]);
+			assert!(output.cost > 0);
+			assert_eq!(balance_of(ALICE), encode_uint(700u32.into()));
+			assert_eq!(balance_of(BOB), encode_uint(300u32.into()));
+			assert_eq!(TemplateModule::balance(7, account_of(BOB)), AssetBalance::from(300u32));
+
+			let amount = encode_uint(5_000u32.into());
+			assert!(call(ALICE, input(SELECTOR_TRANSFER, &[address(BOB), amount])).is_err());
+		});
+	}
+
+	#[test]
+	fn transfer_from_should_spend_the_allowance() {
+		new_test_ext().execute_with(|| {
+			let allowance = || {
+				let args = [address(ALICE), address(BOB)];
+				call(CAROL, input(SELECTOR_ALLOWANCE, &args)).unwrap().output
+			};
+			let amount = encode_uint(500u32.into());
+			let output = call(ALICE, input(SELECTOR_APPROVE, &[address(BOB), amount])).unwrap();
+			assert_eq!(output.output, encode_bool(true));
+			assert_eq!(output.logs, vec![log(TOPIC_APPROVAL, ALICE, BOB, 500)]);
+			assert_eq!(allowance(), encode_uint(500u32.into()));
+
+			let args = [address(ALICE), address(CAROL), encode_uint(300u32.into())];
+			let output = call(BOB, input(SELECTOR_TRANSFER_FROM, &args)).unwrap();
+			assert_eq!(output.output, encode_bool(true));
+			assert_eq!(output.logs, vec![log(TOPIC_TRANSFER, ALICE, CAROL, 300)]);
+			assert_eq!(allowance(), encode_uint(200u32.into()));
+			assert_eq!(balance_of(CAROL), encode_uint(300u32.into()));
+
+			// More than the allowance left.
+			assert!(call(BOB, input(SELECTOR_TRANSFER_FROM, &args)).is_err());
+			assert_eq!(allowance(), encode_uint(200u32.into()));
+
+			// An approval of the maximum is unlimited, and replaces the one before.
+			let amount = encode_uint(U256::MAX);
+			assert!(call(ALICE, input(SELECTOR_APPROVE, &[address(BOB), amount])).is_ok());
+			assert!(call(BOB, input(SELECTOR_TRANSFER_FROM, &args)).is_ok());
+			assert_eq!(allowance(), encode_uint(U256::MAX));
+			assert_eq!(balance_of(CAROL), encode_uint(600u32.into()));
+		});
+	}
+
+	#[test]
+	fn calls_with_value_are_rejected() {
+		new_test_ext().execute_with(|| {
+			let amount = encode_uint(300u32.into());
+			let transfer = input(SELECTOR_TRANSFER, &[address(BOB), amount]);
+			let output = call_with_value(ALICE, transfer, 1u32.into());
+			let not_payable = ExitError::Other("ERC20 functions are not payable".into());
+			assert_eq!(output.err(), Some(not_payable.clone()));
+			assert_eq!(balance_of(BOB), encode_uint(U256::zero()));
+			let total_supply = input(SELECTOR_TOTAL_SUPPLY, &[]);
+			assert_eq!(call_with_value(BOB, total_supply, 1u32.into()).err(), Some(not_payable));
+		});
+	}
+
+	#[test]
+	fn delegated_calls_are_rejected() {
+		new_test_ext().execute_with(|| {
+			const CONTRACT: H160 = H160::repeat_byte(0xdd);
+			let message = "ERC20 precompiles cannot be called by DELEGATECALL or CALLCODE";
+			let refused = ExitError::Other(message.into());
+			let amount = encode_uint(300u32.into());
+			let transfer = input(SELECTOR_TRANSFER, &[address(BOB), amount]);
+			let apparent_value = U256::zero();
+
+			// A DELEGATECALL from a contract ALICE called keeps ALICE as the caller.
+			let delegatecall = Context { address: CONTRACT, caller: ALICE, apparent_value };
+			let output = call_in_context(delegatecall, transfer.clone());
+			assert_eq!(output.err(), Some(refused.clone()));
+			// A CALLCODE makes the contract the caller, at its own address.
+			let callcode = Context { address: CONTRACT, caller: CONTRACT, apparent_value };
+			assert_eq!(call_in_context(callcode, transfer).err(), Some(refused));
+
+			assert_eq!(balance_of(ALICE), encode_uint(1_000u32.into()));
+			assert_eq!(balance_of(BOB), encode_uint(U256::zero()));
+		});
+	}
+}